@@ -0,0 +1,356 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use super::compact::{CompactGraph, Symbol};
+use super::graph_items::node::Node;
+use super::Graph;
+
+/// The two sides of a bipartite graph, each in node order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bipartition {
+    pub left: Vec<String>,
+    pub right: Vec<String>,
+}
+
+/// Proof that a graph is not bipartite: a cycle of odd length, listed
+/// without repeating the first node at the end.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OddCycle {
+    pub nodes: Vec<String>,
+}
+
+impl fmt::Display for OddCycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "graph is not bipartite: odd cycle {} -- {}",
+            self.nodes.join(" -- "),
+            self.nodes[0]
+        )
+    }
+}
+
+impl Error for OddCycle {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColoringStrategy {
+    /// Colours nodes in order, each with the lowest colour its neighbours leave free.
+    Greedy,
+    /// Always colours the node with the most differently coloured neighbours next.
+    Dsatur,
+}
+
+impl Graph {
+    /// Splits the nodes into two sides with every edge running between them,
+    /// ignoring edge direction.
+    pub fn bipartition(&self) -> Result<Bipartition, OddCycle> {
+        let compact = self.to_compact();
+        let sides = two_colour(&compact)?;
+        let side = |wanted: bool| {
+            compact
+                .nodes()
+                .filter(|node| sides[node.index()] == wanted)
+                .map(|node| compact.id(node).to_string())
+                .collect()
+        };
+
+        Ok(Bipartition {
+            left: side(false),
+            right: side(true),
+        })
+    }
+
+    pub fn is_bipartite(&self) -> bool {
+        self.bipartition().is_ok()
+    }
+
+    /// A maximum matching (Hopcroft–Karp) as `(left, right)` pairs of the
+    /// bipartition, ordered by the left node.
+    pub fn maximum_matching(&self) -> Result<Vec<(String, String)>, OddCycle> {
+        let compact = self.to_compact();
+        let sides = two_colour(&compact)?;
+        let adjacency: Vec<Vec<Symbol>> = compact
+            .nodes()
+            .map(|node| neighbours(&compact, node))
+            .collect();
+
+        let mut matcher = HopcroftKarp {
+            adjacency: &adjacency,
+            left: compact
+                .nodes()
+                .filter(|node| !sides[node.index()])
+                .collect(),
+            mate: vec![None; compact.node_count()],
+            layer: vec![None; compact.node_count()],
+        };
+        matcher.run();
+
+        Ok(matcher
+            .left
+            .iter()
+            .filter_map(|&node| {
+                let mate = matcher.mate[node.index()]?;
+                Some((compact.id(node).to_string(), compact.id(mate).to_string()))
+            })
+            .collect())
+    }
+
+    /// A proper vertex colouring, numbering colours from 0. Self-loops are
+    /// ignored since no colouring could satisfy them.
+    pub fn coloring(&self, strategy: ColoringStrategy) -> HashMap<String, usize> {
+        let compact = self.to_compact();
+        let colours = match strategy {
+            ColoringStrategy::Greedy => greedy(&compact),
+            ColoringStrategy::Dsatur => dsatur(&compact),
+        };
+
+        compact
+            .nodes()
+            .map(|node| (compact.id(node).to_string(), colours[node.index()]))
+            .collect()
+    }
+
+    /// Colours the graph and stores each node's colour in `attr`, declaring
+    /// nodes that were only edge endpoints. Colours are written as numbers,
+    /// or taken from `palette` (cycling) if it is not empty. Returns the
+    /// number of colours used.
+    pub fn color_nodes(
+        &mut self,
+        strategy: ColoringStrategy,
+        attr: &str,
+        palette: &[&str],
+    ) -> usize {
+        let colours = self.coloring(strategy);
+
+        for id in self
+            .node_ids()
+            .into_iter()
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+        {
+            if self.node(&id).is_none() {
+                let node = self.adopt_node(Node::new(&id));
+                self.nodes.push(node);
+            }
+            let colour = colours[&id];
+            let value = match palette {
+                [] => colour.to_string(),
+                _ => palette[colour % palette.len()].to_string(),
+            };
+            let node = self.nodes.iter_mut().find(|node| node.id == id).unwrap();
+            node.attrs.insert(attr.to_string(), value);
+        }
+
+        colours.values().max().map_or(0, |&max| max + 1)
+    }
+}
+
+/// Distinct neighbours regardless of edge direction, self-loops included.
+fn neighbours(compact: &CompactGraph, node: Symbol) -> Vec<Symbol> {
+    let mut seen = HashSet::new();
+    compact
+        .successors(node)
+        .iter()
+        .chain(compact.predecessors(node))
+        .copied()
+        .filter(|&other| seen.insert(other))
+        .collect()
+}
+
+/// Sides indexed by symbol, or the odd cycle that rules a 2-colouring out.
+fn two_colour(compact: &CompactGraph) -> Result<Vec<bool>, OddCycle> {
+    let mut side: Vec<Option<bool>> = vec![None; compact.node_count()];
+    let mut parent: Vec<Option<Symbol>> = vec![None; compact.node_count()];
+
+    for root in compact.nodes() {
+        if side[root.index()].is_some() {
+            continue;
+        }
+        side[root.index()] = Some(false);
+        let mut queue = VecDeque::from([root]);
+
+        while let Some(node) = queue.pop_front() {
+            let colour = side[node.index()].unwrap();
+            for next in neighbours(compact, node) {
+                match side[next.index()] {
+                    None => {
+                        side[next.index()] = Some(!colour);
+                        parent[next.index()] = Some(node);
+                        queue.push_back(next);
+                    }
+                    Some(other) if other == colour => {
+                        return Err(odd_cycle(compact, &parent, node, next));
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+
+    Ok(side.into_iter().map(Option::unwrap).collect())
+}
+
+/// Joins the BFS tree paths from `a` and `b` (same side, adjacent) up to
+/// their common ancestor, which closes an odd cycle.
+fn odd_cycle(compact: &CompactGraph, parent: &[Option<Symbol>], a: Symbol, b: Symbol) -> OddCycle {
+    let ancestors = |mut node: Symbol| {
+        let mut path = vec![node];
+        while let Some(up) = parent[node.index()] {
+            path.push(up);
+            node = up;
+        }
+        path
+    };
+    let mut from_a = ancestors(a);
+    let mut from_b = ancestors(b);
+
+    // Both paths end at the BFS root; strip the shared tail but keep the
+    // lowest common ancestor once, at the start of the cycle.
+    while from_a.len() > 1
+        && from_b.len() > 1
+        && from_a[from_a.len() - 2] == from_b[from_b.len() - 2]
+    {
+        from_a.pop();
+        from_b.pop();
+    }
+    from_b.pop();
+
+    let nodes = from_a
+        .into_iter()
+        .rev()
+        .chain(from_b)
+        .map(|node| compact.id(node).to_string())
+        .collect();
+    OddCycle { nodes }
+}
+
+struct HopcroftKarp<'a> {
+    adjacency: &'a [Vec<Symbol>],
+    left: Vec<Symbol>,
+    mate: Vec<Option<Symbol>>,
+    /// BFS layer of each left node in the current phase.
+    layer: Vec<Option<usize>>,
+}
+
+impl HopcroftKarp<'_> {
+    fn run(&mut self) {
+        while self.layer_free_paths() {
+            for index in 0..self.left.len() {
+                let node = self.left[index];
+                if self.mate[node.index()].is_none() {
+                    self.augment(node);
+                }
+            }
+        }
+    }
+
+    /// Layers the left side by alternating path length from the free left
+    /// nodes; true if some free right node can be reached.
+    fn layer_free_paths(&mut self) -> bool {
+        let mut queue = VecDeque::new();
+        for &node in &self.left {
+            if self.mate[node.index()].is_none() {
+                self.layer[node.index()] = Some(0);
+                queue.push_back(node);
+            } else {
+                self.layer[node.index()] = None;
+            }
+        }
+
+        let mut found = false;
+        while let Some(node) = queue.pop_front() {
+            let depth = self.layer[node.index()].unwrap();
+            for &right in &self.adjacency[node.index()] {
+                match self.mate[right.index()] {
+                    None => found = true,
+                    Some(next) if self.layer[next.index()].is_none() => {
+                        self.layer[next.index()] = Some(depth + 1);
+                        queue.push_back(next);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        found
+    }
+
+    fn augment(&mut self, node: Symbol) -> bool {
+        let depth = self.layer[node.index()];
+        for &right in self.adjacency[node.index()].iter() {
+            let extends = match self.mate[right.index()] {
+                None => true,
+                Some(next) => {
+                    self.layer[next.index()] == depth.map(|depth| depth + 1) && self.augment(next)
+                }
+            };
+            if extends {
+                self.mate[node.index()] = Some(right);
+                self.mate[right.index()] = Some(node);
+                return true;
+            }
+        }
+        // Dead end for this phase.
+        self.layer[node.index()] = None;
+        false
+    }
+}
+
+fn adjacency_without_loops(compact: &CompactGraph) -> Vec<Vec<Symbol>> {
+    compact
+        .nodes()
+        .map(|node| {
+            neighbours(compact, node)
+                .into_iter()
+                .filter(|&other| other != node)
+                .collect()
+        })
+        .collect()
+}
+
+fn lowest_free(adjacency: &[Symbol], colours: &[Option<usize>]) -> usize {
+    let used: HashSet<usize> = adjacency
+        .iter()
+        .filter_map(|other| colours[other.index()])
+        .collect();
+    (0..).find(|colour| !used.contains(colour)).unwrap()
+}
+
+fn greedy(compact: &CompactGraph) -> Vec<usize> {
+    let adjacency = adjacency_without_loops(compact);
+    let mut colours = vec![None; compact.node_count()];
+    for node in compact.nodes() {
+        colours[node.index()] = Some(lowest_free(&adjacency[node.index()], &colours));
+    }
+    colours.into_iter().map(Option::unwrap).collect()
+}
+
+fn dsatur(compact: &CompactGraph) -> Vec<usize> {
+    let adjacency = adjacency_without_loops(compact);
+    let mut colours: Vec<Option<usize>> = vec![None; compact.node_count()];
+
+    for _ in 0..compact.node_count() {
+        let saturation = |node: Symbol| {
+            adjacency[node.index()]
+                .iter()
+                .filter_map(|other| colours[other.index()])
+                .collect::<HashSet<_>>()
+                .len()
+        };
+        // Most saturated, then highest degree, then earliest in node order.
+        let next = compact
+            .nodes()
+            .filter(|node| colours[node.index()].is_none())
+            .max_by_key(|&node| {
+                (
+                    saturation(node),
+                    adjacency[node.index()].len(),
+                    std::cmp::Reverse(node),
+                )
+            })
+            .unwrap();
+        colours[next.index()] = Some(lowest_free(&adjacency[next.index()], &colours));
+    }
+
+    colours.into_iter().map(Option::unwrap).collect()
+}
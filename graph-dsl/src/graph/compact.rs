@@ -0,0 +1,257 @@
+use std::collections::{HashMap, HashSet};
+
+use super::graph_items::id::Id;
+use super::Graph;
+
+/// An interned node id. Only meaningful together with the table that issued it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Dense numbers for node ids. The table holds one shared copy of each
+/// name, which comes straight from the graph when built by `intern_id`.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolTable {
+    names: Vec<Id>,
+    lookup: HashMap<Id, Symbol>,
+}
+
+impl SymbolTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        match self.lookup.get(name) {
+            Some(&symbol) => symbol,
+            None => self.push(Id::from(name)),
+        }
+    }
+
+    /// Like `intern`, sharing the name with `id` rather than copying it.
+    pub fn intern_id(&mut self, id: &Id) -> Symbol {
+        match self.lookup.get(id.as_str()) {
+            Some(&symbol) => symbol,
+            None => self.push(id.clone()),
+        }
+    }
+
+    fn push(&mut self, id: Id) -> Symbol {
+        let symbol = Symbol(self.names.len() as u32);
+        self.names.push(id.clone());
+        self.lookup.insert(id, symbol);
+        symbol
+    }
+
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.lookup.get(name).copied()
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Symbols in the order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (Symbol(index as u32), id.as_str()))
+    }
+}
+
+/// Read-only adjacency in compressed sparse row form: the neighbours of node
+/// `n` are `targets[offsets[n]..offsets[n + 1]]`. Undirected edges are stored
+/// in both directions, self-loops once.
+#[derive(Clone, Debug)]
+struct Csr {
+    offsets: Vec<usize>,
+    targets: Vec<Symbol>,
+    /// Position of the originating edge in `Graph::edges`, parallel to `targets`.
+    edges: Vec<usize>,
+}
+
+impl Csr {
+    fn build(node_count: usize, arcs: &[(Symbol, Symbol, usize)]) -> Self {
+        let mut offsets = vec![0; node_count + 1];
+        for &(from, _, _) in arcs {
+            offsets[from.index() + 1] += 1;
+        }
+        for index in 0..node_count {
+            offsets[index + 1] += offsets[index];
+        }
+
+        let mut next = offsets.clone();
+        let mut targets = vec![Symbol(0); arcs.len()];
+        let mut edges = vec![0; arcs.len()];
+        for &(from, to, edge) in arcs {
+            let slot = &mut next[from.index()];
+            targets[*slot] = to;
+            edges[*slot] = edge;
+            *slot += 1;
+        }
+
+        Self {
+            offsets,
+            targets,
+            edges,
+        }
+    }
+
+    fn range(&self, node: Symbol) -> std::ops::Range<usize> {
+        self.offsets[node.index()]..self.offsets[node.index() + 1]
+    }
+}
+
+/// A compact, read-only CSR snapshot of a [`Graph`] for analysis. Node ids
+/// are numbered in the order of [`Graph::node_ids`] by a [`SymbolTable`]
+/// that shares the graph's interned names: symbols from two snapshots
+/// cannot be mixed.
+#[derive(Clone, Debug)]
+pub struct CompactGraph {
+    pub symbols: SymbolTable,
+    directed: bool,
+    edge_count: usize,
+    outgoing: Csr,
+    /// Only built for directed graphs; undirected ones reuse `outgoing`.
+    incoming: Option<Csr>,
+    /// Self-loops per node of an undirected graph, which `outgoing` holds
+    /// once but which count twice towards the degree. Empty when directed.
+    loops: Vec<usize>,
+}
+
+impl CompactGraph {
+    pub fn from_graph(graph: &Graph) -> Self {
+        let mut symbols = SymbolTable::new();
+        let endpoints = graph.edges.iter().flat_map(|edge| [&edge.from, &edge.to]);
+        for id in graph.nodes.iter().map(|node| &node.id).chain(endpoints) {
+            symbols.intern_id(id);
+        }
+
+        let mut arcs = Vec::with_capacity(graph.edges.len() * 2);
+        let mut loops = Vec::new();
+        for (index, edge) in graph.edges.iter().enumerate() {
+            let from = symbols.intern_id(&edge.from);
+            let to = symbols.intern_id(&edge.to);
+            arcs.push((from, to, index));
+            if graph.directed {
+                continue;
+            }
+            if from != to {
+                arcs.push((to, from, index));
+            } else {
+                if loops.len() <= from.index() {
+                    loops.resize(from.index() + 1, 0);
+                }
+                loops[from.index()] += 1;
+            }
+        }
+
+        let outgoing = Csr::build(symbols.len(), &arcs);
+        let incoming = graph.directed.then(|| {
+            let reversed: Vec<_> = arcs
+                .iter()
+                .map(|&(from, to, edge)| (to, from, edge))
+                .collect();
+            Csr::build(symbols.len(), &reversed)
+        });
+
+        Self {
+            symbols,
+            directed: graph.directed,
+            edge_count: graph.edges.len(),
+            outgoing,
+            incoming,
+            loops,
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = Symbol> {
+        (0..self.node_count() as u32).map(Symbol)
+    }
+
+    pub fn symbol(&self, id: &str) -> Option<Symbol> {
+        self.symbols.get(id)
+    }
+
+    pub fn id(&self, node: Symbol) -> &str {
+        self.symbols.resolve(node)
+    }
+
+    /// Nodes reachable over one edge, once per parallel edge.
+    pub fn successors(&self, node: Symbol) -> &[Symbol] {
+        &self.outgoing.targets[self.outgoing.range(node)]
+    }
+
+    /// Nodes with an edge into `node`; the same as `successors` when undirected.
+    pub fn predecessors(&self, node: Symbol) -> &[Symbol] {
+        let csr = self.incoming.as_ref().unwrap_or(&self.outgoing);
+        &csr.targets[csr.range(node)]
+    }
+
+    /// Positions in `Graph::edges` of the edges behind `successors`, in the same order.
+    pub fn successor_edges(&self, node: Symbol) -> &[usize] {
+        &self.outgoing.edges[self.outgoing.range(node)]
+    }
+
+    /// Positions in `Graph::edges` of the edges behind `predecessors`, in the same order.
+    pub fn predecessor_edges(&self, node: Symbol) -> &[usize] {
+        let csr = self.incoming.as_ref().unwrap_or(&self.outgoing);
+        &csr.edges[csr.range(node)]
+    }
+
+    /// Edges leaving `node`. In an undirected graph, the degree of `node`,
+    /// with a self-loop counted twice although `successors` lists it once.
+    pub fn out_degree(&self, node: Symbol) -> usize {
+        self.outgoing.range(node).len() + self.loops.get(node.index()).unwrap_or(&0)
+    }
+
+    /// Edges entering `node`; the same as `out_degree` when undirected.
+    pub fn in_degree(&self, node: Symbol) -> usize {
+        match &self.incoming {
+            Some(csr) => csr.range(node).len(),
+            None => self.out_degree(node),
+        }
+    }
+}
+
+/// `nodes` without repeats, such as the neighbours behind parallel edges.
+pub(crate) fn unique(nodes: &[Symbol]) -> Vec<Symbol> {
+    let mut seen = HashSet::new();
+    nodes
+        .iter()
+        .copied()
+        .filter(|node| seen.insert(*node))
+        .collect()
+}
+
+impl Graph {
+    pub fn to_compact(&self) -> CompactGraph {
+        CompactGraph::from_graph(self)
+    }
+}
@@ -0,0 +1,516 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use super::graph_items::attr::AttrMap;
+use super::graph_items::{edge::Edge, node::Node};
+use super::Graph;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttrChange {
+    Added {
+        key: String,
+        value: String,
+    },
+    Removed {
+        key: String,
+        value: String,
+    },
+    Changed {
+        key: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl AttrChange {
+    pub fn key(&self) -> &str {
+        match self {
+            AttrChange::Added { key, .. }
+            | AttrChange::Removed { key, .. }
+            | AttrChange::Changed { key, .. } => key,
+        }
+    }
+
+    /// The value the attribute has after the change, `None` if it was removed.
+    pub fn new_value(&self) -> Option<&str> {
+        match self {
+            AttrChange::Added { value, .. } => Some(value),
+            AttrChange::Changed { new, .. } => Some(new),
+            AttrChange::Removed { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for AttrChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttrChange::Added { key, value } => write!(f, "+{key}={value:?}"),
+            AttrChange::Removed { key, value } => write!(f, "-{key}={value:?}"),
+            AttrChange::Changed { key, old, new } => write!(f, "{key}: {old:?} -> {new:?}"),
+        }
+    }
+}
+
+/// Edges carry no identity of their own, so they are keyed by their endpoints
+/// plus their position among the edges sharing those endpoints. Endpoints of
+/// undirected edges are kept in sorted order, so `a -- b` and `b -- a` match.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeKey {
+    pub from: String,
+    pub to: String,
+    /// Position among the unkeyed edges between the same nodes.
+    pub index: usize,
+    pub directed: bool,
+    /// The edge's `Edge::key`. Keyed edges are matched by it alone.
+    pub key: Option<String>,
+}
+
+impl fmt::Display for EdgeKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.from, edge_op(self.directed), self.to)?;
+        if let Some(key) = &self.key {
+            write!(f, " [key {key}]")?;
+        } else if self.index > 0 {
+            write!(f, " (#{})", self.index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphDiff {
+    pub added_nodes: Vec<Node>,
+    pub removed_nodes: Vec<Node>,
+    pub changed_nodes: Vec<(String, Vec<AttrChange>)>,
+    pub added_edges: Vec<Edge>,
+    pub removed_edges: Vec<(EdgeKey, Edge)>,
+    pub changed_edges: Vec<(EdgeKey, Vec<AttrChange>)>,
+    pub graph_attrs: Vec<AttrChange>,
+    /// Set to the target's value when the two graphs differ in directedness.
+    pub directed: Option<bool>,
+    /// Whether edges were matched, and are shown, as directed: the target's
+    /// directedness.
+    pub edges_directed: bool,
+    pub strict: Option<bool>,
+}
+
+pub fn diff(source: &Graph, target: &Graph) -> GraphDiff {
+    let mut result = GraphDiff {
+        graph_attrs: diff_attrs(&source.attrs, &target.attrs),
+        directed: (source.directed != target.directed).then_some(target.directed),
+        strict: (source.strict != target.strict).then_some(target.strict),
+        edges_directed: target.directed,
+        ..GraphDiff::default()
+    };
+
+    let source_nodes = first_nodes_by_id(&source.nodes);
+    let target_nodes = first_nodes_by_id(&target.nodes);
+
+    for node in unique_nodes(&source.nodes) {
+        match target_nodes.get(node.id.as_str()) {
+            None => result.removed_nodes.push(node.clone()),
+            Some(target_node) => {
+                let changes = diff_attrs(&node.attrs, &target_node.attrs);
+                if !changes.is_empty() {
+                    result.changed_nodes.push((node.id.to_string(), changes));
+                }
+            }
+        }
+    }
+
+    for node in unique_nodes(&target.nodes) {
+        if !source_nodes.contains_key(node.id.as_str()) {
+            result.added_nodes.push(node.clone());
+        }
+    }
+
+    // The patch switches directedness before touching edges, so both sides
+    // are keyed the way the target's edges are.
+    let source_edges = keyed_edges(&source.edges, target.directed);
+    let target_edges = keyed_edges(&target.edges, target.directed);
+    let target_lookup: HashMap<&EdgeKey, &Edge> = target_edges
+        .iter()
+        .map(|(key, edge)| (key, *edge))
+        .collect();
+    let source_keys: HashSet<&EdgeKey> = source_edges.iter().map(|(key, _)| key).collect();
+
+    for (key, edge) in &source_edges {
+        match target_lookup.get(key) {
+            None => result.removed_edges.push((key.clone(), (*edge).clone())),
+            Some(target_edge) => {
+                let changes = diff_attrs(&edge.attrs, &target_edge.attrs);
+                if !changes.is_empty() {
+                    result.changed_edges.push((key.clone(), changes));
+                }
+            }
+        }
+    }
+
+    for (key, edge) in &target_edges {
+        if !source_keys.contains(key) {
+            result.added_edges.push((*edge).clone());
+        }
+    }
+
+    result
+}
+
+fn diff_attrs(source: &AttrMap, target: &AttrMap) -> Vec<AttrChange> {
+    let mut changes: Vec<AttrChange> = source
+        .iter()
+        .filter_map(|(key, old)| match target.get(key) {
+            None => Some(AttrChange::Removed {
+                key: key.clone(),
+                value: old.clone(),
+            }),
+            Some(new) if new != old => Some(AttrChange::Changed {
+                key: key.clone(),
+                old: old.clone(),
+                new: new.clone(),
+            }),
+            Some(_) => None,
+        })
+        .chain(
+            target
+                .iter()
+                .filter(|(key, _)| !source.contains_key(key))
+                .map(|(key, value)| AttrChange::Added {
+                    key: key.clone(),
+                    value: value.clone(),
+                }),
+        )
+        .collect();
+
+    changes.sort_by(|a, b| a.key().cmp(b.key()));
+    changes
+}
+
+fn first_nodes_by_id(nodes: &[Node]) -> HashMap<&str, &Node> {
+    let mut result = HashMap::new();
+    for node in nodes {
+        result.entry(node.id.as_str()).or_insert(node);
+    }
+    result
+}
+
+fn unique_nodes(nodes: &[Node]) -> impl Iterator<Item = &Node> {
+    let mut seen = HashSet::new();
+    nodes
+        .iter()
+        .filter(move |node| seen.insert(node.id.as_str()))
+}
+
+pub(crate) fn keyed_edges(edges: &[Edge], directed: bool) -> Vec<(EdgeKey, &Edge)> {
+    let mut counts: HashMap<(&str, &str), usize> = HashMap::new();
+    edges
+        .iter()
+        .map(|edge| {
+            let (from, to) = endpoints(edge, directed);
+            let index = match edge.key {
+                Some(_) => 0,
+                None => {
+                    let count = counts.entry((from, to)).or_default();
+                    *count += 1;
+                    *count - 1
+                }
+            };
+            let key = EdgeKey {
+                from: from.to_string(),
+                to: to.to_string(),
+                index,
+                directed,
+                key: edge.key.clone(),
+            };
+            (key, edge)
+        })
+        .collect()
+}
+
+/// The edge's endpoints, sorted unless `directed`.
+fn endpoints(edge: &Edge, directed: bool) -> (&str, &str) {
+    let (from, to) = (edge.from.as_str(), edge.to.as_str());
+    if directed || from <= to {
+        (from, to)
+    } else {
+        (to, from)
+    }
+}
+
+fn edge_op(directed: bool) -> &'static str {
+    if directed {
+        "->"
+    } else {
+        "--"
+    }
+}
+
+impl GraphDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_nodes.is_empty()
+            && self.removed_nodes.is_empty()
+            && self.changed_nodes.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.changed_edges.is_empty()
+            && self.graph_attrs.is_empty()
+            && self.directed.is_none()
+            && self.strict.is_none()
+    }
+
+    pub fn to_patch(&self) -> Patch {
+        let mut ops = Vec::new();
+
+        if let Some(directed) = self.directed {
+            ops.push(PatchOp::SetDirected(directed));
+        }
+
+        if let Some(strict) = self.strict {
+            ops.push(PatchOp::SetStrict(strict));
+        }
+
+        for change in &self.graph_attrs {
+            ops.push(PatchOp::SetGraphAttr {
+                key: change.key().to_string(),
+                value: change.new_value().map(str::to_string),
+            });
+        }
+
+        for node in &self.removed_nodes {
+            ops.push(PatchOp::RemoveNode(node.id.to_string()));
+        }
+
+        for (id, changes) in &self.changed_nodes {
+            for change in changes {
+                ops.push(PatchOp::SetNodeAttr {
+                    node: id.clone(),
+                    key: change.key().to_string(),
+                    value: change.new_value().map(str::to_string),
+                });
+            }
+        }
+
+        for node in &self.added_nodes {
+            ops.push(PatchOp::AddNode(node.clone()));
+        }
+
+        // Removed edges always sit after the retained ones sharing their
+        // endpoints, so attribute changes can use the keys as they stand.
+        for (key, changes) in &self.changed_edges {
+            for change in changes {
+                ops.push(PatchOp::SetEdgeAttr {
+                    edge: key.clone(),
+                    key: change.key().to_string(),
+                    value: change.new_value().map(str::to_string),
+                });
+            }
+        }
+
+        let mut removed: Vec<&EdgeKey> = self.removed_edges.iter().map(|(key, _)| key).collect();
+        removed.sort_by_key(|key| Reverse(key.index));
+        for key in removed {
+            ops.push(PatchOp::RemoveEdge(key.clone()));
+        }
+
+        for edge in &self.added_edges {
+            ops.push(PatchOp::AddEdge(edge.clone()));
+        }
+
+        Patch { ops }
+    }
+}
+
+impl fmt::Display for GraphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "no changes");
+        }
+
+        if let Some(directed) = self.directed {
+            writeln!(f, "~ graph directed: {} -> {directed}", !directed)?;
+        }
+        if let Some(strict) = self.strict {
+            writeln!(f, "~ graph strict: {} -> {strict}", !strict)?;
+        }
+        for change in &self.graph_attrs {
+            writeln!(f, "~ graph {change}")?;
+        }
+        for node in &self.added_nodes {
+            writeln!(f, "+ node {}{}", node.id, format_attrs(&node.attrs))?;
+        }
+        for node in &self.removed_nodes {
+            writeln!(f, "- node {}", node.id)?;
+        }
+        for (id, changes) in &self.changed_nodes {
+            writeln!(f, "~ node {id}: {}", join_changes(changes))?;
+        }
+        for edge in &self.added_edges {
+            writeln!(
+                f,
+                "+ edge {} {} {}{}",
+                edge.from,
+                edge_op(self.edges_directed),
+                edge.to,
+                format_attrs(&edge.attrs)
+            )?;
+        }
+        for (key, _) in &self.removed_edges {
+            writeln!(f, "- edge {key}")?;
+        }
+        for (key, changes) in &self.changed_edges {
+            writeln!(f, "~ edge {key}: {}", join_changes(changes))?;
+        }
+
+        Ok(())
+    }
+}
+
+fn format_attrs(attrs: &AttrMap) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+
+    let pairs: Vec<String> = attrs
+        .iter()
+        .map(|(key, value)| format!("{key}={value:?}"))
+        .collect();
+    format!(" [{}]", pairs.join(", "))
+}
+
+fn join_changes(changes: &[AttrChange]) -> String {
+    changes
+        .iter()
+        .map(|change| change.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchOp {
+    AddNode(Node),
+    RemoveNode(String),
+    SetNodeAttr {
+        node: String,
+        key: String,
+        value: Option<String>,
+    },
+    AddEdge(Edge),
+    RemoveEdge(EdgeKey),
+    SetEdgeAttr {
+        edge: EdgeKey,
+        key: String,
+        value: Option<String>,
+    },
+    SetGraphAttr {
+        key: String,
+        value: Option<String>,
+    },
+    SetDirected(bool),
+    /// Only flips the flag: the edge operations already describe the target's edges.
+    SetStrict(bool),
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Patch {
+    pub ops: Vec<PatchOp>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum PatchError {
+    MissingNode(String),
+    DuplicateNode(String),
+    MissingEdge(EdgeKey),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::MissingNode(id) => write!(f, "node {id} does not exist"),
+            PatchError::DuplicateNode(id) => write!(f, "node {id} already exists"),
+            PatchError::MissingEdge(key) => write!(f, "edge {key} does not exist"),
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+impl Patch {
+    /// Applies every operation in order. The graph is left untouched if any
+    /// operation fails.
+    pub fn apply(&self, graph: &mut Graph) -> Result<(), PatchError> {
+        let mut patched = graph.clone();
+        for op in &self.ops {
+            apply_op(&mut patched, op)?;
+        }
+        *graph = patched;
+        Ok(())
+    }
+}
+
+fn apply_op(graph: &mut Graph, op: &PatchOp) -> Result<(), PatchError> {
+    match op {
+        PatchOp::AddNode(node) => {
+            if graph.node(&node.id).is_some() {
+                return Err(PatchError::DuplicateNode(node.id.to_string()));
+            }
+            let node = graph.adopt_node(node.clone());
+            graph.nodes.push(node);
+        }
+        PatchOp::RemoveNode(id) => {
+            let position = node_position(graph, id)?;
+            graph.nodes.remove(position);
+        }
+        PatchOp::SetNodeAttr { node, key, value } => {
+            let position = node_position(graph, node)?;
+            set_attr(&mut graph.nodes[position].attrs, key, value);
+        }
+        PatchOp::AddEdge(edge) => {
+            let edge = graph.adopt_edge(edge.clone());
+            graph.edges.push(edge);
+        }
+        PatchOp::RemoveEdge(key) => {
+            let position = edge_position(graph, key)?;
+            graph.edges.remove(position);
+        }
+        PatchOp::SetEdgeAttr { edge, key, value } => {
+            let position = edge_position(graph, edge)?;
+            set_attr(&mut graph.edges[position].attrs, key, value);
+        }
+        PatchOp::SetGraphAttr { key, value } => set_attr(&mut graph.attrs, key, value),
+        PatchOp::SetDirected(directed) => graph.directed = *directed,
+        PatchOp::SetStrict(strict) => graph.strict = *strict,
+    }
+
+    Ok(())
+}
+
+fn node_position(graph: &Graph, id: &str) -> Result<usize, PatchError> {
+    graph
+        .nodes
+        .iter()
+        .position(|node| node.id == id)
+        .ok_or_else(|| PatchError::MissingNode(id.to_string()))
+}
+
+fn edge_position(graph: &Graph, key: &EdgeKey) -> Result<usize, PatchError> {
+    graph
+        .edges
+        .iter()
+        .enumerate()
+        .filter(|(_, edge)| edge.connects(&key.from, &key.to, key.directed) && edge.key == key.key)
+        .nth(key.index)
+        .map(|(position, _)| position)
+        .ok_or_else(|| PatchError::MissingEdge(key.clone()))
+}
+
+fn set_attr(attrs: &mut AttrMap, key: &str, value: &Option<String>) {
+    match value {
+        Some(value) => {
+            attrs.insert(key.to_string(), value.clone());
+        }
+        None => {
+            attrs.remove(key);
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+
+use super::graph_items::attr::AttrMap;
+use super::graph_items::port::Port;
+use super::Graph;
+
+/// Renders the graph in the DOT language. Attributes are written in insertion
+/// order, so the output is reproducible.
+impl fmt::Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.strict {
+            write!(f, "strict ")?;
+        }
+        writeln!(f, "{} {{", if self.directed { "digraph" } else { "graph" })?;
+
+        if !self.attrs.is_empty() {
+            writeln!(f, "    graph{}", format_attr_list(&self.attrs))?;
+        }
+
+        for node in &self.nodes {
+            writeln!(
+                f,
+                "    {}{}",
+                quote_id(&node.id),
+                format_attr_list(&node.attrs)
+            )?;
+        }
+
+        let op = if self.directed { "->" } else { "--" };
+        for edge in &self.edges {
+            writeln!(
+                f,
+                "    {} {op} {}{}",
+                format_endpoint(&edge.from, &edge.tail_port),
+                format_endpoint(&edge.to, &edge.head_port),
+                format_attr_list(&edge.attrs)
+            )?;
+        }
+
+        write!(f, "}}")
+    }
+}
+
+impl Graph {
+    pub fn to_dot(&self) -> String {
+        format!("{self}\n")
+    }
+}
+
+fn format_endpoint(id: &str, port: &Option<Port>) -> String {
+    let mut result = quote_id(id);
+    if let Some(port) = port {
+        if let Some(name) = &port.name {
+            result.push(':');
+            result.push_str(&quote_id(name));
+        }
+        if let Some(compass) = port.compass {
+            result.push(':');
+            result.push_str(compass.as_str());
+        }
+    }
+    result
+}
+
+fn format_attr_list(attrs: &AttrMap) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+
+    let pairs: Vec<String> = attrs
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{}={}",
+                quote_id(key),
+                quote_value(value, attrs.is_html(key))
+            )
+        })
+        .collect();
+    format!(" [{}]", pairs.join(", "))
+}
+
+/// Leaves identifiers, numerals and HTML strings bare and quotes everything else.
+pub fn quote_id(id: &str) -> String {
+    if is_html(id) {
+        id.to_string()
+    } else {
+        quote_text(id)
+    }
+}
+
+/// Like `quote_id`, for text known not to be an HTML string: `<b>` gets
+/// quoted.
+pub fn quote_text(text: &str) -> String {
+    if is_identifier(text) || is_numeral(text) {
+        text.to_string()
+    } else {
+        quote(text)
+    }
+}
+
+/// Attribute values are always quoted, except HTML strings which must stay bare.
+pub fn quote_value(value: &str, html: bool) -> String {
+    if html {
+        value.to_string()
+    } else {
+        quote(value)
+    }
+}
+
+/// Escapes quotes and copies every backslash as it is, undoing what a DOT
+/// parser does to a quoted string: `\\` and `\l` stay for Graphviz to
+/// interpret. No parser produces an odd run of backslashes before a quote
+/// or at the end, as in `C:\`, so such a run gets one more backslash to keep
+/// it from escaping the quote after it.
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in text.chars() {
+        if c == '"' {
+            if backslashes % 2 == 1 {
+                quoted.push('\\');
+            }
+            quoted.push('\\');
+        }
+        backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+        quoted.push(c);
+    }
+    if backslashes % 2 == 1 {
+        quoted.push('\\');
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn is_identifier(id: &str) -> bool {
+    let mut chars = id.chars();
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_')
+        && !is_keyword(id)
+}
+
+fn is_keyword(id: &str) -> bool {
+    ["graph", "digraph", "subgraph", "node", "edge", "strict"]
+        .iter()
+        .any(|keyword| keyword.eq_ignore_ascii_case(id))
+}
+
+fn is_numeral(id: &str) -> bool {
+    let digits = id.strip_prefix('-').unwrap_or(id);
+    let mut parts = digits.splitn(2, '.');
+    let whole = parts.next().unwrap_or_default();
+    let fraction = parts.next();
+
+    !digits.is_empty()
+        && digits != "."
+        && whole.chars().all(|c| c.is_ascii_digit())
+        && fraction.is_none_or(|part| part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// HTML strings are delimited by `<` and `>` with balanced brackets in between.
+fn is_html(value: &str) -> bool {
+    if !(value.starts_with('<') && value.ends_with('>')) {
+        return false;
+    }
+
+    let mut depth = 0usize;
+    for (position, c) in value.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => {
+                depth -= 1;
+                if depth == 0 && position != value.len() - 1 {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    depth == 0
+}
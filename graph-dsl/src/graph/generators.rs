@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use super::graph_items::{edge::Edge, node::Node};
+use super::Graph;
+
+/// A small SplitMix64 generator, so generated graphs only depend on the seed.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A float in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// An index in `0..bound`, every one equally likely. `bound` must not be
+    /// zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        // Lemire's method: multiply into 128 bits and retry the few draws
+        // whose low half would favour some results.
+        let bound = bound as u64;
+        let mut product = u128::from(self.next_u64()) * u128::from(bound);
+        if (product as u64) < bound {
+            let threshold = bound.wrapping_neg() % bound;
+            while (product as u64) < threshold {
+                product = u128::from(self.next_u64()) * u128::from(bound);
+            }
+        }
+        (product >> 64) as usize
+    }
+}
+
+pub struct Generator {
+    prefix: String,
+    random_attrs: Vec<(String, Vec<String>)>,
+    rng: Rng,
+}
+
+#[allow(clippy::new_without_default)]
+impl Generator {
+    pub fn new() -> Self {
+        Self {
+            prefix: "n".to_string(),
+            random_attrs: Vec::new(),
+            rng: Rng::seeded(0),
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = prefix.to_string();
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng::seeded(seed);
+        self
+    }
+
+    /// Every generated node gets `key` set to one of `values`, picked at random.
+    pub fn with_random_attr(mut self, key: &str, values: &[&str]) -> Self {
+        if !values.is_empty() {
+            self.random_attrs.push((
+                key.to_string(),
+                values.iter().map(|value| value.to_string()).collect(),
+            ));
+        }
+        self
+    }
+
+    pub fn complete(&mut self, n: usize) -> Graph {
+        let edges = (0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect();
+        self.build(n, edges)
+    }
+
+    pub fn path(&mut self, n: usize) -> Graph {
+        let edges = (1..n).map(|i| (i - 1, i)).collect();
+        self.build(n, edges)
+    }
+
+    pub fn cycle(&mut self, n: usize) -> Graph {
+        let mut edges: Vec<_> = (1..n).map(|i| (i - 1, i)).collect();
+        if n > 2 {
+            edges.push((n - 1, 0));
+        }
+        self.build(n, edges)
+    }
+
+    /// One hub (`{prefix}0`) connected to `leaves` other nodes.
+    pub fn star(&mut self, leaves: usize) -> Graph {
+        let edges = (1..=leaves).map(|i| (0, i)).collect();
+        self.build(leaves + 1, edges)
+    }
+
+    /// Nodes are named `{prefix}{row}_{col}` and linked to their right and lower neighbours.
+    pub fn grid(&mut self, rows: usize, cols: usize) -> Graph {
+        let ids = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| (row, col)))
+            .map(|(row, col)| format!("{}{row}_{col}", self.prefix))
+            .collect();
+
+        let mut edges = Vec::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                let index = row * cols + col;
+                if col + 1 < cols {
+                    edges.push((index, index + 1));
+                }
+                if row + 1 < rows {
+                    edges.push((index, index + cols));
+                }
+            }
+        }
+
+        self.build_with_ids(ids, edges)
+    }
+
+    /// A complete binary tree with `n` nodes, laid out like a binary heap.
+    pub fn binary_tree(&mut self, n: usize) -> Graph {
+        let edges = (1..n).map(|i| ((i - 1) / 2, i)).collect();
+        self.build(n, edges)
+    }
+
+    /// G(n, p): every pair of nodes is linked independently with probability `p`.
+    pub fn erdos_renyi(&mut self, n: usize, p: f64) -> Graph {
+        let mut edges = Vec::new();
+        for i in 0..n {
+            for j in i + 1..n {
+                if self.rng.next_f64() < p {
+                    edges.push((i, j));
+                }
+            }
+        }
+        self.build(n, edges)
+    }
+
+    /// Preferential attachment: starting from a star of `m + 1` nodes, every new
+    /// node links to `m` distinct existing nodes chosen proportionally to their degree.
+    ///
+    /// # Panics
+    ///
+    /// Unless `0 < m < n`.
+    pub fn barabasi_albert(&mut self, n: usize, m: usize) -> Graph {
+        assert!(
+            m > 0 && m < n,
+            "barabasi_albert needs 0 < m < n, got n = {n} and m = {m}"
+        );
+
+        let mut edges: Vec<(usize, usize)> = (1..=m).map(|i| (0, i)).collect();
+        let mut endpoints: Vec<usize> = edges.iter().flat_map(|&(a, b)| [a, b]).collect();
+
+        for new in m + 1..n {
+            let mut targets = HashSet::new();
+            let mut chosen = Vec::new();
+            while chosen.len() < m {
+                let target = endpoints[self.rng.below(endpoints.len())];
+                if targets.insert(target) {
+                    chosen.push(target);
+                }
+            }
+
+            for target in chosen {
+                edges.push((target, new));
+                endpoints.push(target);
+                endpoints.push(new);
+            }
+        }
+
+        self.build(n, edges)
+    }
+
+    fn build(&mut self, n: usize, edges: Vec<(usize, usize)>) -> Graph {
+        let ids = (0..n).map(|i| format!("{}{i}", self.prefix)).collect();
+        self.build_with_ids(ids, edges)
+    }
+
+    fn build_with_ids(&mut self, ids: Vec<String>, edges: Vec<(usize, usize)>) -> Graph {
+        let mut graph = Graph::new();
+
+        for id in &ids {
+            let mut node = graph.adopt_node(Node::new(id));
+            for (key, values) in &self.random_attrs {
+                let value = &values[self.rng.below(values.len())];
+                node.attrs.insert(key.clone(), value.clone());
+            }
+            graph.nodes.push(node);
+        }
+
+        for (from, to) in edges {
+            let edge = graph.adopt_edge(Edge::new(&ids[from], &ids[to]));
+            graph.edges.push(edge);
+        }
+
+        graph
+    }
+}
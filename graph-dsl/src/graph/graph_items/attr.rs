@@ -1,5 +1,186 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Index;
 
+/// Attribute map that remembers insertion order, so iterating over it (and
+/// anything rendered from it) is reproducible. Equality ignores order.
+#[derive(Clone, Default)]
+pub struct AttrMap {
+    entries: Vec<(String, String)>,
+    /// Keys whose value is an HTML string rather than text.
+    html: HashSet<String>,
+}
+
+impl AttrMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&String> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing == key)
+            .map(|(_, value)| value)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Overwriting an existing key keeps its original position.
+    pub fn insert(&mut self, key: String, value: String) -> Option<String> {
+        self.html.remove(&key);
+        self.set(key, value)
+    }
+
+    /// Inserts an HTML string, such as `<<b>bold</b>>`, with its outer
+    /// brackets. It is written back unquoted.
+    pub fn insert_html(&mut self, key: String, value: String) -> Option<String> {
+        self.html.insert(key.clone());
+        self.set(key, value)
+    }
+
+    /// Whether the value of `key` was inserted as an HTML string.
+    pub fn is_html(&self, key: &str) -> bool {
+        self.html.contains(key)
+    }
+
+    fn set(&mut self, key: String, value: String) -> Option<String> {
+        match self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| *existing == key)
+        {
+            Some((_, existing)) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.html.remove(key);
+        let position = self
+            .entries
+            .iter()
+            .position(|(existing, _)| existing == key)?;
+        Some(self.entries.remove(position).1)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+pub struct Iter<'a> {
+    inner: std::slice::Iter<'a, (String, String)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (key, value))
+    }
+}
+
+impl<'a> IntoIterator for &'a AttrMap {
+    type Item = (&'a String, &'a String);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for AttrMap {
+    type Item = (String, String);
+    type IntoIter = std::vec::IntoIter<(String, String)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl FromIterator<(String, String)> for AttrMap {
+    fn from_iter<T: IntoIterator<Item = (String, String)>>(iter: T) -> Self {
+        let mut map = AttrMap::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, String)> for AttrMap {
+    fn extend<T: IntoIterator<Item = (String, String)>>(&mut self, iter: T) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl Index<&str> for AttrMap {
+    type Output = String;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key).expect("no attribute with this key")
+    }
+}
+
+impl PartialEq for AttrMap {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self.html == other.html
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl Eq for AttrMap {}
+
+impl PartialEq<HashMap<String, String>> for AttrMap {
+    fn eq(&self, other: &HashMap<String, String>) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl fmt::Debug for AttrMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+pub fn attr_list_to_map(attrs: &[(&str, &str)]) -> AttrMap {
+    AttrMap::from_iter(
+        attrs
+            .iter()
+            .map(|(key, val)| (key.to_string(), val.to_string())),
+    )
+}
+
+#[deprecated(note = "attributes are kept in an `AttrMap` now; use `attr_list_to_map`")]
 pub fn attr_list_to_hash_map(attrs: &[(&str, &str)]) -> HashMap<String, String> {
     HashMap::from_iter(
         attrs
@@ -8,6 +189,12 @@ pub fn attr_list_to_hash_map(attrs: &[(&str, &str)]) -> HashMap<String, String>
     )
 }
 
-pub fn attr<'a>(attrs: &'a HashMap<String, String>, key: &str) -> Option<&'a str> {
+pub fn attr<'a>(attrs: &'a AttrMap, key: &str) -> Option<&'a str> {
     attrs.get(key).map(|val| val.as_str())
 }
+
+pub fn attr_iter(attrs: &AttrMap) -> impl Iterator<Item = (&str, &str)> {
+    attrs
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+}
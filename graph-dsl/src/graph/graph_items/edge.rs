@@ -1,29 +1,63 @@
-use std::collections::HashMap;
-
-use super::attr::{attr, attr_list_to_hash_map};
+use super::attr::{attr, attr_iter, attr_list_to_map, AttrMap};
+use super::id::Id;
+use super::port::Port;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Edge {
-    from: String,
-    to: String,
-    attrs: HashMap<String, String>,
+    pub from: Id,
+    pub to: Id,
+    pub attrs: AttrMap,
+    /// Tells parallel edges between the same nodes apart.
+    pub key: Option<String>,
+    pub tail_port: Option<Port>,
+    pub head_port: Option<Port>,
 }
 
 impl Edge {
     pub fn new(from: &str, to: &str) -> Self {
         Self {
-            from: from.to_string(),
-            to: to.to_string(),
-            attrs: HashMap::default(),
+            from: Id::from(from),
+            to: Id::from(to),
+            attrs: AttrMap::default(),
+            key: None,
+            tail_port: None,
+            head_port: None,
         }
     }
 
     pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
-        self.attrs = attr_list_to_hash_map(attrs);
+        self.attrs = attr_list_to_map(attrs);
+        self
+    }
+
+    pub fn with_key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// The port on the `from` node.
+    pub fn with_tail_port(mut self, port: Port) -> Self {
+        self.tail_port = Some(port);
         self
     }
 
+    /// The port on the `to` node.
+    pub fn with_head_port(mut self, port: Port) -> Self {
+        self.head_port = Some(port);
+        self
+    }
+
+    /// Whether this edge runs between `a` and `b`, in either direction unless `directed`.
+    pub fn connects(&self, a: &str, b: &str, directed: bool) -> bool {
+        (self.from == a && self.to == b) || (!directed && self.from == b && self.to == a)
+    }
+
     pub fn attr(&self, key: &str) -> Option<&str> {
         attr(&self.attrs, key)
     }
+
+    /// Attributes in the order they were set.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        attr_iter(&self.attrs)
+    }
 }
@@ -0,0 +1,124 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A node id. Ids handed out by a graph's [`Interner`] share one allocation
+/// per name, so the endpoints of every edge cost a pointer instead of a copy
+/// of the name. Compares, hashes and prints like the name itself.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Id(Arc<str>);
+
+impl Id {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether both ids share one allocation, as ids from the same
+    /// [`Interner`] do.
+    pub fn ptr_eq(&self, other: &Id) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for Id {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Id {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Id {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Id {
+    fn from(name: &str) -> Self {
+        Id(Arc::from(name))
+    }
+}
+
+impl From<String> for Id {
+    fn from(name: String) -> Self {
+        Id(Arc::from(name))
+    }
+}
+
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl PartialEq<str> for Id {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Id {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Id {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+/// The ids a graph has handed out, each name stored once.
+#[derive(Clone, Debug, Default)]
+pub struct Interner {
+    ids: HashSet<Id>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The id for `name`, shared with every other id of that name.
+    pub fn intern(&mut self, name: &str) -> Id {
+        if let Some(id) = self.ids.get(name) {
+            return id.clone();
+        }
+        let id = Id::from(name);
+        self.ids.insert(id.clone());
+        id
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Id> {
+        self.ids.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Forgets the names no node or edge uses any more.
+    pub fn shrink(&mut self) {
+        self.ids.retain(|id| Arc::strong_count(&id.0) > 1);
+    }
+}
@@ -0,0 +1,632 @@
+use std::error::Error;
+use std::fmt;
+use std::str::CharIndices;
+
+use super::attr::{attr_iter, AttrMap};
+
+/// A node label, interpreted according to its syntax and the node's shape.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Label {
+    Text(String),
+    Record(RecordLabel),
+    Html(HtmlLabel),
+}
+
+impl Label {
+    /// Reads a label attribute value. HTML-like labels are recognised by their
+    /// surrounding `<...>`, record labels only apply to record shaped nodes.
+    pub fn parse(value: &str, shape: Option<&str>) -> Result<Label, LabelError> {
+        let record = matches!(shape, Some("record") | Some("Mrecord"));
+
+        // A record label such as `<p1>` is also valid HTML text, so record
+        // shaped nodes only get an HTML label if it contains markup.
+        if value.starts_with('<') && value.ends_with('>') {
+            match HtmlLabel::parse(value) {
+                Ok(html) if record && !html.has_elements() => {}
+                Err(_) if record => {}
+                result => return result.map(Label::Html),
+            }
+        }
+
+        if record {
+            RecordLabel::parse(value).map(Label::Record)
+        } else {
+            Ok(Label::Text(value.to_string()))
+        }
+    }
+
+    /// Port names the label declares, in document order.
+    pub fn ports(&self) -> Vec<String> {
+        match self {
+            Label::Text(_) => Vec::new(),
+            Label::Record(record) => record.ports(),
+            Label::Html(html) => html.ports(),
+        }
+    }
+}
+
+impl fmt::Display for Label {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Label::Text(text) => f.write_str(text),
+            Label::Record(record) => record.fmt(f),
+            Label::Html(html) => html.fmt(f),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelError {
+    pub message: String,
+    /// Byte offset into the label text.
+    pub position: usize,
+}
+
+impl LabelError {
+    fn new(message: &str, position: usize) -> Self {
+        Self {
+            message: message.to_string(),
+            position,
+        }
+    }
+}
+
+impl fmt::Display for LabelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.position)
+    }
+}
+
+impl Error for LabelError {}
+
+/// A Graphviz record label such as `{a|<p1> b|c}`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RecordLabel {
+    pub fields: Vec<RecordField>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordField {
+    Text {
+        port: Option<String>,
+        text: String,
+    },
+    /// A nested `{...}` group, laid out across the enclosing direction.
+    Group(Vec<RecordField>),
+}
+
+impl RecordField {
+    pub fn text(text: &str) -> Self {
+        RecordField::Text {
+            port: None,
+            text: text.to_string(),
+        }
+    }
+
+    pub fn port(port: &str, text: &str) -> Self {
+        RecordField::Text {
+            port: Some(port.to_string()),
+            text: text.to_string(),
+        }
+    }
+
+    pub fn group(fields: Vec<RecordField>) -> Self {
+        RecordField::Group(fields)
+    }
+}
+
+const RECORD_SPECIAL: [char; 5] = ['{', '}', '|', '<', '>'];
+
+impl RecordLabel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_field(mut self, field: RecordField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn parse(text: &str) -> Result<RecordLabel, LabelError> {
+        let fields = parse_record_fields(&mut text.char_indices(), None)?;
+        Ok(RecordLabel { fields })
+    }
+
+    pub fn ports(&self) -> Vec<String> {
+        fn collect(fields: &[RecordField], ports: &mut Vec<String>) {
+            for field in fields {
+                match field {
+                    RecordField::Text {
+                        port: Some(port), ..
+                    } => ports.push(port.clone()),
+                    RecordField::Text { .. } => {}
+                    RecordField::Group(fields) => collect(fields, ports),
+                }
+            }
+        }
+
+        let mut ports = Vec::new();
+        collect(&self.fields, &mut ports);
+        ports
+    }
+}
+
+/// Parses fields up to the end of the text, or up to the `}` closing the
+/// group opened at `group_start`.
+fn parse_record_fields(
+    chars: &mut CharIndices,
+    group_start: Option<usize>,
+) -> Result<Vec<RecordField>, LabelError> {
+    let mut fields = Vec::new();
+    let mut port: Option<String> = None;
+    let mut text = String::new();
+    let mut group: Option<Vec<RecordField>> = None;
+
+    while let Some((position, c)) = chars.next() {
+        match c {
+            '|' => fields.push(finish_field(port.take(), &mut text, group.take())),
+            '}' if group_start.is_some() => {
+                fields.push(finish_field(port, &mut text, group));
+                return Ok(fields);
+            }
+            '}' => return Err(LabelError::new("unexpected '}'", position)),
+            '{' => group = Some(parse_record_fields(chars, Some(position))?),
+            '<' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '>')) => break,
+                        Some((_, '\\')) => {
+                            if let Some((_, escaped)) = chars.next() {
+                                name.push(escaped);
+                            }
+                        }
+                        Some((_, c)) => name.push(c),
+                        None => return Err(LabelError::new("unterminated port name", position)),
+                    }
+                }
+                port = Some(name.trim().to_string());
+            }
+            '\\' => match chars.next() {
+                Some((_, escaped)) if RECORD_SPECIAL.contains(&escaped) || escaped == ' ' => {
+                    text.push(escaped)
+                }
+                Some((_, other)) => {
+                    text.push('\\');
+                    text.push(other);
+                }
+                None => text.push('\\'),
+            },
+            _ => text.push(c),
+        }
+    }
+
+    if let Some(start) = group_start {
+        return Err(LabelError::new("unterminated '{'", start));
+    }
+
+    fields.push(finish_field(port, &mut text, group));
+    Ok(fields)
+}
+
+fn finish_field(
+    port: Option<String>,
+    text: &mut String,
+    group: Option<Vec<RecordField>>,
+) -> RecordField {
+    let trimmed = text.trim().to_string();
+    text.clear();
+    match group {
+        Some(fields) => RecordField::Group(fields),
+        None => RecordField::Text {
+            port,
+            text: trimmed,
+        },
+    }
+}
+
+impl fmt::Display for RecordLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_record_fields(f, &self.fields)
+    }
+}
+
+fn write_record_fields(f: &mut fmt::Formatter<'_>, fields: &[RecordField]) -> fmt::Result {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            f.write_str("|")?;
+        }
+        match field {
+            RecordField::Text { port, text } => {
+                if let Some(port) = port {
+                    write!(f, "<{}>", escape_record(port))?;
+                    if !text.is_empty() {
+                        f.write_str(" ")?;
+                    }
+                }
+                f.write_str(&escape_record(text))?;
+            }
+            RecordField::Group(fields) => {
+                f.write_str("{")?;
+                write_record_fields(f, fields)?;
+                f.write_str("}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn escape_record(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if RECORD_SPECIAL.contains(&c) {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// An HTML-like label such as `<<TABLE><TR><TD>a</TD></TR></TABLE>>`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HtmlLabel {
+    pub content: Vec<HtmlNode>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum HtmlNode {
+    Element(HtmlElement),
+    /// Text is kept as written, entities included.
+    Text(String),
+}
+
+impl From<HtmlElement> for HtmlNode {
+    fn from(element: HtmlElement) -> Self {
+        HtmlNode::Element(element)
+    }
+}
+
+impl From<&str> for HtmlNode {
+    fn from(text: &str) -> Self {
+        HtmlNode::Text(text.to_string())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HtmlElement {
+    pub name: String,
+    pub attrs: AttrMap,
+    pub children: Vec<HtmlNode>,
+}
+
+impl HtmlElement {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            attrs: AttrMap::new(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_attr(mut self, key: &str, value: &str) -> Self {
+        self.attrs.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn with_child<T: Into<HtmlNode>>(mut self, child: T) -> Self {
+        self.children.push(child.into());
+        self
+    }
+
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        attr_iter(&self.attrs)
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| value)
+    }
+
+    fn is(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+/// Elements Graphviz understands, and the elements allowed directly inside them
+/// (`None` meaning text and inline formatting).
+const HTML_ELEMENTS: [(&str, Option<&[&str]>); 15] = [
+    ("TABLE", Some(&["TR", "HR"])),
+    ("TR", Some(&["TD", "VR"])),
+    ("TD", None),
+    ("FONT", None),
+    ("B", None),
+    ("I", None),
+    ("U", None),
+    ("O", None),
+    ("S", None),
+    ("SUB", None),
+    ("SUP", None),
+    ("BR", Some(&[])),
+    ("HR", Some(&[])),
+    ("VR", Some(&[])),
+    ("IMG", Some(&[])),
+];
+
+impl HtmlLabel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_child<T: Into<HtmlNode>>(mut self, child: T) -> Self {
+        self.content.push(child.into());
+        self
+    }
+
+    /// A plain table with one cell per string, e.g. for quick tabular nodes.
+    pub fn table(rows: &[&[&str]]) -> Self {
+        let table = rows.iter().fold(HtmlElement::new("TABLE"), |table, row| {
+            table.with_child(row.iter().fold(HtmlElement::new("TR"), |tr, cell| {
+                tr.with_child(HtmlElement::new("TD").with_child(escape_html(cell).as_str()))
+            }))
+        });
+        HtmlLabel::new().with_child(table)
+    }
+
+    /// Parses a label including its outer angle brackets.
+    pub fn parse(value: &str) -> Result<HtmlLabel, LabelError> {
+        let inner = value
+            .strip_prefix('<')
+            .and_then(|rest| rest.strip_suffix('>'))
+            .ok_or_else(|| LabelError::new("HTML labels must be wrapped in '<' and '>'", 0))?;
+
+        let mut parser = HtmlParser {
+            source: inner,
+            position: 0,
+        };
+        let content = parser.parse_children(None)?;
+        Ok(HtmlLabel { content })
+    }
+
+    pub fn has_elements(&self) -> bool {
+        self.content
+            .iter()
+            .any(|node| matches!(node, HtmlNode::Element(_)))
+    }
+
+    pub fn ports(&self) -> Vec<String> {
+        fn collect(nodes: &[HtmlNode], ports: &mut Vec<String>) {
+            for node in nodes {
+                if let HtmlNode::Element(element) = node {
+                    if let Some(port) = element.attr("PORT") {
+                        ports.push(port.to_string());
+                    }
+                    collect(&element.children, ports);
+                }
+            }
+        }
+
+        let mut ports = Vec::new();
+        collect(&self.content, &mut ports);
+        ports
+    }
+
+    /// Problems Graphviz would reject: unknown elements and misplaced table parts.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        validate_nodes(&self.content, None, &mut problems);
+        problems
+    }
+}
+
+fn validate_nodes(nodes: &[HtmlNode], parent: Option<&HtmlElement>, problems: &mut Vec<String>) {
+    let allowed = parent.and_then(|parent| {
+        HTML_ELEMENTS
+            .iter()
+            .find(|(name, _)| parent.is(name))
+            .and_then(|(_, allowed)| *allowed)
+    });
+
+    for node in nodes {
+        match node {
+            HtmlNode::Text(text) => {
+                if allowed.is_some() && !text.trim().is_empty() {
+                    problems.push(format!(
+                        "text is not allowed directly inside <{}>",
+                        parent.unwrap().name
+                    ));
+                }
+            }
+            HtmlNode::Element(element) => {
+                if !HTML_ELEMENTS.iter().any(|(name, _)| element.is(name)) {
+                    problems.push(format!("unknown element <{}>", element.name));
+                } else if let Some(allowed) = allowed {
+                    if !allowed.iter().any(|name| element.is(name)) {
+                        problems.push(format!(
+                            "<{}> is not allowed inside <{}>",
+                            element.name,
+                            parent.unwrap().name
+                        ));
+                    }
+                } else if ["TR", "TD", "HR", "VR"].iter().any(|name| element.is(name)) {
+                    problems.push(format!(
+                        "<{}> must be inside a {}",
+                        element.name,
+                        if element.is("TD") || element.is("VR") {
+                            "<TR>"
+                        } else {
+                            "<TABLE>"
+                        }
+                    ));
+                }
+                validate_nodes(&element.children, Some(element), problems);
+            }
+        }
+    }
+}
+
+struct HtmlParser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl<'a> HtmlParser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.source[self.position..]
+    }
+
+    /// Offsets are reported relative to the full label, outer `<` included.
+    fn error(&self, message: &str, position: usize) -> LabelError {
+        LabelError::new(message, position + 1)
+    }
+
+    fn parse_children(&mut self, parent: Option<&str>) -> Result<Vec<HtmlNode>, LabelError> {
+        let mut children = Vec::new();
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return match parent {
+                    Some(name) => Err(self.error(&format!("unclosed <{name}>"), self.position)),
+                    None => Ok(children),
+                };
+            }
+
+            if rest.starts_with("<!--") {
+                let end = rest
+                    .find("-->")
+                    .ok_or_else(|| self.error("unterminated comment", self.position))?;
+                self.position += end + 3;
+            } else if let Some(after) = rest.strip_prefix("</") {
+                let start = self.position;
+                let end = after
+                    .find('>')
+                    .ok_or_else(|| self.error("unterminated closing tag", start))?;
+                let name = after[..end].trim();
+                self.position += 2 + end + 1;
+                return match parent {
+                    Some(open) if open.eq_ignore_ascii_case(name) => Ok(children),
+                    Some(open) => {
+                        Err(self.error(&format!("expected </{open}>, found </{name}>"), start))
+                    }
+                    None => Err(self.error(&format!("unexpected </{name}>"), start)),
+                };
+            } else if rest.starts_with('<') {
+                children.push(HtmlNode::Element(self.parse_element()?));
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                children.push(HtmlNode::Text(rest[..end].to_string()));
+                self.position += end;
+            }
+        }
+    }
+
+    fn parse_element(&mut self) -> Result<HtmlElement, LabelError> {
+        let start = self.position;
+        self.position += 1;
+
+        let name_length = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+            .unwrap_or(self.rest().len());
+        if name_length == 0 {
+            return Err(self.error("expected element name", start));
+        }
+        let mut element = HtmlElement::new(&self.rest()[..name_length]);
+        self.position += name_length;
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.position += 2;
+                return Ok(element);
+            }
+            if rest.starts_with('>') {
+                self.position += 1;
+                break;
+            }
+            if rest.is_empty() {
+                return Err(self.error(&format!("unterminated <{}>", element.name), start));
+            }
+
+            let key_length = rest
+                .find(|c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/')
+                .unwrap_or(rest.len());
+            let key = rest[..key_length].to_string();
+            self.position += key_length;
+            self.skip_whitespace();
+
+            let Some(after) = self.rest().strip_prefix('=') else {
+                return Err(self.error(&format!("attribute {key} has no value"), self.position));
+            };
+            let quote_at = self.position + 1 + (after.len() - after.trim_start().len());
+            let after = after.trim_start();
+            let quote = after
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')
+                .ok_or_else(|| self.error("attribute values must be quoted", quote_at))?;
+            let end = after[1..]
+                .find(quote)
+                .ok_or_else(|| self.error("unterminated attribute value", quote_at))?;
+            element.attrs.insert(key, after[1..=end].to_string());
+            self.position = quote_at + end + 2;
+        }
+
+        const VOID: [&str; 4] = ["BR", "HR", "VR", "IMG"];
+        if VOID.iter().any(|name| element.is(name)) {
+            return Ok(element);
+        }
+
+        element.children = self.parse_children(Some(&element.name.clone()))?;
+        Ok(element)
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+}
+
+impl fmt::Display for HtmlLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("<")?;
+        for node in &self.content {
+            node.fmt(f)?;
+        }
+        f.write_str(">")
+    }
+}
+
+impl fmt::Display for HtmlNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HtmlNode::Text(text) => f.write_str(text),
+            HtmlNode::Element(element) => element.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for HtmlElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<{}", self.name)?;
+        for (key, value) in attr_iter(&self.attrs) {
+            write!(f, " {key}=\"{}\"", value.replace('"', "&quot;"))?;
+        }
+
+        if self.children.is_empty() {
+            return f.write_str("/>");
+        }
+
+        f.write_str(">")?;
+        for child in &self.children {
+            child.fmt(f)?;
+        }
+        write!(f, "</{}>", self.name)
+    }
+}
+
+/// Escapes text for use inside an HTML-like label.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
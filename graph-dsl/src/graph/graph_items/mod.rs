@@ -1,3 +1,6 @@
 pub mod attr;
 pub mod edge;
+pub mod id;
+pub mod label;
 pub mod node;
+pub mod port;
@@ -1,27 +1,53 @@
-use std::collections::HashMap;
-
-use super::attr::{attr, attr_list_to_hash_map};
+use super::attr::{attr, attr_iter, attr_list_to_map, AttrMap};
+use super::id::Id;
+use super::label::{Label, LabelError};
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Node {
-    pub id: String,
-    pub attrs: HashMap<String, String>,
+    pub id: Id,
+    pub attrs: AttrMap,
 }
 
 impl Node {
     pub fn new(id: &str) -> Self {
         Self {
-            id: id.to_string(),
-            attrs: HashMap::default(),
+            id: Id::from(id),
+            attrs: AttrMap::default(),
         }
     }
 
     pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
-        self.attrs = attr_list_to_hash_map(attrs);
+        self.attrs = attr_list_to_map(attrs);
         self
     }
 
     pub fn attr(&self, key: &str) -> Option<&str> {
         attr(&self.attrs, key)
     }
+
+    /// Attributes in the order they were set.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        attr_iter(&self.attrs)
+    }
+
+    /// Sets the `label` attribute. Record labels also make the node record
+    /// shaped, unless it already has a shape.
+    pub fn with_label(mut self, label: &Label) -> Self {
+        if matches!(label, Label::Record(_)) && !self.attrs.contains_key("shape") {
+            self.attrs.insert("shape".to_string(), "record".to_string());
+        }
+        match label {
+            Label::Html(_) => self
+                .attrs
+                .insert_html("label".to_string(), label.to_string()),
+            _ => self.attrs.insert("label".to_string(), label.to_string()),
+        };
+        self
+    }
+
+    /// The parsed `label` attribute, if the node has one.
+    pub fn label(&self) -> Option<Result<Label, LabelError>> {
+        self.attr("label")
+            .map(|label| Label::parse(label, self.attr("shape")))
+    }
 }
@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CompassPoint {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+    C,
+    /// `_`, letting Graphviz pick the side.
+    Any,
+}
+
+impl CompassPoint {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CompassPoint::N => "n",
+            CompassPoint::NE => "ne",
+            CompassPoint::E => "e",
+            CompassPoint::SE => "se",
+            CompassPoint::S => "s",
+            CompassPoint::SW => "sw",
+            CompassPoint::W => "w",
+            CompassPoint::NW => "nw",
+            CompassPoint::C => "c",
+            CompassPoint::Any => "_",
+        }
+    }
+}
+
+impl FromStr for CompassPoint {
+    type Err = PortParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "n" => CompassPoint::N,
+            "ne" => CompassPoint::NE,
+            "e" => CompassPoint::E,
+            "se" => CompassPoint::SE,
+            "s" => CompassPoint::S,
+            "sw" => CompassPoint::SW,
+            "w" => CompassPoint::W,
+            "nw" => CompassPoint::NW,
+            "c" => CompassPoint::C,
+            "_" => CompassPoint::Any,
+            _ => return Err(PortParseError::UnknownCompassPoint(s.to_string())),
+        })
+    }
+}
+
+impl fmt::Display for CompassPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where an edge attaches to a node, as in `a:out:ne -> b:in`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Port {
+    pub name: Option<String>,
+    pub compass: Option<CompassPoint>,
+}
+
+impl Port {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: Some(name.to_string()),
+            compass: None,
+        }
+    }
+
+    pub fn compass(compass: CompassPoint) -> Self {
+        Self {
+            name: None,
+            compass: Some(compass),
+        }
+    }
+
+    pub fn with_compass(mut self, compass: CompassPoint) -> Self {
+        self.compass = Some(compass);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PortParseError {
+    Empty,
+    UnknownCompassPoint(String),
+}
+
+impl fmt::Display for PortParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PortParseError::Empty => write!(f, "port is empty"),
+            PortParseError::UnknownCompassPoint(value) => {
+                write!(f, "{value:?} is not a compass point")
+            }
+        }
+    }
+}
+
+impl Error for PortParseError {}
+
+/// Parses the part after the node id: `name`, `name:compass` or a bare compass
+/// point, which Graphviz prefers over a port of the same name.
+impl FromStr for Port {
+    type Err = PortParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((name, compass)) if !name.is_empty() => {
+                Ok(Port::new(name).with_compass(compass.parse()?))
+            }
+            Some(_) => Err(PortParseError::Empty),
+            None if s.is_empty() => Err(PortParseError::Empty),
+            None => Ok(s
+                .parse()
+                .map(Port::compass)
+                .unwrap_or_else(|_| Port::new(s))),
+        }
+    }
+}
+
+impl fmt::Display for Port {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.name, self.compass) {
+            (Some(name), Some(compass)) => write!(f, "{name}:{compass}"),
+            (Some(name), None) => write!(f, "{name}"),
+            (None, Some(compass)) => write!(f, "{compass}"),
+            (None, None) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,826 @@
+use std::error::Error;
+use std::fmt;
+
+use super::graph_items::attr::AttrMap;
+use super::graph_items::edge::Edge;
+use super::graph_items::node::Node;
+use super::graph_items::port::Port;
+use super::Graph;
+
+/// A single change to a graph that carries enough of the old state to be
+/// reversed exactly. Nodes and edges are addressed by position.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    InsertNode {
+        index: usize,
+        node: Node,
+    },
+    RemoveNode {
+        index: usize,
+        node: Node,
+    },
+    InsertEdge {
+        index: usize,
+        edge: Edge,
+    },
+    RemoveEdge {
+        index: usize,
+        edge: Edge,
+    },
+    SetNodeAttr {
+        node: String,
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    SetEdgeAttr {
+        index: usize,
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    SetGraphAttr {
+        key: String,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    SetDirected {
+        old: bool,
+        new: bool,
+    },
+    SetStrict {
+        old: bool,
+        new: bool,
+    },
+}
+
+impl Edit {
+    pub fn inverse(&self) -> Edit {
+        match self.clone() {
+            Edit::InsertNode { index, node } => Edit::RemoveNode { index, node },
+            Edit::RemoveNode { index, node } => Edit::InsertNode { index, node },
+            Edit::InsertEdge { index, edge } => Edit::RemoveEdge { index, edge },
+            Edit::RemoveEdge { index, edge } => Edit::InsertEdge { index, edge },
+            Edit::SetNodeAttr {
+                node,
+                key,
+                old,
+                new,
+            } => Edit::SetNodeAttr {
+                node,
+                key,
+                old: new,
+                new: old,
+            },
+            Edit::SetEdgeAttr {
+                index,
+                key,
+                old,
+                new,
+            } => Edit::SetEdgeAttr {
+                index,
+                key,
+                old: new,
+                new: old,
+            },
+            Edit::SetGraphAttr { key, old, new } => Edit::SetGraphAttr {
+                key,
+                old: new,
+                new: old,
+            },
+            Edit::SetDirected { old, new } => Edit::SetDirected { old: new, new: old },
+            Edit::SetStrict { old, new } => Edit::SetStrict { old: new, new: old },
+        }
+    }
+
+    pub fn apply(&self, graph: &mut Graph) -> Result<(), HistoryError> {
+        match self {
+            Edit::InsertNode { index, node } => {
+                if graph.node(&node.id).is_some() {
+                    return Err(HistoryError::DuplicateNode(node.id.to_string()));
+                }
+                if *index > graph.nodes.len() {
+                    return Err(HistoryError::MissingNode(node.id.to_string()));
+                }
+                let node = graph.adopt_node(node.clone());
+                graph.nodes.insert(*index, node);
+            }
+            Edit::RemoveNode { index, node } => {
+                if graph.nodes.get(*index).map(|node| &node.id) != Some(&node.id) {
+                    return Err(HistoryError::MissingNode(node.id.to_string()));
+                }
+                graph.nodes.remove(*index);
+            }
+            Edit::InsertEdge { index, edge } => {
+                if *index > graph.edges.len() {
+                    return Err(HistoryError::MissingEdge(*index));
+                }
+                let edge = graph.adopt_edge(edge.clone());
+                graph.edges.insert(*index, edge);
+            }
+            Edit::RemoveEdge { index, edge } => {
+                if graph.edges.get(*index) != Some(edge) {
+                    return Err(HistoryError::MissingEdge(*index));
+                }
+                graph.edges.remove(*index);
+            }
+            Edit::SetNodeAttr { node, key, new, .. } => {
+                let target = graph
+                    .nodes
+                    .iter_mut()
+                    .find(|existing| &existing.id == node)
+                    .ok_or_else(|| HistoryError::MissingNode(node.clone()))?;
+                set_attr(&mut target.attrs, key, new);
+            }
+            Edit::SetEdgeAttr {
+                index, key, new, ..
+            } => {
+                let edge = graph
+                    .edges
+                    .get_mut(*index)
+                    .ok_or(HistoryError::MissingEdge(*index))?;
+                set_attr(&mut edge.attrs, key, new);
+            }
+            Edit::SetGraphAttr { key, new, .. } => set_attr(&mut graph.attrs, key, new),
+            Edit::SetDirected { new, .. } => graph.directed = *new,
+            Edit::SetStrict { new, .. } => graph.strict = *new,
+        }
+
+        Ok(())
+    }
+}
+
+fn set_attr(attrs: &mut AttrMap, key: &str, value: &Option<String>) {
+    match value {
+        Some(value) => {
+            attrs.insert(key.to_string(), value.clone());
+        }
+        None => {
+            attrs.remove(key);
+        }
+    }
+}
+
+/// Edits that are undone and redone together. Single edits made outside a
+/// transaction get one of their own, without a label.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transaction {
+    pub label: Option<String>,
+    pub edits: Vec<Edit>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HistoryError {
+    MissingNode(String),
+    DuplicateNode(String),
+    MissingEdge(usize),
+    /// Undo and redo are not allowed while a transaction is open.
+    TransactionOpen,
+    NoTransaction,
+    Parse {
+        line: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HistoryError::MissingNode(id) => write!(f, "node {id} does not exist"),
+            HistoryError::DuplicateNode(id) => write!(f, "node {id} already exists"),
+            HistoryError::MissingEdge(index) => write!(f, "edge #{index} does not exist"),
+            HistoryError::TransactionOpen => write!(f, "a transaction is still open"),
+            HistoryError::NoTransaction => write!(f, "no transaction is open"),
+            HistoryError::Parse { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl Error for HistoryError {}
+
+/// A graph together with the edits made to it, so they can be undone and
+/// redone. All changes must go through this type to be recorded.
+#[derive(Clone, Debug)]
+pub struct History {
+    graph: Graph,
+    undo: Vec<Transaction>,
+    redo: Vec<Transaction>,
+    open: Option<Transaction>,
+    depth: usize,
+}
+
+impl History {
+    pub fn new(graph: Graph) -> Self {
+        Self {
+            graph,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            open: None,
+            depth: 0,
+        }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    pub fn into_graph(self) -> Graph {
+        self.graph
+    }
+
+    pub fn undo_stack(&self) -> &[Transaction] {
+        &self.undo
+    }
+
+    pub fn redo_stack(&self) -> &[Transaction] {
+        &self.redo
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Starts grouping edits. Transactions nest; only the outermost label is kept.
+    pub fn begin(&mut self, label: &str) {
+        if self.depth == 0 {
+            self.open = Some(Transaction {
+                label: Some(label.to_string()),
+                edits: Vec::new(),
+            });
+        }
+        self.depth += 1;
+    }
+
+    pub fn commit(&mut self) -> Result<(), HistoryError> {
+        if self.depth == 0 {
+            return Err(HistoryError::NoTransaction);
+        }
+        self.depth -= 1;
+        if self.depth == 0 {
+            let transaction = self.open.take().unwrap_or_default();
+            if !transaction.edits.is_empty() {
+                self.redo.clear();
+                self.undo.push(transaction);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reverts and discards everything since the outermost `begin`.
+    pub fn rollback(&mut self) -> Result<(), HistoryError> {
+        let transaction = self.open.take().ok_or(HistoryError::NoTransaction)?;
+        self.depth = 0;
+        revert(&mut self.graph, &transaction)
+    }
+
+    pub fn undo(&mut self) -> Result<bool, HistoryError> {
+        if self.open.is_some() {
+            return Err(HistoryError::TransactionOpen);
+        }
+        let Some(transaction) = self.undo.pop() else {
+            return Ok(false);
+        };
+        revert(&mut self.graph, &transaction)?;
+        self.redo.push(transaction);
+        Ok(true)
+    }
+
+    pub fn redo(&mut self) -> Result<bool, HistoryError> {
+        if self.open.is_some() {
+            return Err(HistoryError::TransactionOpen);
+        }
+        let Some(transaction) = self.redo.pop() else {
+            return Ok(false);
+        };
+        for edit in &transaction.edits {
+            edit.apply(&mut self.graph)?;
+        }
+        self.undo.push(transaction);
+        Ok(true)
+    }
+
+    /// Applies and records an edit. Any redo history is dropped, once the
+    /// transaction the edit belongs to is committed.
+    pub fn apply(&mut self, edit: Edit) -> Result<(), HistoryError> {
+        edit.apply(&mut self.graph)?;
+        match &mut self.open {
+            Some(transaction) => transaction.edits.push(edit),
+            None => {
+                self.redo.clear();
+                self.undo.push(Transaction {
+                    label: None,
+                    edits: vec![edit],
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn add_node(&mut self, node: Node) -> Result<(), HistoryError> {
+        self.apply(Edit::InsertNode {
+            index: self.graph.nodes.len(),
+            node,
+        })
+    }
+
+    /// Removes a declared node and every edge touching it, as one step. On
+    /// failure only this step is reverted, not an enclosing transaction.
+    pub fn remove_node(&mut self, id: &str) -> Result<(), HistoryError> {
+        let index = self
+            .graph
+            .nodes
+            .iter()
+            .position(|node| node.id == id)
+            .ok_or_else(|| HistoryError::MissingNode(id.to_string()))?;
+
+        self.begin(&format!("remove node {id}"));
+        let start = self.open.as_ref().map_or(0, |open| open.edits.len());
+        let result = (|| {
+            for edge_index in (0..self.graph.edges.len()).rev() {
+                let edge = &self.graph.edges[edge_index];
+                if edge.from == id || edge.to == id {
+                    let edge = edge.clone();
+                    self.apply(Edit::RemoveEdge {
+                        index: edge_index,
+                        edge,
+                    })?;
+                }
+            }
+            let node = self.graph.nodes[index].clone();
+            self.apply(Edit::RemoveNode { index, node })
+        })();
+        match result {
+            Ok(()) => self.commit(),
+            Err(error) => {
+                let applied = Transaction {
+                    label: None,
+                    edits: self
+                        .open
+                        .as_mut()
+                        .map(|open| open.edits.split_off(start))
+                        .unwrap_or_default(),
+                };
+                revert(&mut self.graph, &applied)?;
+                self.commit()?;
+                Err(error)
+            }
+        }
+    }
+
+    pub fn set_node_attr(
+        &mut self,
+        id: &str,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), HistoryError> {
+        let node = self
+            .graph
+            .node(id)
+            .ok_or_else(|| HistoryError::MissingNode(id.to_string()))?;
+        self.apply(Edit::SetNodeAttr {
+            node: id.to_string(),
+            key: key.to_string(),
+            old: node.attr(key).map(str::to_string),
+            new: value.map(str::to_string),
+        })
+    }
+
+    /// Appends an edge as given; strict graphs do not merge it into an existing one.
+    pub fn add_edge(&mut self, edge: Edge) -> Result<(), HistoryError> {
+        self.apply(Edit::InsertEdge {
+            index: self.graph.edges.len(),
+            edge,
+        })
+    }
+
+    pub fn remove_edge(&mut self, index: usize) -> Result<(), HistoryError> {
+        let edge = self
+            .graph
+            .edges
+            .get(index)
+            .ok_or(HistoryError::MissingEdge(index))?
+            .clone();
+        self.apply(Edit::RemoveEdge { index, edge })
+    }
+
+    pub fn set_edge_attr(
+        &mut self,
+        index: usize,
+        key: &str,
+        value: Option<&str>,
+    ) -> Result<(), HistoryError> {
+        let edge = self
+            .graph
+            .edges
+            .get(index)
+            .ok_or(HistoryError::MissingEdge(index))?;
+        self.apply(Edit::SetEdgeAttr {
+            index,
+            key: key.to_string(),
+            old: edge.attr(key).map(str::to_string),
+            new: value.map(str::to_string),
+        })
+    }
+
+    pub fn set_graph_attr(&mut self, key: &str, value: Option<&str>) -> Result<(), HistoryError> {
+        self.apply(Edit::SetGraphAttr {
+            key: key.to_string(),
+            old: self.graph.attr(key).map(str::to_string),
+            new: value.map(str::to_string),
+        })
+    }
+
+    pub fn set_directed(&mut self, directed: bool) -> Result<(), HistoryError> {
+        self.apply(Edit::SetDirected {
+            old: self.graph.directed,
+            new: directed,
+        })
+    }
+
+    pub fn set_strict(&mut self, strict: bool) -> Result<(), HistoryError> {
+        self.apply(Edit::SetStrict {
+            old: self.graph.strict,
+            new: strict,
+        })
+    }
+
+    /// Writes the committed undo and redo stacks in a line-based text form.
+    /// Together with the graph the history started from, this is enough to
+    /// rebuild the current state with [`History::restore`].
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("history 1\n");
+        for (kind, stack) in [("undo", &self.undo), ("redo", &self.redo)] {
+            for transaction in stack {
+                text.push_str(kind);
+                text.push(' ');
+                text.push_str(&optional(&transaction.label));
+                text.push('\n');
+                for edit in &transaction.edits {
+                    text.push_str("  ");
+                    text.push_str(&write_edit(edit));
+                    text.push('\n');
+                }
+            }
+        }
+        text
+    }
+
+    /// Replays a saved history onto the graph it started from.
+    pub fn restore(base: Graph, text: &str) -> Result<History, HistoryError> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line));
+        match lines.next() {
+            Some((_, "history 1")) => {}
+            _ => {
+                return Err(HistoryError::Parse {
+                    line: 1,
+                    message: "expected 'history 1'".to_string(),
+                })
+            }
+        }
+
+        let mut undo: Vec<Transaction> = Vec::new();
+        let mut redo: Vec<Transaction> = Vec::new();
+        let mut in_undo = None;
+        for (number, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let parse_error = |message: String| HistoryError::Parse {
+                line: number,
+                message,
+            };
+            let tokens = tokenize(line.trim()).map_err(parse_error)?;
+
+            if line.starts_with(' ') {
+                let transaction = match in_undo {
+                    Some(true) => undo.last_mut(),
+                    Some(false) => redo.last_mut(),
+                    None => None,
+                }
+                .ok_or_else(|| parse_error("edit outside a transaction".to_string()))?;
+                transaction
+                    .edits
+                    .push(read_edit(&tokens).map_err(parse_error)?);
+                continue;
+            }
+
+            let (is_undo, label) = match tokens.as_slice() {
+                [Token::Word(kind), label] if kind == "undo" => (true, label),
+                [Token::Word(kind), label] if kind == "redo" => (false, label),
+                _ => return Err(parse_error("expected 'undo' or 'redo'".to_string())),
+            };
+            let transaction = Transaction {
+                label: read_optional(label).map_err(parse_error)?,
+                edits: Vec::new(),
+            };
+            if is_undo {
+                undo.push(transaction);
+            } else {
+                redo.push(transaction);
+            }
+            in_undo = Some(is_undo);
+        }
+
+        let mut graph = base;
+        for transaction in &undo {
+            for edit in &transaction.edits {
+                edit.apply(&mut graph)?;
+            }
+        }
+
+        Ok(History {
+            graph,
+            undo,
+            redo,
+            open: None,
+            depth: 0,
+        })
+    }
+}
+
+fn revert(graph: &mut Graph, transaction: &Transaction) -> Result<(), HistoryError> {
+    for edit in transaction.edits.iter().rev() {
+        edit.inverse().apply(graph)?;
+    }
+    Ok(())
+}
+
+// The text form: one token per field, strings always quoted and `-` for
+// missing values, e.g. `set-node-attr "a" "color" - "red"`.
+
+fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn optional(value: &Option<String>) -> String {
+    value
+        .as_deref()
+        .map(quote)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn write_attrs(attrs: &AttrMap) -> String {
+    attrs
+        .iter()
+        .map(|(key, value)| format!(" {}={}", quote(key), quote(value)))
+        .collect()
+}
+
+fn write_node(node: &Node) -> String {
+    format!("{}{}", quote(&node.id), write_attrs(&node.attrs))
+}
+
+fn write_edge(edge: &Edge) -> String {
+    let port = |port: &Option<Port>| optional(&port.as_ref().map(Port::to_string));
+    format!(
+        "{} {} {} {} {}{}",
+        quote(&edge.from),
+        quote(&edge.to),
+        optional(&edge.key),
+        port(&edge.tail_port),
+        port(&edge.head_port),
+        write_attrs(&edge.attrs)
+    )
+}
+
+fn write_edit(edit: &Edit) -> String {
+    match edit {
+        Edit::InsertNode { index, node } => format!("insert-node {index} {}", write_node(node)),
+        Edit::RemoveNode { index, node } => format!("remove-node {index} {}", write_node(node)),
+        Edit::InsertEdge { index, edge } => format!("insert-edge {index} {}", write_edge(edge)),
+        Edit::RemoveEdge { index, edge } => format!("remove-edge {index} {}", write_edge(edge)),
+        Edit::SetNodeAttr {
+            node,
+            key,
+            old,
+            new,
+        } => format!(
+            "set-node-attr {} {} {} {}",
+            quote(node),
+            quote(key),
+            optional(old),
+            optional(new)
+        ),
+        Edit::SetEdgeAttr {
+            index,
+            key,
+            old,
+            new,
+        } => format!(
+            "set-edge-attr {index} {} {} {}",
+            quote(key),
+            optional(old),
+            optional(new)
+        ),
+        Edit::SetGraphAttr { key, old, new } => format!(
+            "set-graph-attr {} {} {}",
+            quote(key),
+            optional(old),
+            optional(new)
+        ),
+        Edit::SetDirected { old, new } => format!("set-directed {old} {new}"),
+        Edit::SetStrict { old, new } => format!("set-strict {old} {new}"),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    /// `"key"="value"`
+    Attr(String, String),
+}
+
+fn tokenize(line: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            let key = read_string(&mut chars)?;
+            if chars.peek() == Some(&'=') {
+                chars.next();
+                tokens.push(Token::Attr(key, read_string(&mut chars)?));
+            } else {
+                tokens.push(Token::Str(key));
+            }
+        } else {
+            let mut word = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                word.push(c);
+                chars.next();
+            }
+            tokens.push(Token::Word(word));
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn read_string(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, String> {
+    if chars.next() != Some('"') {
+        return Err("expected a quoted string".to_string());
+    }
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(text),
+            Some('\\') => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some(c) => text.push(c),
+                None => return Err("unterminated string".to_string()),
+            },
+            Some(c) => text.push(c),
+            None => return Err("unterminated string".to_string()),
+        }
+    }
+}
+
+fn read_optional(token: &Token) -> Result<Option<String>, String> {
+    match token {
+        Token::Str(text) => Ok(Some(text.clone())),
+        Token::Word(word) if word == "-" => Ok(None),
+        other => Err(format!("expected a string or '-', found {other:?}")),
+    }
+}
+
+fn read_string_token(token: Option<&Token>) -> Result<String, String> {
+    match token {
+        Some(Token::Str(text)) => Ok(text.clone()),
+        other => Err(format!("expected a string, found {other:?}")),
+    }
+}
+
+fn read_word<T: std::str::FromStr>(token: Option<&Token>) -> Result<T, String> {
+    match token {
+        Some(Token::Word(word)) => word.parse().map_err(|_| format!("invalid value {word}")),
+        other => Err(format!("expected a value, found {other:?}")),
+    }
+}
+
+fn read_attrs(tokens: &[Token]) -> Result<AttrMap, String> {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Attr(key, value) => Ok((key.clone(), value.clone())),
+            other => Err(format!("expected an attribute, found {other:?}")),
+        })
+        .collect()
+}
+
+fn read_node(tokens: &[Token]) -> Result<Node, String> {
+    let mut node = Node::new(&read_string_token(tokens.first())?);
+    node.attrs = read_attrs(tokens.get(1..).unwrap_or_default())?;
+    Ok(node)
+}
+
+fn read_edge(tokens: &[Token]) -> Result<Edge, String> {
+    if tokens.len() < 5 {
+        return Err("incomplete edge".to_string());
+    }
+    let port = |token: &Token| -> Result<Option<Port>, String> {
+        read_optional(token)?
+            .map(|port| port.parse().map_err(|error| format!("{error}")))
+            .transpose()
+    };
+
+    let mut edge = Edge::new(
+        &read_string_token(tokens.first())?,
+        &read_string_token(tokens.get(1))?,
+    );
+    edge.key = read_optional(&tokens[2])?;
+    edge.tail_port = port(&tokens[3])?;
+    edge.head_port = port(&tokens[4])?;
+    edge.attrs = read_attrs(&tokens[5..])?;
+    Ok(edge)
+}
+
+fn read_edit(tokens: &[Token]) -> Result<Edit, String> {
+    let Some(Token::Word(kind)) = tokens.first() else {
+        return Err("expected an edit".to_string());
+    };
+    let args = &tokens[1..];
+    let values = |from: usize| -> Result<(Option<String>, Option<String>), String> {
+        match args.get(from..) {
+            Some([old, new]) => Ok((read_optional(old)?, read_optional(new)?)),
+            _ => Err("expected an old and a new value".to_string()),
+        }
+    };
+
+    Ok(match kind.as_str() {
+        "insert-node" => Edit::InsertNode {
+            index: read_word(args.first())?,
+            node: read_node(&args[1..])?,
+        },
+        "remove-node" => Edit::RemoveNode {
+            index: read_word(args.first())?,
+            node: read_node(&args[1..])?,
+        },
+        "insert-edge" => Edit::InsertEdge {
+            index: read_word(args.first())?,
+            edge: read_edge(&args[1..])?,
+        },
+        "remove-edge" => Edit::RemoveEdge {
+            index: read_word(args.first())?,
+            edge: read_edge(&args[1..])?,
+        },
+        "set-node-attr" => {
+            let (old, new) = values(2)?;
+            Edit::SetNodeAttr {
+                node: read_string_token(args.first())?,
+                key: read_string_token(args.get(1))?,
+                old,
+                new,
+            }
+        }
+        "set-edge-attr" => {
+            let (old, new) = values(2)?;
+            Edit::SetEdgeAttr {
+                index: read_word(args.first())?,
+                key: read_string_token(args.get(1))?,
+                old,
+                new,
+            }
+        }
+        "set-graph-attr" => {
+            let (old, new) = values(1)?;
+            Edit::SetGraphAttr {
+                key: read_string_token(args.first())?,
+                old,
+                new,
+            }
+        }
+        "set-directed" => Edit::SetDirected {
+            old: read_word(args.first())?,
+            new: read_word(args.get(1))?,
+        },
+        "set-strict" => Edit::SetStrict {
+            old: read_word(args.first())?,
+            new: read_word(args.get(1))?,
+        },
+        other => return Err(format!("unknown edit {other}")),
+    })
+}
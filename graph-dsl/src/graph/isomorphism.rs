@@ -0,0 +1,484 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use super::graph_items::attr::AttrMap;
+use super::Graph;
+
+/// Which attributes have to match for two graphs to count as isomorphic.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IsoOptions {
+    pub node_attrs: bool,
+    pub edge_attrs: bool,
+}
+
+impl IsoOptions {
+    pub fn structural() -> Self {
+        Self::default()
+    }
+
+    pub fn with_attrs() -> Self {
+        Self {
+            node_attrs: true,
+            edge_attrs: true,
+        }
+    }
+}
+
+/// The graph flattened into indices, with attributes reduced to comparable labels.
+struct Structure {
+    ids: Vec<String>,
+    labels: Vec<String>,
+    edges: BTreeMap<(usize, usize), Vec<String>>,
+    neighbours: Vec<Vec<usize>>,
+    directed: bool,
+}
+
+const NO_EDGES: &[String] = &[];
+
+impl Structure {
+    fn new(graph: &Graph, options: IsoOptions) -> Self {
+        let ids: Vec<String> = graph.node_ids().into_iter().map(String::from).collect();
+        let index: HashMap<&str, usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (id.as_str(), i))
+            .collect();
+
+        let labels = ids
+            .iter()
+            .map(|id| match graph.node(id) {
+                Some(node) if options.node_attrs => attrs_label(&node.attrs),
+                _ => String::new(),
+            })
+            .collect();
+
+        let mut edges: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+        let mut neighbours = vec![HashSet::new(); ids.len()];
+        for edge in &graph.edges {
+            let (from, to) = (index[edge.from.as_str()], index[edge.to.as_str()]);
+            let key = if graph.directed {
+                (from, to)
+            } else {
+                (from.min(to), from.max(to))
+            };
+            let label = if options.edge_attrs {
+                attrs_label(&edge.attrs)
+            } else {
+                String::new()
+            };
+            edges.entry(key).or_default().push(label);
+            neighbours[from].insert(to);
+            neighbours[to].insert(from);
+        }
+
+        for labels in edges.values_mut() {
+            labels.sort();
+        }
+
+        let neighbours = neighbours
+            .into_iter()
+            .map(|set| {
+                let mut list: Vec<usize> = set.into_iter().collect();
+                list.sort_unstable();
+                list
+            })
+            .collect();
+
+        Self {
+            ids,
+            labels,
+            edges,
+            neighbours,
+            directed: graph.directed,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn edges_between(&self, from: usize, to: usize) -> &[String] {
+        let key = if self.directed {
+            (from, to)
+        } else {
+            (from.min(to), from.max(to))
+        };
+        self.edges.get(&key).map_or(NO_EDGES, Vec::as_slice)
+    }
+
+    fn out_degree(&self, node: usize) -> usize {
+        self.neighbours[node]
+            .iter()
+            .map(|&other| self.edges_between(node, other).len())
+            .sum()
+    }
+
+    fn in_degree(&self, node: usize) -> usize {
+        self.neighbours[node]
+            .iter()
+            .map(|&other| self.edges_between(other, node).len())
+            .sum()
+    }
+
+    /// Everything about a node that an isomorphism has to preserve on its own.
+    fn signature(&self, node: usize) -> (&str, usize, usize, usize, &[String]) {
+        (
+            &self.labels[node],
+            self.out_degree(node),
+            self.in_degree(node),
+            self.neighbours[node].len(),
+            self.edges_between(node, node),
+        )
+    }
+}
+
+fn attrs_label(attrs: &AttrMap) -> String {
+    let mut pairs: Vec<_> = attrs.iter().collect();
+    pairs.sort();
+    format!("{pairs:?}")
+}
+
+/// Finds a mapping from the node ids of `left` to those of `right` that
+/// preserves edges (and attributes, depending on `options`).
+pub fn find_isomorphism(
+    left: &Graph,
+    right: &Graph,
+    options: IsoOptions,
+) -> Option<HashMap<String, String>> {
+    if left.directed != right.directed {
+        return None;
+    }
+
+    let g1 = Structure::new(left, options);
+    let g2 = Structure::new(right, options);
+
+    if g1.len() != g2.len() || left.edges.len() != right.edges.len() {
+        return None;
+    }
+
+    let mut sig1: Vec<_> = (0..g1.len()).map(|n| g1.signature(n)).collect();
+    let mut sig2: Vec<_> = (0..g2.len()).map(|n| g2.signature(n)).collect();
+    sig1.sort();
+    sig2.sort();
+    if sig1 != sig2 {
+        return None;
+    }
+
+    let mut matcher = Matcher {
+        g1: &g1,
+        g2: &g2,
+        order: matching_order(&g1),
+        core1: vec![None; g1.len()],
+        core2: vec![None; g2.len()],
+    };
+
+    if !matcher.search(0) {
+        return None;
+    }
+
+    Some(
+        matcher
+            .core1
+            .iter()
+            .enumerate()
+            .map(|(n1, n2)| (g1.ids[n1].clone(), g2.ids[n2.unwrap()].clone()))
+            .collect(),
+    )
+}
+
+/// Visits nodes breadth-first from the best connected ones, so every node after
+/// the first in a component has an already-mapped neighbour to check against.
+fn matching_order(graph: &Structure) -> Vec<usize> {
+    let mut by_degree: Vec<usize> = (0..graph.len()).collect();
+    by_degree.sort_by_key(|&n| std::cmp::Reverse(graph.neighbours[n].len()));
+
+    let mut visited = vec![false; graph.len()];
+    let mut order = Vec::with_capacity(graph.len());
+    for start in by_degree {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &next in &graph.neighbours[node] {
+                if !visited[next] {
+                    visited[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    order
+}
+
+struct Matcher<'a> {
+    g1: &'a Structure,
+    g2: &'a Structure,
+    order: Vec<usize>,
+    core1: Vec<Option<usize>>,
+    core2: Vec<Option<usize>>,
+}
+
+impl Matcher<'_> {
+    fn search(&mut self, depth: usize) -> bool {
+        let Some(&n1) = self.order.get(depth) else {
+            return true;
+        };
+
+        for n2 in 0..self.g2.len() {
+            if self.core2[n2].is_some() || !self.feasible(n1, n2) {
+                continue;
+            }
+
+            self.core1[n1] = Some(n2);
+            self.core2[n2] = Some(n1);
+            if self.search(depth + 1) {
+                return true;
+            }
+            self.core1[n1] = None;
+            self.core2[n2] = None;
+        }
+
+        false
+    }
+
+    fn feasible(&self, n1: usize, n2: usize) -> bool {
+        if self.g1.signature(n1) != self.g2.signature(n2) {
+            return false;
+        }
+
+        let mut mapped1 = 0;
+        for &m1 in &self.g1.neighbours[n1] {
+            let Some(m2) = self.core1[m1] else {
+                continue;
+            };
+            mapped1 += 1;
+            if self.g1.edges_between(n1, m1) != self.g2.edges_between(n2, m2)
+                || self.g1.edges_between(m1, n1) != self.g2.edges_between(m2, n2)
+            {
+                return false;
+            }
+        }
+
+        let mapped2 = self.g2.neighbours[n2]
+            .iter()
+            .filter(|&&m2| self.core2[m2].is_some())
+            .count();
+
+        mapped1 == mapped2
+    }
+}
+
+/// A string describing the graph up to renaming of its nodes: two graphs have
+/// the same canonical form exactly when they are isomorphic under `options`.
+///
+/// Nodes are ordered by colour refinement, with ties broken by trying each
+/// candidate and keeping the smallest encoding. Automorphisms found along the
+/// way prune equivalent branches.
+pub fn canonical_form(graph: &Graph, options: IsoOptions) -> String {
+    let structure = Structure::new(graph, options);
+    let signatures: Vec<_> = (0..structure.len())
+        .map(|n| structure.signature(n))
+        .collect();
+    let colours = refine(&structure, ranks(&signatures));
+
+    let mut search = CanonicalSearch {
+        graph: &structure,
+        best: None,
+        automorphisms: Vec::new(),
+    };
+    search.explore(colours, &mut Vec::new());
+
+    let (encoding, _) = search.best.unwrap_or_default();
+    encoding
+}
+
+/// A stable 64-bit FNV-1a hash of [`canonical_form`].
+pub fn canonical_hash(graph: &Graph, options: IsoOptions) -> u64 {
+    canonical_form(graph, options)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// Replaces every value by its position among the sorted distinct values.
+fn ranks<T: Ord>(values: &[T]) -> Vec<usize> {
+    let mut sorted: Vec<&T> = values.iter().collect();
+    sorted.sort();
+    sorted.dedup();
+    values
+        .iter()
+        .map(|value| sorted.binary_search(&value).unwrap())
+        .collect()
+}
+
+fn refine(graph: &Structure, mut colours: Vec<usize>) -> Vec<usize> {
+    loop {
+        let signatures: Vec<_> = (0..graph.len())
+            .map(|node| {
+                let mut outgoing: Vec<(usize, &[String])> = Vec::new();
+                let mut incoming: Vec<(usize, &[String])> = Vec::new();
+                for &other in &graph.neighbours[node] {
+                    let out = graph.edges_between(node, other);
+                    if !out.is_empty() {
+                        outgoing.push((colours[other], out));
+                    }
+                    let inc = graph.edges_between(other, node);
+                    if graph.directed && !inc.is_empty() {
+                        incoming.push((colours[other], inc));
+                    }
+                }
+                outgoing.sort();
+                incoming.sort();
+                (colours[node], outgoing, incoming)
+            })
+            .collect();
+
+        let refined = ranks(&signatures);
+        let before = colours.iter().collect::<HashSet<_>>().len();
+        let after = refined.iter().collect::<HashSet<_>>().len();
+        colours = refined;
+        if before == after {
+            return colours;
+        }
+    }
+}
+
+struct CanonicalSearch<'a> {
+    graph: &'a Structure,
+    best: Option<(String, Vec<usize>)>,
+    automorphisms: Vec<Vec<usize>>,
+}
+
+impl CanonicalSearch<'_> {
+    fn explore(&mut self, colours: Vec<usize>, path: &mut Vec<usize>) {
+        let Some(cell) = target_cell(&colours) else {
+            self.leaf(&colours);
+            return;
+        };
+
+        let mut explored: Vec<usize> = Vec::new();
+        for &node in &cell {
+            if explored
+                .iter()
+                .any(|&done| self.same_orbit(done, node, path))
+            {
+                continue;
+            }
+            explored.push(node);
+
+            let individualised: Vec<(usize, bool)> = colours
+                .iter()
+                .enumerate()
+                .map(|(other, &colour)| (colour, other != node))
+                .collect();
+            path.push(node);
+            self.explore(refine(self.graph, ranks(&individualised)), path);
+            path.pop();
+        }
+    }
+
+    fn leaf(&mut self, colours: &[usize]) {
+        let mut order = vec![0; colours.len()];
+        for (node, &colour) in colours.iter().enumerate() {
+            order[colour] = node;
+        }
+        let encoding = encode(self.graph, colours, &order);
+
+        match &self.best {
+            Some((best, best_order)) if *best == encoding => {
+                let mut automorphism = vec![0; order.len()];
+                for (&from, &to) in best_order.iter().zip(&order) {
+                    automorphism[from] = to;
+                }
+                self.automorphisms.push(automorphism);
+            }
+            Some((best, _)) if *best < encoding => {}
+            _ => self.best = Some((encoding, order)),
+        }
+    }
+
+    /// Whether a known automorphism fixing every node on `path` relates `a` and `b`.
+    fn same_orbit(&self, a: usize, b: usize, path: &[usize]) -> bool {
+        let mut parent: Vec<usize> = (0..self.graph.len()).collect();
+        fn find(parent: &mut [usize], node: usize) -> usize {
+            let mut root = node;
+            while parent[root] != root {
+                root = parent[root];
+            }
+            parent[node] = root;
+            root
+        }
+
+        for automorphism in &self.automorphisms {
+            if path.iter().any(|&fixed| automorphism[fixed] != fixed) {
+                continue;
+            }
+            for (from, &to) in automorphism.iter().enumerate() {
+                let (x, y) = (find(&mut parent, from), find(&mut parent, to));
+                parent[x] = y;
+            }
+        }
+
+        find(&mut parent, a) == find(&mut parent, b)
+    }
+}
+
+/// The first smallest colour class with more than one node, if any.
+fn target_cell(colours: &[usize]) -> Option<Vec<usize>> {
+    let mut cells: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (node, &colour) in colours.iter().enumerate() {
+        cells.entry(colour).or_default().push(node);
+    }
+    cells
+        .into_values()
+        .filter(|cell| cell.len() > 1)
+        .min_by_key(|cell| cell.len())
+}
+
+fn encode(graph: &Structure, position: &[usize], order: &[usize]) -> String {
+    let labels: Vec<&str> = order
+        .iter()
+        .map(|&node| graph.labels[node].as_str())
+        .collect();
+    let mut edges: Vec<(usize, usize, &Vec<String>)> = graph
+        .edges
+        .iter()
+        .map(|(&(from, to), labels)| {
+            let (from, to) = (position[from], position[to]);
+            if graph.directed {
+                (from, to, labels)
+            } else {
+                (from.min(to), from.max(to), labels)
+            }
+        })
+        .collect();
+    edges.sort();
+
+    format!(
+        "{}{};{labels:?};{edges:?}",
+        if graph.directed { "digraph" } else { "graph" },
+        order.len()
+    )
+}
+
+impl Graph {
+    pub fn is_isomorphic(&self, other: &Graph) -> bool {
+        find_isomorphism(self, other, IsoOptions::structural()).is_some()
+    }
+
+    pub fn is_isomorphic_with(&self, other: &Graph, options: IsoOptions) -> bool {
+        find_isomorphism(self, other, options).is_some()
+    }
+
+    pub fn canonical_form(&self, options: IsoOptions) -> String {
+        canonical_form(self, options)
+    }
+
+    pub fn canonical_hash(&self, options: IsoOptions) -> u64 {
+        canonical_hash(self, options)
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use super::compact::{unique, CompactGraph, Symbol};
+use super::Graph;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DegreeKind {
+    /// Every edge touching the node. The only kind that matters for undirected graphs.
+    Total,
+    In,
+    Out,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageRankOptions {
+    pub damping: f64,
+    /// Edge attribute holding a numeric weight. Missing or unparsable weights count as 1.
+    pub weight: Option<String>,
+    pub max_iterations: usize,
+    pub tolerance: f64,
+}
+
+impl Default for PageRankOptions {
+    fn default() -> Self {
+        Self {
+            damping: 0.85,
+            weight: None,
+            max_iterations: 100,
+            tolerance: 1e-6,
+        }
+    }
+}
+
+impl PageRankOptions {
+    pub fn with_weight(mut self, attr: &str) -> Self {
+        self.weight = Some(attr.to_string());
+        self
+    }
+}
+
+impl Graph {
+    pub fn degrees(&self, kind: DegreeKind) -> HashMap<String, usize> {
+        let compact = self.to_compact();
+        by_id(&compact, |node| degree(&compact, node, kind))
+    }
+
+    /// How many nodes have each degree.
+    pub fn degree_distribution(&self, kind: DegreeKind) -> BTreeMap<usize, usize> {
+        let compact = self.to_compact();
+        let mut distribution = BTreeMap::new();
+        for node in compact.nodes() {
+            *distribution
+                .entry(degree(&compact, node, kind))
+                .or_default() += 1;
+        }
+        distribution
+    }
+
+    /// Normalised betweenness centrality (Brandes' algorithm, unweighted).
+    pub fn betweenness_centrality(&self) -> HashMap<String, f64> {
+        let compact = self.to_compact();
+        let n = compact.node_count();
+        let mut centrality = vec![0.0; n];
+
+        for source in compact.nodes() {
+            let mut stack = Vec::with_capacity(n);
+            let mut predecessors: Vec<Vec<Symbol>> = vec![Vec::new(); n];
+            let mut paths = vec![0.0; n];
+            let mut distance: Vec<Option<usize>> = vec![None; n];
+            paths[source.index()] = 1.0;
+            distance[source.index()] = Some(0);
+
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                stack.push(node);
+                let next_distance = distance[node.index()].unwrap() + 1;
+                for &next in unique(compact.successors(node)).iter() {
+                    if distance[next.index()].is_none() {
+                        distance[next.index()] = Some(next_distance);
+                        queue.push_back(next);
+                    }
+                    if distance[next.index()] == Some(next_distance) {
+                        paths[next.index()] += paths[node.index()];
+                        predecessors[next.index()].push(node);
+                    }
+                }
+            }
+
+            let mut dependency = vec![0.0; n];
+            while let Some(node) = stack.pop() {
+                for &previous in &predecessors[node.index()] {
+                    dependency[previous.index()] += paths[previous.index()] / paths[node.index()]
+                        * (1.0 + dependency[node.index()]);
+                }
+                if node != source {
+                    centrality[node.index()] += dependency[node.index()];
+                }
+            }
+        }
+
+        let pairs = if n > 2 {
+            ((n - 1) * (n - 2)) as f64
+        } else {
+            1.0
+        };
+        // Undirected paths are counted once from each end, which cancels out
+        // against there being half as many unordered pairs.
+        by_id(&compact, |node| centrality[node.index()] / pairs)
+    }
+
+    /// Closeness centrality over outgoing shortest paths, scaled by the share
+    /// of the graph each node can reach so disconnected graphs stay comparable.
+    pub fn closeness_centrality(&self) -> HashMap<String, f64> {
+        let compact = self.to_compact();
+        let n = compact.node_count();
+
+        by_id(&compact, |node| {
+            let distances = bfs_distances(&compact, node);
+            let reached: Vec<usize> = distances.iter().flatten().copied().collect();
+            let total: usize = reached.iter().sum();
+            if total == 0 || n < 2 {
+                return 0.0;
+            }
+            let others = (reached.len() - 1) as f64;
+            (others / total as f64) * (others / (n - 1) as f64)
+        })
+    }
+
+    pub fn pagerank(&self, options: &PageRankOptions) -> HashMap<String, f64> {
+        let compact = self.to_compact();
+        let n = compact.node_count();
+        if n == 0 {
+            return HashMap::new();
+        }
+
+        let weight = |edge: usize| -> f64 {
+            options
+                .weight
+                .as_deref()
+                .and_then(|attr| self.edges[edge].attr(attr))
+                .and_then(|value| value.parse::<f64>().ok())
+                .unwrap_or(1.0)
+        };
+        let out_weight: Vec<f64> = compact
+            .nodes()
+            .map(|node| {
+                compact
+                    .successor_edges(node)
+                    .iter()
+                    .map(|&edge| weight(edge))
+                    .sum()
+            })
+            .collect();
+
+        let uniform = 1.0 / n as f64;
+        let mut rank = vec![uniform; n];
+        for _ in 0..options.max_iterations {
+            let dangling: f64 = compact
+                .nodes()
+                .filter(|node| out_weight[node.index()] <= 0.0)
+                .map(|node| rank[node.index()])
+                .sum();
+            let base = (1.0 - options.damping) * uniform + options.damping * dangling * uniform;
+
+            let mut next = vec![base; n];
+            for node in compact.nodes() {
+                let total = out_weight[node.index()];
+                if total <= 0.0 {
+                    continue;
+                }
+                let share = options.damping * rank[node.index()] / total;
+                for (&target, &edge) in compact
+                    .successors(node)
+                    .iter()
+                    .zip(compact.successor_edges(node))
+                {
+                    next[target.index()] += share * weight(edge);
+                }
+            }
+
+            let change: f64 = next.iter().zip(&rank).map(|(a, b)| (a - b).abs()).sum();
+            rank = next;
+            if change < options.tolerance * n as f64 {
+                break;
+            }
+        }
+
+        by_id(&compact, |node| rank[node.index()])
+    }
+
+    /// Local clustering coefficient of every node, ignoring edge direction,
+    /// parallel edges and self-loops.
+    pub fn clustering_coefficients(&self) -> HashMap<String, f64> {
+        let compact = self.to_compact();
+        let neighbours: Vec<HashSet<Symbol>> = compact
+            .nodes()
+            .map(|node| {
+                compact
+                    .successors(node)
+                    .iter()
+                    .chain(compact.predecessors(node))
+                    .copied()
+                    .filter(|&other| other != node)
+                    .collect()
+            })
+            .collect();
+
+        by_id(&compact, |node| {
+            let around = &neighbours[node.index()];
+            let k = around.len();
+            if k < 2 {
+                return 0.0;
+            }
+            let links = around
+                .iter()
+                .map(|a| {
+                    neighbours[a.index()]
+                        .iter()
+                        .filter(|b| around.contains(b))
+                        .count()
+                })
+                .sum::<usize>()
+                / 2;
+            links as f64 / (k * (k - 1) / 2) as f64
+        })
+    }
+
+    pub fn average_clustering(&self) -> f64 {
+        let coefficients = self.clustering_coefficients();
+        if coefficients.is_empty() {
+            return 0.0;
+        }
+        coefficients.values().sum::<f64>() / coefficients.len() as f64
+    }
+
+    /// The longest shortest path between any two connected nodes, in edges.
+    /// Pairs that cannot reach each other are ignored; `None` for an empty graph.
+    pub fn diameter(&self) -> Option<usize> {
+        let compact = self.to_compact();
+        compact
+            .nodes()
+            .filter_map(|node| bfs_distances(&compact, node).into_iter().flatten().max())
+            .max()
+    }
+
+    /// Edges present relative to the most a simple graph of this size could hold.
+    pub fn density(&self) -> f64 {
+        let compact = self.to_compact();
+        let n = compact.node_count() as f64;
+        if n < 2.0 {
+            return 0.0;
+        }
+        let possible = n * (n - 1.0);
+        let edges = compact.edge_count() as f64;
+        if compact.is_directed() {
+            edges / possible
+        } else {
+            2.0 * edges / possible
+        }
+    }
+}
+
+fn degree(compact: &CompactGraph, node: Symbol, kind: DegreeKind) -> usize {
+    match kind {
+        DegreeKind::In => compact.in_degree(node),
+        DegreeKind::Out => compact.out_degree(node),
+        DegreeKind::Total if compact.is_directed() => {
+            compact.in_degree(node) + compact.out_degree(node)
+        }
+        DegreeKind::Total => compact.out_degree(node),
+    }
+}
+
+fn by_id<T, F>(compact: &CompactGraph, value: F) -> HashMap<String, T>
+where
+    F: Fn(Symbol) -> T,
+{
+    compact
+        .nodes()
+        .map(|node| (compact.id(node).to_string(), value(node)))
+        .collect()
+}
+
+fn bfs_distances(compact: &CompactGraph, source: Symbol) -> Vec<Option<usize>> {
+    let mut distance = vec![None; compact.node_count()];
+    distance[source.index()] = Some(0);
+    let mut queue = VecDeque::from([source]);
+
+    while let Some(node) = queue.pop_front() {
+        let next_distance = distance[node.index()].unwrap() + 1;
+        for &next in compact.successors(node) {
+            if distance[next.index()].is_none() {
+                distance[next.index()] = Some(next_distance);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distance
+}
@@ -1,15 +1,48 @@
-use std::collections::HashMap;
+use std::collections::HashSet;
 
+use diff::GraphDiff;
+use graph_items::id::{Id, Interner};
 use graph_items::{edge::Edge, node::Node};
 
-use self::graph_items::attr::attr_list_to_hash_map;
+use self::graph_items::attr::{attr, attr_iter, attr_list_to_map, AttrMap};
 
+pub mod coloring;
+pub mod compact;
+pub mod diff;
+pub mod dot;
+pub mod generators;
 pub mod graph_items;
+pub mod history;
+pub mod isomorphism;
+pub mod metrics;
+pub mod multigraph;
+pub mod ops;
+pub mod pattern;
+pub mod ports;
+pub mod query;
+pub mod transitive;
 
+/// Node ids added through the graph's methods are interned, so every node
+/// and edge naming the same node shares one copy of its id.
+#[derive(Clone, Debug)]
 pub struct Graph {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
-    pub attrs: HashMap<String, String>,
+    pub attrs: AttrMap,
+    pub directed: bool,
+    pub strict: bool,
+    ids: Interner,
+}
+
+/// Interning is a storage detail, so graphs compare by content only.
+impl PartialEq for Graph {
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes
+            && self.edges == other.edges
+            && self.attrs == other.attrs
+            && self.directed == other.directed
+            && self.strict == other.strict
+    }
 }
 
 #[allow(clippy::new_without_default)]
@@ -18,26 +51,99 @@ impl Graph {
         Self {
             nodes: Vec::new(),
             edges: Vec::new(),
-            attrs: HashMap::new(),
+            attrs: AttrMap::new(),
+            directed: false,
+            strict: false,
+            ids: Interner::new(),
         }
     }
 
     pub fn with_nodes(mut self, nodes: &Vec<Node>) -> Self {
-        self.nodes.clone_from(nodes);
+        self.nodes.clear();
+        for node in nodes {
+            let node = self.adopt_node(node.clone());
+            self.nodes.push(node);
+        }
         self
     }
 
+    /// Replaces the edges. Duplicates are merged as `add_edge` would, but
+    /// unkeyed edges stay unkeyed.
     pub fn with_edges(mut self, edges: &Vec<Edge>) -> Self {
-        self.edges.clone_from(edges);
+        self.edges.clear();
+        for edge in edges {
+            self.insert_edge(edge.clone());
+        }
         self
     }
 
     pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
-        self.attrs = attr_list_to_hash_map(attrs);
+        self.attrs = attr_list_to_map(attrs);
         self
     }
 
+    pub fn with_directed(mut self, directed: bool) -> Self {
+        self.directed = directed;
+        self
+    }
+
+    pub fn attr(&self, key: &str) -> Option<&str> {
+        attr(&self.attrs, key)
+    }
+
+    /// Graph attributes in the order they were set.
+    pub fn attrs(&self) -> impl Iterator<Item = (&str, &str)> {
+        attr_iter(&self.attrs)
+    }
+
     pub fn node(&self, node_id: &str) -> Option<&Node> {
         self.nodes.iter().find(|node| node.id == node_id)
     }
+
+    /// Ids of all declared nodes followed by any edge endpoints that were not
+    /// declared, each listed once in order of first appearance.
+    pub fn node_ids(&self) -> Vec<&str> {
+        let mut seen = HashSet::new();
+        self.nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .chain(
+                self.edges
+                    .iter()
+                    .flat_map(|edge| [edge.from.as_str(), edge.to.as_str()]),
+            )
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// The id for `name`, shared with the graph's other ids of that name.
+    pub fn intern(&mut self, name: &str) -> Id {
+        self.ids.intern(name)
+    }
+
+    pub fn ids(&self) -> &Interner {
+        &self.ids
+    }
+
+    /// Drops interned ids that no node or edge uses any more.
+    pub fn shrink_ids(&mut self) {
+        self.ids.shrink();
+    }
+
+    /// Points the node's id at the graph's copy of it.
+    pub(crate) fn adopt_node(&mut self, mut node: Node) -> Node {
+        node.id = self.ids.intern(&node.id);
+        node
+    }
+
+    /// Points the edge's endpoints at the graph's copies of them.
+    pub(crate) fn adopt_edge(&mut self, mut edge: Edge) -> Edge {
+        edge.from = self.ids.intern(&edge.from);
+        edge.to = self.ids.intern(&edge.to);
+        edge
+    }
+
+    pub fn diff(&self, target: &Graph) -> GraphDiff {
+        diff::diff(self, target)
+    }
 }
@@ -0,0 +1,118 @@
+use super::graph_items::edge::Edge;
+use super::Graph;
+
+impl Graph {
+    /// Marks the graph as `strict`, collapsing any parallel edges it already
+    /// has. Attributes of collapsed edges are merged, later edges winning.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        if strict {
+            let edges = std::mem::take(&mut self.edges);
+            for edge in edges {
+                self.add_edge(edge);
+            }
+        }
+        self
+    }
+
+    /// Adds an edge and returns its key.
+    ///
+    /// Edges without a key get the lowest unused number among the edges between
+    /// the same nodes. Adding an edge whose key is already taken, or any edge
+    /// between already linked nodes of a `strict` graph, updates the attributes
+    /// of the existing edge instead.
+    pub fn add_edge(&mut self, edge: Edge) -> String {
+        let mut edge = self.adopt_edge(edge);
+        if let Some(position) = self.merge_duplicate(&mut edge) {
+            let other = &mut self.edges[position];
+            return other.key.get_or_insert_with(|| "0".to_string()).clone();
+        }
+
+        let key = match edge.key.take() {
+            Some(key) => key,
+            None => self.next_edge_key(&edge.from, &edge.to),
+        };
+        edge.key = Some(key.clone());
+        self.edges.push(edge);
+        key
+    }
+
+    /// Adds an edge as `add_edge` does, except that an unkeyed edge stays
+    /// unkeyed.
+    pub fn insert_edge(&mut self, edge: Edge) {
+        let mut edge = self.adopt_edge(edge);
+        if self.merge_duplicate(&mut edge).is_none() {
+            self.edges.push(edge);
+        }
+    }
+
+    /// Moves the attributes of `edge` onto the edge it duplicates, if any,
+    /// and returns that edge's position.
+    fn merge_duplicate(&mut self, edge: &mut Edge) -> Option<usize> {
+        if !self.strict && edge.key.is_none() {
+            return None;
+        }
+        let position = self.edges.iter().position(|other| {
+            other.connects(&edge.from, &edge.to, self.directed)
+                && (self.strict || (edge.key.is_some() && other.key == edge.key))
+        })?;
+        let attrs = std::mem::take(&mut edge.attrs);
+        self.edges[position].attrs.extend(attrs);
+        Some(position)
+    }
+
+    /// Every edge between `a` and `b`, in insertion order. Edges of undirected
+    /// graphs match in both directions.
+    pub fn edges_between(&self, a: &str, b: &str) -> Vec<&Edge> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.connects(a, b, self.directed))
+            .collect()
+    }
+
+    pub fn edge(&self, from: &str, to: &str, key: &str) -> Option<&Edge> {
+        self.edges
+            .iter()
+            .find(|edge| edge.connects(from, to, self.directed) && edge.key.as_deref() == Some(key))
+    }
+
+    pub fn edge_mut(&mut self, from: &str, to: &str, key: &str) -> Option<&mut Edge> {
+        let directed = self.directed;
+        self.edges
+            .iter_mut()
+            .find(|edge| edge.connects(from, to, directed) && edge.key.as_deref() == Some(key))
+    }
+
+    /// Removes a single edge, leaving its parallel edges and their keys alone.
+    pub fn remove_edge(&mut self, from: &str, to: &str, key: &str) -> Option<Edge> {
+        let position = self.edges.iter().position(|edge| {
+            edge.connects(from, to, self.directed) && edge.key.as_deref() == Some(key)
+        })?;
+        Some(self.edges.remove(position))
+    }
+
+    /// Gives every unkeyed edge (for example those passed to `with_edges`) a key,
+    /// as `add_edge` would have.
+    pub fn assign_edge_keys(&mut self) {
+        for position in 0..self.edges.len() {
+            if self.edges[position].key.is_none() {
+                let edge = &self.edges[position];
+                let key = self.next_edge_key(&edge.from, &edge.to);
+                self.edges[position].key = Some(key);
+            }
+        }
+    }
+
+    fn next_edge_key(&self, from: &str, to: &str) -> String {
+        let taken: Vec<&str> = self
+            .edges_between(from, to)
+            .into_iter()
+            .filter_map(|edge| edge.key.as_deref())
+            .collect();
+
+        (0..)
+            .map(|n: usize| n.to_string())
+            .find(|key| !taken.contains(&key.as_str()))
+            .unwrap()
+    }
+}
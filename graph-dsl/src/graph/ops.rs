@@ -0,0 +1,319 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use super::diff::{keyed_edges, EdgeKey};
+use super::graph_items::attr::AttrMap;
+use super::graph_items::id::Id;
+use super::graph_items::{edge::Edge, node::Node};
+use super::Graph;
+
+/// Decides which value wins when both graphs set the same attribute differently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    KeepLeft,
+    KeepRight,
+    Fail,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConflictSite {
+    Graph,
+    Node(String),
+    Edge(Box<EdgeKey>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttrConflict {
+    pub site: ConflictSite,
+    pub key: String,
+    pub left: String,
+    pub right: String,
+}
+
+impl fmt::Display for AttrConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.site {
+            ConflictSite::Graph => write!(f, "graph")?,
+            ConflictSite::Node(id) => write!(f, "node {id}")?,
+            ConflictSite::Edge(key) => write!(f, "edge {key}")?,
+        }
+        write!(
+            f,
+            ": conflicting values for {}: {:?} and {:?}",
+            self.key, self.left, self.right
+        )
+    }
+}
+
+impl Error for AttrConflict {}
+
+/// The graphs differ in being directed or strict, named here.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KindMismatch(pub &'static str);
+
+impl fmt::Display for KindMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "only one of the graphs is {}", self.0)
+    }
+}
+
+impl Error for KindMismatch {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UnionError {
+    KindMismatch(KindMismatch),
+    Conflict(AttrConflict),
+}
+
+impl fmt::Display for UnionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnionError::KindMismatch(mismatch) => write!(f, "{mismatch}"),
+            UnionError::Conflict(conflict) => write!(f, "{conflict}"),
+        }
+    }
+}
+
+impl Error for UnionError {}
+
+impl From<KindMismatch> for UnionError {
+    fn from(mismatch: KindMismatch) -> Self {
+        UnionError::KindMismatch(mismatch)
+    }
+}
+
+impl From<AttrConflict> for UnionError {
+    fn from(conflict: AttrConflict) -> Self {
+        UnionError::Conflict(conflict)
+    }
+}
+
+impl Graph {
+    /// Combines both graphs, which must agree on being directed and strict.
+    /// Nodes are matched by id and edges by their endpoints and position
+    /// among parallel edges.
+    pub fn union(&self, other: &Graph, policy: ConflictPolicy) -> Result<Graph, UnionError> {
+        check_kinds(self, other)?;
+
+        let mut result = self.clone();
+        merge_attrs(&mut result.attrs, &other.attrs, policy, || {
+            ConflictSite::Graph
+        })?;
+
+        let mut index: HashMap<Id, usize> = HashMap::new();
+        for (position, node) in result.nodes.iter().enumerate() {
+            index.entry(node.id.clone()).or_insert(position);
+        }
+        for node in &other.nodes {
+            match index.get(node.id.as_str()) {
+                Some(&position) => merge_attrs(
+                    &mut result.nodes[position].attrs,
+                    &node.attrs,
+                    policy,
+                    || ConflictSite::Node(node.id.to_string()),
+                )?,
+                None => {
+                    let node = result.adopt_node(node.clone());
+                    index.insert(node.id.clone(), result.nodes.len());
+                    result.nodes.push(node);
+                }
+            }
+        }
+
+        let positions: HashMap<EdgeKey, usize> = keyed_edges(&self.edges, self.directed)
+            .into_iter()
+            .enumerate()
+            .map(|(position, (key, _))| (key, position))
+            .collect();
+
+        for (key, edge) in keyed_edges(&other.edges, self.directed) {
+            match positions.get(&key) {
+                Some(&position) => merge_attrs(
+                    &mut result.edges[position].attrs,
+                    &edge.attrs,
+                    policy,
+                    || ConflictSite::Edge(Box::new(key.clone())),
+                )?,
+                None => {
+                    let edge = result.adopt_edge(edge.clone());
+                    result.edges.push(edge);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Keeps the nodes and edges present in both graphs, which must agree on
+    /// being directed and strict, along with the attributes both sides
+    /// agree on.
+    pub fn intersection(&self, other: &Graph) -> Result<Graph, KindMismatch> {
+        check_kinds(self, other)?;
+        let other_nodes: HashMap<&str, &Node> = other
+            .nodes
+            .iter()
+            .map(|node| (node.id.as_str(), node))
+            .collect();
+        let other_edges: HashMap<EdgeKey, &Edge> = keyed_edges(&other.edges, self.directed)
+            .into_iter()
+            .collect();
+
+        Ok(Graph {
+            nodes: self
+                .nodes
+                .iter()
+                .filter_map(|node| {
+                    other_nodes.get(node.id.as_str()).map(|other_node| Node {
+                        id: node.id.clone(),
+                        attrs: common_attrs(&node.attrs, &other_node.attrs),
+                    })
+                })
+                .collect(),
+            edges: keyed_edges(&self.edges, self.directed)
+                .into_iter()
+                .filter_map(|(key, edge)| {
+                    other_edges.get(&key).map(|other_edge| Edge {
+                        attrs: common_attrs(&edge.attrs, &other_edge.attrs),
+                        ..edge.clone()
+                    })
+                })
+                .collect(),
+            attrs: common_attrs(&self.attrs, &other.attrs),
+            directed: self.directed,
+            strict: self.strict,
+            ids: self.ids.clone(),
+        })
+    }
+
+    /// Keeps the nodes and edges of this graph that do not appear in `other`,
+    /// which must agree on being directed and strict, dropping edges to the
+    /// nodes removed. Graph attributes are left as they are.
+    pub fn difference(&self, other: &Graph) -> Result<Graph, KindMismatch> {
+        check_kinds(self, other)?;
+        let other_edges: HashSet<EdgeKey> = keyed_edges(&other.edges, self.directed)
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        let other_nodes: HashSet<&str> = other.nodes.iter().map(|node| node.id.as_str()).collect();
+        let removed: HashSet<&str> = self
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .filter(|id| other_nodes.contains(id))
+            .collect();
+
+        Ok(Graph {
+            nodes: self
+                .nodes
+                .iter()
+                .filter(|node| !removed.contains(node.id.as_str()))
+                .cloned()
+                .collect(),
+            edges: keyed_edges(&self.edges, self.directed)
+                .into_iter()
+                .filter(|(key, edge)| {
+                    !other_edges.contains(key)
+                        && !removed.contains(edge.from.as_str())
+                        && !removed.contains(edge.to.as_str())
+                })
+                .map(|(_, edge)| edge.clone())
+                .collect(),
+            attrs: self.attrs.clone(),
+            directed: self.directed,
+            strict: self.strict,
+            ids: self.ids.clone(),
+        })
+    }
+
+    /// The nodes accepted by `predicate` and every edge running between two of them.
+    pub fn induced_subgraph<F>(&self, predicate: F) -> Graph
+    where
+        F: Fn(&Node) -> bool,
+    {
+        let nodes: Vec<Node> = self
+            .nodes
+            .iter()
+            .filter(|node| predicate(node))
+            .cloned()
+            .collect();
+        let ids: HashSet<&str> = nodes.iter().map(|node| node.id.as_str()).collect();
+
+        let edges = self
+            .edges
+            .iter()
+            .filter(|edge| ids.contains(edge.from.as_str()) && ids.contains(edge.to.as_str()))
+            .cloned()
+            .collect();
+
+        Graph {
+            nodes,
+            edges,
+            attrs: self.attrs.clone(),
+            directed: self.directed,
+            strict: self.strict,
+            ids: self.ids.clone(),
+        }
+    }
+
+    /// Shorthand for the subgraph induced by nodes carrying every given attribute.
+    pub fn subgraph_with_attrs(&self, attrs: &[(&str, &str)]) -> Graph {
+        self.induced_subgraph(|node| {
+            attrs
+                .iter()
+                .all(|(key, value)| node.attr(key) == Some(value))
+        })
+    }
+}
+
+fn check_kinds(left: &Graph, right: &Graph) -> Result<(), KindMismatch> {
+    if left.directed != right.directed {
+        return Err(KindMismatch("directed"));
+    }
+    if left.strict != right.strict {
+        return Err(KindMismatch("strict"));
+    }
+    Ok(())
+}
+
+fn merge_attrs<F>(
+    left: &mut AttrMap,
+    right: &AttrMap,
+    policy: ConflictPolicy,
+    site: F,
+) -> Result<(), AttrConflict>
+where
+    F: Fn() -> ConflictSite,
+{
+    for (key, value) in right {
+        match left.get(key) {
+            None => {
+                left.insert(key.clone(), value.clone());
+            }
+            Some(existing) if existing == value => {}
+            Some(existing) => match policy {
+                ConflictPolicy::KeepLeft => {}
+                ConflictPolicy::KeepRight => {
+                    left.insert(key.clone(), value.clone());
+                }
+                ConflictPolicy::Fail => {
+                    return Err(AttrConflict {
+                        site: site(),
+                        key: key.clone(),
+                        left: existing.clone(),
+                        right: value.clone(),
+                    })
+                }
+            },
+        }
+    }
+
+    Ok(())
+}
+
+fn common_attrs(left: &AttrMap, right: &AttrMap) -> AttrMap {
+    left.iter()
+        .filter(|(key, value)| right.get(key) == Some(value))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
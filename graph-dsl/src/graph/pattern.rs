@@ -0,0 +1,582 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use super::compact::{unique, CompactGraph, Symbol};
+use super::graph_items::edge::Edge;
+use super::graph_items::id::Id;
+use super::graph_items::node::Node;
+use super::query::{Lookup, Predicate};
+use super::Graph;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternNode {
+    pub name: String,
+    pub constraint: Predicate,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatternEdge {
+    pub from: String,
+    pub to: String,
+    pub constraint: Predicate,
+}
+
+/// A small graph to look for. Nodes are named so matches and rewrite
+/// templates can refer to them; constraints use the query predicates.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Pattern {
+    pub nodes: Vec<PatternNode>,
+    pub edges: Vec<PatternEdge>,
+}
+
+impl Pattern {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_node(mut self, name: &str, constraint: Predicate) -> Self {
+        self.nodes.push(PatternNode {
+            name: name.to_string(),
+            constraint,
+        });
+        self
+    }
+
+    pub fn with_edge(mut self, from: &str, to: &str, constraint: Predicate) -> Self {
+        self.edges.push(PatternEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            constraint,
+        });
+        self
+    }
+}
+
+/// Where a pattern was found: pattern node names to graph node ids, and the
+/// position in `Graph::edges` of the edge used for each pattern edge.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub nodes: HashMap<String, String>,
+    pub edges: Vec<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateNode {
+    pub name: String,
+    /// Id for a node the template creates. `{name}` is replaced by the id the
+    /// pattern node `name` matched. Without one, the node is called `name`,
+    /// or `name_1`, `name_2`, ... when that is taken.
+    pub id: Option<String>,
+    /// Values may use `{name}` and `{name.attr}` placeholders.
+    pub attrs: Vec<(String, String)>,
+    /// Deleted pattern nodes whose outside edges move over to this node.
+    pub inherits: Vec<String>,
+}
+
+impl TemplateNode {
+    /// Keeps the pattern node `name`, or creates a new one if the pattern has no such node.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            id: None,
+            attrs: Vec::new(),
+            inherits: Vec::new(),
+        }
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    pub fn with_attrs(mut self, attrs: &[(&str, &str)]) -> Self {
+        self.attrs = attrs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        self
+    }
+
+    pub fn with_inherits(mut self, names: &[&str]) -> Self {
+        self.inherits = names.iter().map(|name| name.to_string()).collect();
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TemplateEdge {
+    pub from: String,
+    pub to: String,
+    pub attrs: Vec<(String, String)>,
+}
+
+/// What a match is replaced with. Pattern nodes named here are kept (and get
+/// the template attributes), the rest are deleted along with their edges,
+/// and every matched edge is replaced by the template edges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Template {
+    pub nodes: Vec<TemplateNode>,
+    pub edges: Vec<TemplateEdge>,
+}
+
+impl Template {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_node(mut self, node: TemplateNode) -> Self {
+        self.nodes.push(node);
+        self
+    }
+
+    pub fn with_edge(mut self, from: &str, to: &str, attrs: &[(&str, &str)]) -> Self {
+        self.edges.push(TemplateEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            attrs: attrs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+        });
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub pattern: Pattern,
+    pub replacement: Template,
+}
+
+impl Rule {
+    pub fn new(pattern: Pattern, replacement: Template) -> Self {
+        Self {
+            pattern,
+            replacement,
+        }
+    }
+}
+
+/// A pattern edge refers to a node the pattern does not declare.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPatternNode(pub String);
+
+impl fmt::Display for UnknownPatternNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown node {} in pattern", self.0)
+    }
+}
+
+impl Error for UnknownPatternNode {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RewriteError {
+    /// A template refers to a name that is neither a pattern nor a template node.
+    UnknownName(String),
+    /// A created node would reuse an id that is already taken.
+    DuplicateNode(String),
+    Pattern(UnknownPatternNode),
+}
+
+impl fmt::Display for RewriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RewriteError::UnknownName(name) => write!(f, "unknown name {name} in template"),
+            RewriteError::DuplicateNode(id) => write!(f, "node {id} already exists"),
+            RewriteError::Pattern(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for RewriteError {}
+
+impl From<UnknownPatternNode> for RewriteError {
+    fn from(error: UnknownPatternNode) -> Self {
+        RewriteError::Pattern(error)
+    }
+}
+
+impl Graph {
+    /// Every way the pattern embeds into this graph: distinct pattern nodes go
+    /// to distinct graph nodes and each pattern edge uses its own graph edge.
+    /// Extra edges between matched nodes are allowed.
+    pub fn find_matches(&self, pattern: &Pattern) -> Result<Vec<Match>, UnknownPatternNode> {
+        for edge in &pattern.edges {
+            for name in [&edge.from, &edge.to] {
+                if pattern.nodes.iter().all(|node| &node.name != name) {
+                    return Err(UnknownPatternNode(name.clone()));
+                }
+            }
+        }
+
+        let compact = self.to_compact();
+        let matcher = Matcher {
+            lookup: Lookup::new(self, &compact),
+            compact: &compact,
+            pattern,
+            order: match_order(pattern),
+        };
+        let mut matches = Vec::new();
+        let mut mapping = vec![None; pattern.nodes.len()];
+        matcher.extend(&mut mapping, &mut matches);
+        Ok(matches)
+    }
+
+    /// Replaces every match of the rule's pattern, skipping matches that
+    /// overlap one already taken, and returns how many were replaced. Nothing
+    /// changes if any replacement fails.
+    pub fn rewrite(&mut self, rule: &Rule) -> Result<usize, RewriteError> {
+        let mut taken = HashSet::new();
+        let matches: Vec<Match> = self
+            .find_matches(&rule.pattern)?
+            .into_iter()
+            .filter(|found| {
+                let ids: Vec<&String> = found.nodes.values().collect();
+                if ids.iter().any(|id| taken.contains(*id)) {
+                    return false;
+                }
+                taken.extend(ids.into_iter().cloned());
+                true
+            })
+            .collect();
+
+        let mut result = self.clone();
+        let mut removed_edges = HashSet::new();
+        let mut redirects: HashMap<String, Id> = HashMap::new();
+        let mut removed_nodes: HashSet<String> = HashSet::new();
+        let mut added_edges = Vec::new();
+
+        for found in &matches {
+            let names = resolve_names(self, rule, found, &mut result)?;
+
+            for pattern_node in &rule.pattern.nodes {
+                if rule
+                    .replacement
+                    .nodes
+                    .iter()
+                    .all(|node| node.name != pattern_node.name)
+                {
+                    removed_nodes.insert(found.nodes[&pattern_node.name].clone());
+                }
+            }
+            for node in &rule.replacement.nodes {
+                for inherited in &node.inherits {
+                    let id = found
+                        .nodes
+                        .get(inherited)
+                        .ok_or_else(|| RewriteError::UnknownName(inherited.clone()))?;
+                    redirects.insert(id.clone(), result.intern(&names[&node.name]));
+                }
+            }
+
+            removed_edges.extend(found.edges.iter().copied());
+            for edge in &rule.replacement.edges {
+                let endpoint = |name: &String| {
+                    names
+                        .get(name)
+                        .ok_or_else(|| RewriteError::UnknownName(name.clone()))
+                };
+                let mut new_edge = Edge::new(endpoint(&edge.from)?, endpoint(&edge.to)?);
+                for (key, value) in &edge.attrs {
+                    new_edge
+                        .attrs
+                        .insert(key.clone(), substitute(self, found, value)?);
+                }
+                added_edges.push(result.adopt_edge(new_edge));
+            }
+        }
+
+        result
+            .nodes
+            .retain(|node| !removed_nodes.contains(node.id.as_str()));
+        let mut edges = Vec::with_capacity(result.edges.len() + added_edges.len());
+        for (index, edge) in result.edges.iter().enumerate() {
+            if removed_edges.contains(&index) {
+                continue;
+            }
+            let mut edge = edge.clone();
+            let mut dropped = false;
+            for endpoint in [&mut edge.from, &mut edge.to] {
+                if removed_nodes.contains(endpoint.as_str()) {
+                    match redirects.get(endpoint.as_str()) {
+                        Some(target) => *endpoint = target.clone(),
+                        None => dropped = true,
+                    }
+                }
+            }
+            if !dropped {
+                edges.push(edge);
+            }
+        }
+        edges.extend(added_edges);
+        result.edges = edges;
+
+        *self = result;
+        Ok(matches.len())
+    }
+}
+
+/// Maps every template node name to a graph node id, creating new nodes in
+/// `result` and applying template attributes to kept ones.
+fn resolve_names(
+    graph: &Graph,
+    rule: &Rule,
+    found: &Match,
+    result: &mut Graph,
+) -> Result<HashMap<String, String>, RewriteError> {
+    let mut names = HashMap::new();
+
+    for node in &rule.replacement.nodes {
+        let id = match found.nodes.get(&node.name) {
+            Some(id) => id.clone(),
+            None => {
+                let id = match &node.id {
+                    Some(id) => substitute(graph, found, id)?,
+                    None => fresh_id(result, &node.name),
+                };
+                if result.node_ids().contains(&id.as_str()) {
+                    return Err(RewriteError::DuplicateNode(id));
+                }
+                let created = result.adopt_node(Node::new(&id));
+                result.nodes.push(created);
+                id
+            }
+        };
+
+        let mut attrs = Vec::with_capacity(node.attrs.len());
+        for (key, value) in &node.attrs {
+            attrs.push((key.clone(), substitute(graph, found, value)?));
+        }
+        match result.nodes.iter_mut().find(|existing| existing.id == id) {
+            Some(existing) => existing.attrs.extend(attrs),
+            None if !attrs.is_empty() => {
+                let mut declared = result.adopt_node(Node::new(&id));
+                declared.attrs.extend(attrs);
+                result.nodes.push(declared);
+            }
+            None => {}
+        }
+
+        names.insert(node.name.clone(), id);
+    }
+
+    Ok(names)
+}
+
+/// `name`, or the first of `name_1`, `name_2`, ... that is not taken yet.
+fn fresh_id(graph: &Graph, name: &str) -> String {
+    let taken: HashSet<&str> = graph.node_ids().into_iter().collect();
+    if !taken.contains(name) {
+        return name.to_string();
+    }
+    (1..)
+        .map(|suffix| format!("{name}_{suffix}"))
+        .find(|id| !taken.contains(id.as_str()))
+        .unwrap()
+}
+
+/// Expands `{name}` and `{name.attr}` placeholders against a match.
+fn substitute(graph: &Graph, found: &Match, text: &str) -> Result<String, RewriteError> {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('{') {
+        output.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        let placeholder = &rest[open + 1..open + close];
+        let (name, attr) = match placeholder.split_once('.') {
+            Some((name, attr)) => (name, Some(attr)),
+            None => (placeholder, None),
+        };
+        let id = found
+            .nodes
+            .get(name)
+            .ok_or_else(|| RewriteError::UnknownName(name.to_string()))?;
+        match attr {
+            Some(attr) => {
+                let value = graph.node(id).and_then(|node| node.attr(attr));
+                output.push_str(value.unwrap_or_default());
+            }
+            None => output.push_str(id),
+        }
+        rest = &rest[open + close + 1..];
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Pattern node indices ordered so each one, where possible, is connected to
+/// an earlier one. That lets candidates come from a neighbour list.
+fn match_order(pattern: &Pattern) -> Vec<usize> {
+    let index: HashMap<&str, usize> = pattern
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(position, node)| (node.name.as_str(), position))
+        .collect();
+    let mut order = Vec::with_capacity(pattern.nodes.len());
+    let mut placed = vec![false; pattern.nodes.len()];
+
+    while order.len() < pattern.nodes.len() {
+        let connected = |candidate: usize| {
+            pattern
+                .edges
+                .iter()
+                .filter(|edge| {
+                    let ends = [index.get(edge.from.as_str()), index.get(edge.to.as_str())];
+                    ends.contains(&Some(&candidate))
+                        && ends
+                            .iter()
+                            .flatten()
+                            .any(|&&end| end != candidate && placed[end])
+                })
+                .count()
+        };
+        let next = (0..pattern.nodes.len())
+            .filter(|&candidate| !placed[candidate])
+            .max_by_key(|&candidate| (connected(candidate), std::cmp::Reverse(candidate)))
+            .unwrap();
+        placed[next] = true;
+        order.push(next);
+    }
+
+    order
+}
+
+struct Matcher<'a> {
+    lookup: Lookup<'a>,
+    compact: &'a CompactGraph,
+    pattern: &'a Pattern,
+    order: Vec<usize>,
+}
+
+impl Matcher<'_> {
+    fn position(&self, name: &str) -> Option<usize> {
+        self.pattern.nodes.iter().position(|node| node.name == name)
+    }
+
+    /// Graph edges that could stand in for a pattern edge between two mapped nodes.
+    fn candidate_edges(&self, edge: &PatternEdge, from: Symbol, to: Symbol) -> Vec<usize> {
+        self.compact
+            .successors(from)
+            .iter()
+            .zip(self.compact.successor_edges(from))
+            .filter(|&(&target, &index)| {
+                target == to && self.lookup.edge_matches(index, &edge.constraint)
+            })
+            .map(|(_, &index)| index)
+            .collect()
+    }
+
+    /// The mapped endpoints of a pattern edge, if both are mapped yet.
+    fn endpoints(
+        &self,
+        edge: &PatternEdge,
+        mapping: &[Option<Symbol>],
+    ) -> Option<(Symbol, Symbol)> {
+        let from = mapping[self.position(&edge.from)?]?;
+        let to = mapping[self.position(&edge.to)?]?;
+        Some((from, to))
+    }
+
+    fn extend(&self, mapping: &mut Vec<Option<Symbol>>, matches: &mut Vec<Match>) {
+        let depth = mapping.iter().filter(|node| node.is_some()).count();
+        if depth == self.order.len() {
+            if let Some(edges) = self.assign_edges(mapping) {
+                let nodes = self
+                    .pattern
+                    .nodes
+                    .iter()
+                    .zip(mapping.iter())
+                    .map(|(node, symbol)| {
+                        (
+                            node.name.clone(),
+                            self.compact.id(symbol.unwrap()).to_string(),
+                        )
+                    })
+                    .collect();
+                matches.push(Match { nodes, edges });
+            }
+            return;
+        }
+
+        let current = self.order[depth];
+        let constraint = &self.pattern.nodes[current].constraint;
+        for candidate in self.candidates(current, mapping) {
+            if mapping.contains(&Some(candidate))
+                || !self.lookup.node_matches(candidate, constraint)
+            {
+                continue;
+            }
+            mapping[current] = Some(candidate);
+            let consistent =
+                self.pattern
+                    .edges
+                    .iter()
+                    .all(|edge| match self.endpoints(edge, mapping) {
+                        Some((from, to)) => !self.candidate_edges(edge, from, to).is_empty(),
+                        None => true,
+                    });
+            if consistent {
+                self.extend(mapping, matches);
+            }
+            mapping[current] = None;
+        }
+    }
+
+    /// Graph nodes worth trying for pattern node `current`: neighbours of an
+    /// already mapped pattern neighbour if there is one, otherwise every node.
+    fn candidates(&self, current: usize, mapping: &[Option<Symbol>]) -> Vec<Symbol> {
+        let name = &self.pattern.nodes[current].name;
+        for edge in &self.pattern.edges {
+            if &edge.to == name {
+                if let Some(from) = self.position(&edge.from).and_then(|from| mapping[from]) {
+                    return unique(self.compact.successors(from));
+                }
+            }
+            if &edge.from == name {
+                if let Some(to) = self.position(&edge.to).and_then(|to| mapping[to]) {
+                    return unique(self.compact.predecessors(to));
+                }
+            }
+        }
+        self.compact.nodes().collect()
+    }
+
+    /// Picks a distinct graph edge for every pattern edge, or `None` if the
+    /// available parallel edges run out.
+    fn assign_edges(&self, mapping: &[Option<Symbol>]) -> Option<Vec<usize>> {
+        let options: Option<Vec<Vec<usize>>> = self
+            .pattern
+            .edges
+            .iter()
+            .map(|edge| {
+                let (from, to) = self.endpoints(edge, mapping)?;
+                Some(self.candidate_edges(edge, from, to))
+            })
+            .collect();
+        let mut chosen = Vec::with_capacity(self.pattern.edges.len());
+        choose_distinct(&options?, &mut chosen).then_some(chosen)
+    }
+}
+
+fn choose_distinct(options: &[Vec<usize>], chosen: &mut Vec<usize>) -> bool {
+    let Some(current) = options.get(chosen.len()) else {
+        return true;
+    };
+    for &edge in current {
+        if chosen.contains(&edge) {
+            continue;
+        }
+        chosen.push(edge);
+        if choose_distinct(options, chosen) {
+            return true;
+        }
+        chosen.pop();
+    }
+    false
+}
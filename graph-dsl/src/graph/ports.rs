@@ -0,0 +1,63 @@
+use std::error::Error;
+use std::fmt;
+
+use super::graph_items::label::Label;
+use super::graph_items::node::Node;
+use super::Graph;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownPort {
+    pub node: String,
+    pub port: String,
+    pub known: Vec<String>,
+}
+
+impl fmt::Display for UnknownPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "node {} has no port {:?}", self.node, self.port)?;
+        if !self.known.is_empty() {
+            write!(f, " (available: {})", self.known.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnknownPort {}
+
+impl Graph {
+    /// Checks named edge ports against the ports declared in record and
+    /// HTML-like labels. Nodes without such a label cannot be checked and are
+    /// skipped.
+    pub fn validate_ports(&self) -> Vec<UnknownPort> {
+        let mut errors = Vec::new();
+
+        for edge in &self.edges {
+            for (id, port) in [(&edge.from, &edge.tail_port), (&edge.to, &edge.head_port)] {
+                let Some(name) = port.as_ref().and_then(|port| port.name.as_ref()) else {
+                    continue;
+                };
+                let Some(known) = self.node(id).and_then(declared_ports) else {
+                    continue;
+                };
+                if !known.contains(name) {
+                    errors.push(UnknownPort {
+                        node: id.to_string(),
+                        port: name.clone(),
+                        known,
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// The ports a node's label declares, or `None` if its label cannot declare
+/// any (or does not parse).
+pub fn declared_ports(node: &Node) -> Option<Vec<String>> {
+    match node.label()? {
+        Ok(Label::Text(_)) | Err(_) => None,
+        Ok(label) => Some(label.ports()),
+    }
+}
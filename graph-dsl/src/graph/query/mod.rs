@@ -0,0 +1,550 @@
+use std::cmp::Ordering;
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use super::compact::{CompactGraph, Symbol};
+use super::graph_items::node::Node;
+use super::Graph;
+
+mod parser;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QueryError {
+    Syntax { message: String, position: usize },
+    UnknownNode(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Syntax { message, position } => {
+                write!(f, "{message} at offset {position}")
+            }
+            QueryError::UnknownNode(id) => write!(f, "no node named {id}"),
+        }
+    }
+}
+
+impl Error for QueryError {}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A test against the attributes of a node or edge. The pseudo-attributes
+/// `@id` (nodes) and `@from`, `@to`, `@key` (edges) expose the element itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Predicate {
+    Any,
+    Has(String),
+    /// Values that both parse as numbers are compared numerically. A missing
+    /// attribute only satisfies `!=`.
+    Compare(String, CompareOp, String),
+    Not(Box<Predicate>),
+    And(Box<Predicate>, Box<Predicate>),
+    Or(Box<Predicate>, Box<Predicate>),
+}
+
+impl Predicate {
+    pub fn has(key: &str) -> Self {
+        Predicate::Has(key.to_string())
+    }
+
+    pub fn eq(key: &str, value: &str) -> Self {
+        Predicate::Compare(key.to_string(), CompareOp::Eq, value.to_string())
+    }
+
+    pub fn ne(key: &str, value: &str) -> Self {
+        Predicate::Compare(key.to_string(), CompareOp::Ne, value.to_string())
+    }
+
+    pub fn compare(key: &str, op: CompareOp, value: &str) -> Self {
+        Predicate::Compare(key.to_string(), op, value.to_string())
+    }
+
+    pub fn id(id: &str) -> Self {
+        Predicate::eq("@id", id)
+    }
+
+    pub fn and(self, other: Predicate) -> Self {
+        Predicate::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Predicate) -> Self {
+        Predicate::Or(Box::new(self), Box::new(other))
+    }
+
+    pub fn negate(self) -> Self {
+        Predicate::Not(Box::new(self))
+    }
+
+    pub fn matches<'g>(&self, lookup: &dyn Fn(&str) -> Option<&'g str>) -> bool {
+        match self {
+            Predicate::Any => true,
+            Predicate::Has(key) => lookup(key).is_some(),
+            Predicate::Compare(key, op, expected) => match lookup(key) {
+                None => *op == CompareOp::Ne,
+                Some(actual) => {
+                    let ordering = compare_values(actual, expected);
+                    match op {
+                        CompareOp::Eq => ordering == Ordering::Equal,
+                        CompareOp::Ne => ordering != Ordering::Equal,
+                        CompareOp::Lt => ordering == Ordering::Less,
+                        CompareOp::Le => ordering != Ordering::Greater,
+                        CompareOp::Gt => ordering == Ordering::Greater,
+                        CompareOp::Ge => ordering != Ordering::Less,
+                    }
+                }
+            },
+            Predicate::Not(inner) => !inner.matches(lookup),
+            Predicate::And(a, b) => a.matches(lookup) && b.matches(lookup),
+            Predicate::Or(a, b) => a.matches(lookup) || b.matches(lookup),
+        }
+    }
+}
+
+fn compare_values(actual: &str, expected: &str) -> Ordering {
+    match (actual.parse::<f64>(), expected.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        _ => actual.cmp(expected),
+    }
+}
+
+/// Which edges to follow. Undirected graphs always follow every edge.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Out,
+    In,
+    Both,
+}
+
+/// A chain of node patterns joined by edge patterns, e.g.
+/// `(role=client) -> (role=server) -[proto=sql]-> (role=db)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PathPattern {
+    pub nodes: Vec<Predicate>,
+    /// One fewer than `nodes`; the edge between `nodes[i]` and `nodes[i + 1]`.
+    pub edges: Vec<(Direction, Predicate)>,
+}
+
+impl PathPattern {
+    pub fn new(start: Predicate) -> Self {
+        Self {
+            nodes: vec![start],
+            edges: Vec::new(),
+        }
+    }
+
+    pub fn then(mut self, direction: Direction, edge: Predicate, node: Predicate) -> Self {
+        self.edges.push((direction, edge));
+        self.nodes.push(node);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Filter(Predicate),
+    /// Keeps nodes reachable from `from` over at least one edge.
+    Reachable {
+        from: String,
+        hops: Option<usize>,
+        direction: Direction,
+    },
+    /// Adds every node within `hops` of the selection.
+    Expand {
+        hops: usize,
+        direction: Direction,
+    },
+    /// Narrows the selection to the nodes and edges of matching paths that
+    /// start inside it.
+    Path(PathPattern),
+}
+
+/// A query over the nodes of a graph. Every query starts from all nodes and
+/// applies its steps in order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    pub steps: Vec<Step>,
+}
+
+impl Query {
+    pub fn nodes() -> Self {
+        Self::default()
+    }
+
+    /// Parses the text form, e.g.
+    /// `nodes where shape = box reachable from gateway within 3 hops`.
+    pub fn parse(source: &str) -> Result<Self, QueryError> {
+        parser::parse(source)
+    }
+
+    pub fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    pub fn filter(self, predicate: Predicate) -> Self {
+        self.step(Step::Filter(predicate))
+    }
+
+    pub fn reachable_from(self, id: &str, hops: Option<usize>) -> Self {
+        self.step(Step::Reachable {
+            from: id.to_string(),
+            hops,
+            direction: Direction::Out,
+        })
+    }
+
+    /// Keeps nodes that can reach `id`.
+    pub fn reaching(self, id: &str, hops: Option<usize>) -> Self {
+        self.step(Step::Reachable {
+            from: id.to_string(),
+            hops,
+            direction: Direction::In,
+        })
+    }
+
+    pub fn expand(self, hops: usize) -> Self {
+        self.step(Step::Expand {
+            hops,
+            direction: Direction::Both,
+        })
+    }
+
+    pub fn path(self, pattern: PathPattern) -> Self {
+        self.step(Step::Path(pattern))
+    }
+
+    pub fn run(&self, graph: &Graph) -> Result<Selection, QueryError> {
+        let compact = graph.to_compact();
+        let mut state = State {
+            graph,
+            compact: &compact,
+            lookup: Lookup::new(graph, &compact),
+            nodes: vec![true; compact.node_count()],
+            edges: None,
+        };
+
+        for step in &self.steps {
+            match step {
+                Step::Filter(predicate) => state.filter(predicate),
+                Step::Reachable {
+                    from,
+                    hops,
+                    direction,
+                } => {
+                    let start = compact
+                        .symbol(from)
+                        .ok_or_else(|| QueryError::UnknownNode(from.clone()))?;
+                    let reached = state.reachable(start, *direction, *hops);
+                    for node in compact.nodes() {
+                        state.nodes[node.index()] &= reached[node.index()];
+                    }
+                }
+                Step::Expand { hops, direction } => {
+                    let selected: Vec<Symbol> = compact
+                        .nodes()
+                        .filter(|node| state.nodes[node.index()])
+                        .collect();
+                    let distances = state.distances(&selected, *direction, Some(*hops));
+                    for node in compact.nodes() {
+                        state.nodes[node.index()] = distances[node.index()].is_some();
+                    }
+                }
+                Step::Path(pattern) => state.match_paths(pattern),
+            }
+            state.prune_edges();
+        }
+
+        Ok(state.into_selection())
+    }
+
+    /// Runs the query and projects the result into a new graph.
+    pub fn project(&self, graph: &Graph) -> Result<Graph, QueryError> {
+        Ok(self.run(graph)?.project(graph))
+    }
+}
+
+/// The nodes and edges a query picked out. Edges are positions in
+/// `Graph::edges`; without a path step they are all edges between selected nodes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selection {
+    pub nodes: Vec<String>,
+    pub edges: Vec<usize>,
+}
+
+impl Selection {
+    /// A graph of just the selection, keeping graph attributes and kind.
+    /// Selected nodes that were only edge endpoints become declared nodes.
+    pub fn project(&self, graph: &Graph) -> Graph {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|id| graph.node(id).cloned().unwrap_or_else(|| Node::new(id)))
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .map(|&index| graph.edges[index].clone())
+            .collect();
+
+        Graph {
+            nodes,
+            edges,
+            attrs: graph.attrs.clone(),
+            directed: graph.directed,
+            strict: graph.strict,
+            ids: graph.ids.clone(),
+        }
+    }
+}
+
+impl Graph {
+    /// Parses and runs a query, returning the projected result.
+    pub fn query(&self, source: &str) -> Result<Graph, QueryError> {
+        Query::parse(source)?.project(self)
+    }
+}
+
+/// Answers the keys predicates ask about the nodes and edges of a graph,
+/// finding the declared node behind each symbol once rather than per check.
+pub(crate) struct Lookup<'g> {
+    graph: &'g Graph,
+    compact: &'g CompactGraph,
+    declared: Vec<Option<&'g Node>>,
+}
+
+impl<'g> Lookup<'g> {
+    pub(crate) fn new(graph: &'g Graph, compact: &'g CompactGraph) -> Self {
+        let mut declared = vec![None; compact.node_count()];
+        for node in &graph.nodes {
+            if let Some(symbol) = compact.symbol(&node.id) {
+                declared[symbol.index()].get_or_insert(node);
+            }
+        }
+        Self {
+            graph,
+            compact,
+            declared,
+        }
+    }
+
+    pub(crate) fn node_matches(&self, node: Symbol, predicate: &Predicate) -> bool {
+        let id = self.compact.id(node);
+        let declared = self.declared[node.index()];
+        predicate.matches(&|key| match key {
+            "@id" => Some(id),
+            _ => declared.and_then(|node| node.attr(key)),
+        })
+    }
+
+    pub(crate) fn edge_matches(&self, index: usize, predicate: &Predicate) -> bool {
+        let edge = &self.graph.edges[index];
+        predicate.matches(&|key| match key {
+            "@from" => Some(edge.from.as_str()),
+            "@to" => Some(edge.to.as_str()),
+            "@key" => edge.key.as_deref(),
+            _ => edge.attr(key),
+        })
+    }
+}
+
+struct State<'g> {
+    graph: &'g Graph,
+    compact: &'g CompactGraph,
+    lookup: Lookup<'g>,
+    nodes: Vec<bool>,
+    /// Set once a path step has chosen specific edges.
+    edges: Option<HashSet<usize>>,
+}
+
+impl<'g> State<'g> {
+    fn filter(&mut self, predicate: &Predicate) {
+        for node in self.compact.nodes() {
+            if self.nodes[node.index()] && !self.lookup.node_matches(node, predicate) {
+                self.nodes[node.index()] = false;
+            }
+        }
+    }
+
+    /// Neighbours of `node` in `direction`, paired with the edge used.
+    fn arcs(&self, node: Symbol, direction: Direction) -> Vec<(Symbol, usize)> {
+        let out = || {
+            self.compact
+                .successors(node)
+                .iter()
+                .copied()
+                .zip(self.compact.successor_edges(node).iter().copied())
+        };
+        let incoming = || {
+            self.compact
+                .predecessors(node)
+                .iter()
+                .copied()
+                .zip(self.compact.predecessor_edges(node).iter().copied())
+        };
+
+        match direction {
+            _ if !self.compact.is_directed() => out().collect(),
+            Direction::Out => out().collect(),
+            Direction::In => incoming().collect(),
+            Direction::Both => out().chain(incoming()).collect(),
+        }
+    }
+
+    fn distances(
+        &self,
+        starts: &[Symbol],
+        direction: Direction,
+        hops: Option<usize>,
+    ) -> Vec<Option<usize>> {
+        let mut distance = vec![None; self.compact.node_count()];
+        let mut queue = VecDeque::new();
+        for &start in starts {
+            distance[start.index()] = Some(0);
+            queue.push_back(start);
+        }
+
+        while let Some(node) = queue.pop_front() {
+            let next_distance = distance[node.index()].unwrap() + 1;
+            if hops.is_some_and(|hops| next_distance > hops) {
+                continue;
+            }
+            for (next, _) in self.arcs(node, direction) {
+                if distance[next.index()].is_none() {
+                    distance[next.index()] = Some(next_distance);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        distance
+    }
+
+    /// Nodes reachable from `start` over at least one and at most `hops` edges.
+    fn reachable(&self, start: Symbol, direction: Direction, hops: Option<usize>) -> Vec<bool> {
+        let mut reached = vec![false; self.compact.node_count()];
+        let mut frontier = vec![start];
+        let mut depth = 0;
+
+        while !frontier.is_empty() && hops.is_none_or(|hops| depth < hops) {
+            depth += 1;
+            let mut next = Vec::new();
+            for node in frontier {
+                for (target, _) in self.arcs(node, direction) {
+                    if !reached[target.index()] {
+                        reached[target.index()] = true;
+                        next.push(target);
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        reached
+    }
+
+    fn match_paths(&mut self, pattern: &PathPattern) {
+        let mut nodes = vec![false; self.compact.node_count()];
+        let mut edges = HashSet::new();
+
+        for start in self.compact.nodes() {
+            if !self.nodes[start.index()] || !self.lookup.node_matches(start, &pattern.nodes[0]) {
+                continue;
+            }
+            let mut path = vec![start];
+            let mut used = Vec::new();
+            self.extend_path(pattern, &mut path, &mut used, &mut nodes, &mut edges);
+        }
+
+        self.nodes = nodes;
+        self.edges = Some(match self.edges.take() {
+            Some(previous) => edges.intersection(&previous).copied().collect(),
+            None => edges,
+        });
+    }
+
+    fn extend_path(
+        &self,
+        pattern: &PathPattern,
+        path: &mut Vec<Symbol>,
+        used: &mut Vec<usize>,
+        nodes: &mut [bool],
+        edges: &mut HashSet<usize>,
+    ) {
+        let depth = path.len() - 1;
+        if depth == pattern.edges.len() {
+            for node in path.iter() {
+                nodes[node.index()] = true;
+            }
+            edges.extend(used.iter().copied());
+            return;
+        }
+
+        let (direction, edge_predicate) = &pattern.edges[depth];
+        let node_predicate = &pattern.nodes[depth + 1];
+        for (next, edge) in self.arcs(path[depth], *direction) {
+            if path.contains(&next)
+                || !self.nodes[next.index()]
+                || !self.lookup.edge_matches(edge, edge_predicate)
+                || !self.lookup.node_matches(next, node_predicate)
+            {
+                continue;
+            }
+            path.push(next);
+            used.push(edge);
+            self.extend_path(pattern, path, used, nodes, edges);
+            path.pop();
+            used.pop();
+        }
+    }
+
+    /// Drops chosen edges whose endpoints are no longer selected.
+    fn prune_edges(&mut self) {
+        if let Some(edges) = &mut self.edges {
+            let compact = self.compact;
+            let graph = self.graph;
+            let nodes = &self.nodes;
+            edges.retain(|&index| {
+                let edge = &graph.edges[index];
+                [&edge.from, &edge.to]
+                    .iter()
+                    .all(|id| compact.symbol(id).is_some_and(|node| nodes[node.index()]))
+            });
+        }
+    }
+
+    fn into_selection(self) -> Selection {
+        let nodes = self
+            .compact
+            .nodes()
+            .filter(|node| self.nodes[node.index()])
+            .map(|node| self.compact.id(node).to_string())
+            .collect();
+
+        let mut edges: Vec<usize> = match self.edges {
+            Some(edges) => edges.into_iter().collect(),
+            None => (0..self.graph.edges.len())
+                .filter(|&index| {
+                    let edge = &self.graph.edges[index];
+                    [&edge.from, &edge.to].iter().all(|id| {
+                        self.compact
+                            .symbol(id)
+                            .is_some_and(|node| self.nodes[node.index()])
+                    })
+                })
+                .collect(),
+        };
+        edges.sort_unstable();
+
+        Selection { nodes, edges }
+    }
+}
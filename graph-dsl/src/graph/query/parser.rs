@@ -0,0 +1,389 @@
+//! The text form of queries:
+//!
+//! ```text
+//! query     := "nodes" clause*
+//! clause    := "where" expr
+//!            | "reachable" ("from" | "to") value ["within" NUMBER ["hop" | "hops"]]
+//!            | "expand" [NUMBER ["hop" | "hops"]]
+//!            | "path" "(" [expr] ")" (edge "(" [expr] ")")+
+//! edge      := "->" | "<-" | "--" | "-[" expr "]->" | "<-[" expr "]-" | "-[" expr "]-"
+//! expr      := term ("or" term)*
+//! term      := factor ("and" factor)*
+//! factor    := "not" factor | "(" expr ")" | "has" key | key op value
+//! op        := "=" | "!=" | "<" | "<=" | ">" | ">="
+//! ```
+//!
+//! Keys and values are bare words or double-quoted strings. A `-` right
+//! before a digit belongs to the number, so `x<-1` compares with `-1`.
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use super::{CompareOp, Direction, PathPattern, Predicate, Query, QueryError, Step};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(CompareOp),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    /// `->`
+    Arrow,
+    /// `<-`
+    BackArrow,
+    /// `--`
+    DoubleDash,
+    /// A lone `-`, only valid before `[`.
+    Dash,
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(word) => format!("'{word}'"),
+            Token::Str(value) => format!("{value:?}"),
+            Token::End => "end of query".to_string(),
+            other => format!("{other:?}"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut chars = source.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        chars.next();
+        // A `-` before a digit starts a negative number, not an edge.
+        let rest = &source[start + c.len_utf8()..];
+        let negative = |text: &str| {
+            text.strip_prefix('-')
+                .is_some_and(|digits| digits.starts_with(|c: char| c.is_ascii_digit()))
+        };
+        let mut next_is = |expected: char| {
+            if chars.peek().map(|&(_, c)| c) == Some(expected) {
+                chars.next();
+                true
+            } else {
+                false
+            }
+        };
+
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            '=' => {
+                next_is('=');
+                Token::Op(CompareOp::Eq)
+            }
+            '!' if next_is('=') => Token::Op(CompareOp::Ne),
+            '<' if negative(rest) => Token::Op(CompareOp::Lt),
+            '<' if next_is('-') => Token::BackArrow,
+            '<' if next_is('=') => Token::Op(CompareOp::Le),
+            '<' => Token::Op(CompareOp::Lt),
+            '>' if next_is('=') => Token::Op(CompareOp::Ge),
+            '>' => Token::Op(CompareOp::Gt),
+            '-' if next_is('>') => Token::Arrow,
+            '-' if next_is('-') => Token::DoubleDash,
+            '-' if negative(&source[start..]) => Token::Word(word(c, &mut chars)),
+            '-' => Token::Dash,
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, '"')) => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => value.push(escaped),
+                            None => return Err(syntax("unterminated string", start)),
+                        },
+                        Some((_, c)) => value.push(c),
+                        None => return Err(syntax("unterminated string", start)),
+                    }
+                }
+                Token::Str(value)
+            }
+            c if is_word_char(c) => Token::Word(word(c, &mut chars)),
+            other => return Err(syntax(&format!("unexpected character {other:?}"), start)),
+        };
+        tokens.push((token, start));
+    }
+
+    tokens.push((Token::End, source.len()));
+    Ok(tokens)
+}
+
+/// The word starting with `first`, which has already been consumed.
+fn word(first: char, chars: &mut Peekable<CharIndices>) -> String {
+    let mut word = first.to_string();
+    while let Some(&(_, c)) = chars.peek() {
+        if !is_word_char(c) {
+            break;
+        }
+        word.push(c);
+        chars.next();
+    }
+    word
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '.' | '@')
+}
+
+fn syntax(message: &str, position: usize) -> QueryError {
+    QueryError::Syntax {
+        message: message.to_string(),
+        position,
+    }
+}
+
+pub(super) fn parse(source: &str) -> Result<Query, QueryError> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        position: 0,
+    };
+    let query = parser.query()?;
+    parser.expect(&Token::End)?;
+    Ok(query)
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].0
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens[self.position].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.position].0.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    fn error<T>(&self, expected: &str) -> Result<T, QueryError> {
+        Err(syntax(
+            &format!("expected {expected}, found {}", self.peek().describe()),
+            self.offset(),
+        ))
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<(), QueryError> {
+        if self.peek() == token {
+            self.advance();
+            Ok(())
+        } else {
+            self.error(&token.describe())
+        }
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Word(word) if word == keyword)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), QueryError> {
+        if self.at_keyword(keyword) {
+            self.advance();
+            Ok(())
+        } else {
+            self.error(&format!("'{keyword}'"))
+        }
+    }
+
+    fn value(&mut self) -> Result<String, QueryError> {
+        match self.peek().clone() {
+            Token::Word(word) | Token::Str(word) => {
+                self.advance();
+                Ok(word)
+            }
+            _ => self.error("a name or value"),
+        }
+    }
+
+    fn number(&mut self) -> Result<usize, QueryError> {
+        let offset = self.offset();
+        match self.peek().clone() {
+            Token::Word(word) => {
+                let number = word
+                    .parse()
+                    .map_err(|_| syntax(&format!("expected a number, found '{word}'"), offset))?;
+                self.advance();
+                if self.at_keyword("hop") || self.at_keyword("hops") {
+                    self.advance();
+                }
+                Ok(number)
+            }
+            _ => self.error("a number"),
+        }
+    }
+
+    fn query(&mut self) -> Result<Query, QueryError> {
+        self.keyword("nodes")?;
+        let mut query = Query::nodes();
+
+        while *self.peek() != Token::End {
+            let step = match self.peek() {
+                Token::Word(word) if word == "where" => {
+                    self.advance();
+                    Step::Filter(self.expr()?)
+                }
+                Token::Word(word) if word == "reachable" => {
+                    self.advance();
+                    let direction = if self.at_keyword("to") {
+                        self.advance();
+                        Direction::In
+                    } else {
+                        self.keyword("from")?;
+                        Direction::Out
+                    };
+                    let from = self.value()?;
+                    let hops = if self.at_keyword("within") {
+                        self.advance();
+                        Some(self.number()?)
+                    } else {
+                        None
+                    };
+                    Step::Reachable {
+                        from,
+                        hops,
+                        direction,
+                    }
+                }
+                Token::Word(word) if word == "expand" => {
+                    self.advance();
+                    let hops = match self.peek() {
+                        Token::Word(word) if word.parse::<usize>().is_ok() => self.number()?,
+                        _ => 1,
+                    };
+                    Step::Expand {
+                        hops,
+                        direction: Direction::Both,
+                    }
+                }
+                Token::Word(word) if word == "path" => {
+                    self.advance();
+                    Step::Path(self.path()?)
+                }
+                _ => return self.error("'where', 'reachable', 'expand' or 'path'"),
+            };
+            query = query.step(step);
+        }
+
+        Ok(query)
+    }
+
+    fn path(&mut self) -> Result<PathPattern, QueryError> {
+        let mut pattern = PathPattern::new(self.node_pattern()?);
+        loop {
+            let (direction, edge) = match self.peek() {
+                Token::Arrow => {
+                    self.advance();
+                    (Direction::Out, Predicate::Any)
+                }
+                Token::BackArrow if self.tokens[self.position + 1].0 == Token::LBracket => {
+                    self.advance();
+                    let edge = self.edge_predicate()?;
+                    self.expect(&Token::Dash)?;
+                    (Direction::In, edge)
+                }
+                Token::BackArrow => {
+                    self.advance();
+                    (Direction::In, Predicate::Any)
+                }
+                Token::DoubleDash => {
+                    self.advance();
+                    (Direction::Both, Predicate::Any)
+                }
+                Token::Dash => {
+                    self.advance();
+                    let edge = self.edge_predicate()?;
+                    let direction = match self.peek() {
+                        Token::Arrow => Direction::Out,
+                        Token::Dash => Direction::Both,
+                        _ => return self.error("'->' or '-'"),
+                    };
+                    self.advance();
+                    (direction, edge)
+                }
+                _ if pattern.edges.is_empty() => return self.error("an edge pattern"),
+                _ => return Ok(pattern),
+            };
+            pattern = pattern.then(direction, edge, self.node_pattern()?);
+        }
+    }
+
+    fn edge_predicate(&mut self) -> Result<Predicate, QueryError> {
+        self.expect(&Token::LBracket)?;
+        let predicate = self.expr()?;
+        self.expect(&Token::RBracket)?;
+        Ok(predicate)
+    }
+
+    fn node_pattern(&mut self) -> Result<Predicate, QueryError> {
+        self.expect(&Token::LParen)?;
+        if *self.peek() == Token::RParen {
+            self.advance();
+            return Ok(Predicate::Any);
+        }
+        let predicate = self.expr()?;
+        self.expect(&Token::RParen)?;
+        Ok(predicate)
+    }
+
+    fn expr(&mut self) -> Result<Predicate, QueryError> {
+        let mut predicate = self.term()?;
+        while self.at_keyword("or") {
+            self.advance();
+            predicate = predicate.or(self.term()?);
+        }
+        Ok(predicate)
+    }
+
+    fn term(&mut self) -> Result<Predicate, QueryError> {
+        let mut predicate = self.factor()?;
+        while self.at_keyword("and") {
+            self.advance();
+            predicate = predicate.and(self.factor()?);
+        }
+        Ok(predicate)
+    }
+
+    fn factor(&mut self) -> Result<Predicate, QueryError> {
+        if self.at_keyword("not") {
+            self.advance();
+            return Ok(self.factor()?.negate());
+        }
+        if self.at_keyword("has") {
+            self.advance();
+            return Ok(Predicate::Has(self.value()?));
+        }
+        if *self.peek() == Token::LParen {
+            self.advance();
+            let predicate = self.expr()?;
+            self.expect(&Token::RParen)?;
+            return Ok(predicate);
+        }
+
+        let key = self.value()?;
+        let op = match self.peek() {
+            Token::Op(op) => *op,
+            _ => return self.error("a comparison"),
+        };
+        self.advance();
+        Ok(Predicate::Compare(key, op, self.value()?))
+    }
+}
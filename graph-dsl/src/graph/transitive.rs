@@ -0,0 +1,148 @@
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+use std::fmt;
+
+use super::compact::{CompactGraph, Symbol};
+use super::graph_items::edge::Edge;
+use super::Graph;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TransitiveError {
+    Undirected,
+    /// Reduction is only unique for acyclic graphs; `node` lies on a cycle.
+    Cyclic {
+        node: String,
+    },
+}
+
+impl fmt::Display for TransitiveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransitiveError::Undirected => write!(f, "graph is not directed"),
+            TransitiveError::Cyclic { node } => write!(f, "graph has a cycle through {node}"),
+        }
+    }
+}
+
+impl Error for TransitiveError {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransitiveOptions {
+    /// When set, changed edges are kept in the result with this attribute set
+    /// to `"added"` or `"removed"` instead of silently appearing or vanishing.
+    pub mark: Option<String>,
+}
+
+impl TransitiveOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_mark(mut self, attr: &str) -> Self {
+        self.mark = Some(attr.to_string());
+        self
+    }
+}
+
+impl Graph {
+    /// Removes every edge implied by a longer path. Parallel edges count as
+    /// redundant too, so only the first of them is kept.
+    pub fn transitive_reduction(
+        &self,
+        options: &TransitiveOptions,
+    ) -> Result<Graph, TransitiveError> {
+        if !self.directed {
+            return Err(TransitiveError::Undirected);
+        }
+        let compact = self.to_compact();
+        let reach = reachability(&compact);
+        if let Some(node) = compact
+            .nodes()
+            .find(|node| reach[node.index()][node.index()])
+        {
+            return Err(TransitiveError::Cyclic {
+                node: compact.id(node).to_string(),
+            });
+        }
+
+        let mut redundant = HashSet::new();
+        for node in compact.nodes() {
+            let mut seen = HashSet::new();
+            for (&target, &edge) in compact
+                .successors(node)
+                .iter()
+                .zip(compact.successor_edges(node))
+            {
+                let implied = compact
+                    .successors(node)
+                    .iter()
+                    .any(|&other| other != target && reach[other.index()][target.index()]);
+                if implied || !seen.insert(target) {
+                    redundant.insert(edge);
+                }
+            }
+        }
+
+        let mut result = self.clone();
+        result.edges = Vec::with_capacity(self.edges.len());
+        for (index, edge) in self.edges.iter().enumerate() {
+            if !redundant.contains(&index) {
+                result.edges.push(edge.clone());
+            } else if let Some(mark) = &options.mark {
+                let mut edge = edge.clone();
+                edge.attrs.insert(mark.clone(), "removed".to_string());
+                result.edges.push(edge);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Adds an edge for every pair connected by a path. Nodes on a cycle gain
+    /// a self-loop, since they can reach themselves.
+    pub fn transitive_closure(
+        &self,
+        options: &TransitiveOptions,
+    ) -> Result<Graph, TransitiveError> {
+        if !self.directed {
+            return Err(TransitiveError::Undirected);
+        }
+        let compact = self.to_compact();
+        let reach = reachability(&compact);
+
+        let mut result = self.clone();
+        for node in compact.nodes() {
+            for target in compact.nodes() {
+                if !reach[node.index()][target.index()]
+                    || compact.successors(node).contains(&target)
+                {
+                    continue;
+                }
+                let mut edge = Edge::new(compact.id(node), compact.id(target));
+                if let Some(mark) = &options.mark {
+                    edge.attrs.insert(mark.clone(), "added".to_string());
+                }
+                let edge = result.adopt_edge(edge);
+                result.edges.push(edge);
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// `reach[a][b]` is whether `b` can be reached from `a` over at least one edge.
+fn reachability(compact: &CompactGraph) -> Vec<Vec<bool>> {
+    compact
+        .nodes()
+        .map(|source| {
+            let mut reached = vec![false; compact.node_count()];
+            let mut queue: VecDeque<Symbol> = compact.successors(source).iter().copied().collect();
+            while let Some(node) = queue.pop_front() {
+                if !reached[node.index()] {
+                    reached[node.index()] = true;
+                    queue.extend(compact.successors(node));
+                }
+            }
+            reached
+        })
+        .collect()
+}
@@ -0,0 +1,53 @@
+use graph_dsl::graph::graph_items::attr::{attr_list_to_map, AttrMap};
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::Graph;
+
+const ATTRS: [(&str, &str); 4] = [("z", "1"), ("a", "2"), ("m", "3"), ("b", "4")];
+
+#[test]
+fn node_attributes_keep_insertion_order() {
+    let node = Node::new("a").with_attrs(&ATTRS);
+
+    assert_eq!(node.attrs().collect::<Vec<_>>(), ATTRS);
+}
+
+#[test]
+fn edge_attributes_keep_insertion_order() {
+    let edge = Edge::new("a", "b").with_attrs(&ATTRS);
+
+    assert_eq!(edge.attrs().collect::<Vec<_>>(), ATTRS);
+}
+
+#[test]
+fn graph_attributes_keep_insertion_order() {
+    let graph = Graph::new().with_attrs(&ATTRS);
+
+    assert_eq!(graph.attrs().collect::<Vec<_>>(), ATTRS);
+    assert_eq!(graph.attr("m"), Some("3"));
+}
+
+#[test]
+fn overwriting_keeps_position_and_removing_keeps_the_rest_in_order() {
+    let mut attrs = attr_list_to_map(&ATTRS);
+
+    assert_eq!(
+        attrs.insert("a".to_string(), "5".to_string()),
+        Some("2".to_string())
+    );
+    assert_eq!(attrs.remove("m"), Some("3".to_string()));
+    attrs.insert("c".to_string(), "6".to_string());
+
+    let keys: Vec<&String> = attrs.keys().collect();
+    assert_eq!(keys, ["z", "a", "b", "c"]);
+    assert_eq!(attrs["a"], "5");
+}
+
+#[test]
+fn equality_ignores_order() {
+    let forwards = attr_list_to_map(&[("a", "1"), ("b", "2")]);
+    let backwards = attr_list_to_map(&[("b", "2"), ("a", "1")]);
+
+    assert_eq!(forwards, backwards);
+    assert_ne!(forwards, AttrMap::new());
+}
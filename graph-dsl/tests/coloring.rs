@@ -0,0 +1,153 @@
+use graph_dsl::graph::coloring::{Bipartition, ColoringStrategy, OddCycle};
+use graph_dsl::graph::generators::Generator;
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::Graph;
+
+fn is_proper(graph: &Graph, strategy: ColoringStrategy) -> bool {
+    let colours = graph.coloring(strategy);
+    graph.edges.iter().all(|edge| {
+        edge.from == edge.to || colours[edge.from.as_str()] != colours[edge.to.as_str()]
+    })
+}
+
+#[test]
+fn splits_bipartite_graphs() {
+    let graph = Generator::new().cycle(4);
+
+    assert_eq!(
+        graph.bipartition(),
+        Ok(Bipartition {
+            left: vec!["n0".to_string(), "n2".to_string()],
+            right: vec!["n1".to_string(), "n3".to_string()],
+        })
+    );
+    assert!(Generator::new().grid(3, 4).is_bipartite());
+}
+
+#[test]
+fn odd_cycles_prove_graphs_are_not_bipartite() {
+    let graph = Generator::new().cycle(5);
+    let cycle = graph.bipartition().unwrap_err();
+
+    assert_eq!(cycle.nodes.len(), 5);
+    for (index, id) in cycle.nodes.iter().enumerate() {
+        let next = &cycle.nodes[(index + 1) % cycle.nodes.len()];
+        assert!(graph
+            .edges
+            .iter()
+            .any(|edge| edge.connects(id, next, false)));
+    }
+
+    let looped = Graph::new().with_edges(&vec![Edge::new("a", "b"), Edge::new("b", "b")]);
+    assert_eq!(
+        looped.bipartition(),
+        Err(OddCycle {
+            nodes: vec!["b".to_string()]
+        })
+    );
+}
+
+#[test]
+fn direction_is_ignored_for_bipartiteness() {
+    let graph = Graph::new().with_directed(true).with_edges(&vec![
+        Edge::new("a", "b"),
+        Edge::new("c", "b"),
+        Edge::new("c", "a"),
+    ]);
+
+    assert_eq!(graph.bipartition().unwrap_err().nodes.len(), 3);
+}
+
+#[test]
+fn finds_maximum_matchings() {
+    let graph = Graph::new().with_edges(&vec![
+        Edge::new("alice", "rust"),
+        Edge::new("alice", "go"),
+        Edge::new("bob", "rust"),
+        Edge::new("carol", "go"),
+        Edge::new("carol", "zig"),
+        Edge::new("dave", "zig"),
+    ]);
+
+    let matching = graph.maximum_matching().unwrap();
+    assert_eq!(matching.len(), 3);
+    for (left, right) in &matching {
+        assert!(graph
+            .edges
+            .iter()
+            .any(|edge| edge.connects(left, right, false)));
+    }
+    let mut rights: Vec<&String> = matching.iter().map(|(_, right)| right).collect();
+    rights.sort();
+    rights.dedup();
+    assert_eq!(rights.len(), 3);
+
+    assert!(Generator::new().cycle(3).maximum_matching().is_err());
+    assert_eq!(
+        Generator::new()
+            .grid(2, 3)
+            .maximum_matching()
+            .unwrap()
+            .len(),
+        3
+    );
+}
+
+#[test]
+fn colourings_are_proper() {
+    let graphs = [
+        Generator::new().complete(5),
+        Generator::new().cycle(7),
+        Generator::new().with_seed(3).erdos_renyi(30, 0.2),
+    ];
+
+    for graph in &graphs {
+        assert!(is_proper(graph, ColoringStrategy::Greedy));
+        assert!(is_proper(graph, ColoringStrategy::Dsatur));
+    }
+    assert_eq!(
+        Generator::new()
+            .complete(5)
+            .coloring(ColoringStrategy::Dsatur)
+            .values()
+            .max(),
+        Some(&4)
+    );
+}
+
+#[test]
+fn dsatur_colours_crown_graphs_with_two_colours() {
+    // Declaring a0, b0, a1, b1, ... makes greedy use a colour per pair.
+    let nodes = (0..4)
+        .flat_map(|i| [Node::new(&format!("a{i}")), Node::new(&format!("b{i}"))])
+        .collect();
+    let edges = (0..4)
+        .flat_map(|i| (0..4).filter(move |&j| j != i).map(move |j| (i, j)))
+        .map(|(i, j)| Edge::new(&format!("a{i}"), &format!("b{j}")))
+        .collect();
+    let graph = Graph::new().with_nodes(&nodes).with_edges(&edges);
+
+    let greedy = graph.coloring(ColoringStrategy::Greedy);
+    let dsatur = graph.coloring(ColoringStrategy::Dsatur);
+    assert_eq!(greedy.values().max(), Some(&3));
+    assert_eq!(dsatur.values().max(), Some(&1));
+}
+
+#[test]
+fn writes_colours_into_an_attribute() {
+    let mut graph = Generator::new().path(3);
+
+    let used = graph.color_nodes(ColoringStrategy::Dsatur, "fillcolor", &["red", "blue"]);
+
+    assert_eq!(used, 2);
+    assert_eq!(graph.node("n0").unwrap().attr("fillcolor"), Some("blue"));
+    assert_eq!(graph.node("n1").unwrap().attr("fillcolor"), Some("red"));
+
+    let mut numbered = Graph::new().with_edges(&vec![Edge::new("a", "b")]);
+    numbered.color_nodes(ColoringStrategy::Greedy, "colorscheme_index", &[]);
+    assert_eq!(
+        numbered.node("b").unwrap().attr("colorscheme_index"),
+        Some("1")
+    );
+}
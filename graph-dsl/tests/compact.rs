@@ -0,0 +1,106 @@
+use graph_dsl::graph::compact::SymbolTable;
+use graph_dsl::graph::generators::Generator;
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::Graph;
+
+fn pipeline() -> Graph {
+    Graph::new()
+        .with_directed(true)
+        .with_nodes(&vec![Node::new("a"), Node::new("b")])
+        .with_edges(&vec![
+            Edge::new("a", "b"),
+            Edge::new("a", "c"),
+            Edge::new("b", "c"),
+            Edge::new("a", "b").with_attrs(&[("weight", "3")]),
+        ])
+}
+
+#[test]
+fn symbol_table_interns_each_name_once() {
+    let mut table = SymbolTable::new();
+
+    let a = table.intern("a");
+    let b = table.intern("b");
+
+    assert_eq!(table.intern("a"), a);
+    assert_ne!(a, b);
+    assert_eq!(table.resolve(b), "b");
+    assert_eq!(table.get("c"), None);
+    assert_eq!(table.len(), 2);
+}
+
+#[test]
+fn graphs_share_one_copy_of_each_id() {
+    let mut graph = pipeline();
+
+    assert_eq!(graph.ids().len(), 3);
+    assert!(graph.edges[0].from.ptr_eq(&graph.nodes[0].id));
+    assert!(graph.edges[1].to.ptr_eq(&graph.edges[2].to));
+
+    graph.add_edge(Edge::new("c", "d"));
+    assert!(graph.edges[4].from.ptr_eq(&graph.edges[1].to));
+    assert_eq!(graph.ids().len(), 4);
+
+    graph.edges.pop();
+    graph.shrink_ids();
+    assert_eq!(graph.ids().len(), 3);
+}
+
+#[test]
+fn interns_declared_nodes_then_edge_endpoints() {
+    let compact = pipeline().to_compact();
+
+    let ids: Vec<&str> = compact.nodes().map(|node| compact.id(node)).collect();
+    assert_eq!(ids, ["a", "b", "c"]);
+    assert_eq!(compact.node_count(), 3);
+    assert_eq!(compact.edge_count(), 4);
+}
+
+#[test]
+fn directed_adjacency_keeps_both_directions_apart() {
+    let graph = pipeline();
+    let compact = graph.to_compact();
+    let a = compact.symbol("a").unwrap();
+    let b = compact.symbol("b").unwrap();
+    let c = compact.symbol("c").unwrap();
+
+    assert_eq!(compact.successors(a), [b, c, b]);
+    assert_eq!(compact.predecessors(c), [a, b]);
+    assert_eq!(compact.out_degree(a), 3);
+    assert_eq!(compact.in_degree(a), 0);
+
+    let heavy = compact.successor_edges(a)[2];
+    assert_eq!(graph.edges[heavy].attr("weight"), Some("3"));
+}
+
+#[test]
+fn undirected_adjacency_is_symmetric() {
+    let graph = Generator::new().grid(3, 3);
+    let compact = graph.to_compact();
+
+    for node in compact.nodes() {
+        assert_eq!(compact.successors(node), compact.predecessors(node));
+        for &other in compact.successors(node) {
+            assert!(compact.successors(other).contains(&node));
+        }
+    }
+
+    let centre = compact.symbol("n1_1").unwrap();
+    assert_eq!(compact.out_degree(centre), 4);
+}
+
+#[test]
+fn self_loops_are_stored_once() {
+    let graph = Graph::new().with_edges(&vec![Edge::new("a", "a")]);
+    let compact = graph.to_compact();
+    let a = compact.symbol("a").unwrap();
+
+    assert_eq!(compact.successors(a), [a]);
+    assert_eq!(compact.out_degree(a), 2);
+    assert_eq!(compact.in_degree(a), 2);
+
+    let directed = graph.with_directed(true).to_compact();
+    assert_eq!(directed.out_degree(a), 1);
+    assert_eq!(directed.in_degree(a), 1);
+}
@@ -0,0 +1,115 @@
+use graph_dsl::graph::generators::{Generator, Rng};
+use graph_dsl::graph::graph_items::edge::Edge;
+
+fn degree(edges: &[Edge], id: &str) -> usize {
+    edges
+        .iter()
+        .filter(|edge| edge.from == id || edge.to == id)
+        .count()
+}
+
+#[test]
+fn complete_graph_links_every_pair() {
+    let graph = Generator::new().complete(5);
+
+    assert_eq!(graph.nodes.len(), 5);
+    assert_eq!(graph.edges.len(), 10);
+}
+
+#[test]
+fn paths_cycles_and_stars() {
+    let mut generator = Generator::new();
+
+    assert_eq!(generator.path(4).edges.len(), 3);
+    assert_eq!(generator.cycle(4).edges.len(), 4);
+
+    let star = generator.star(6);
+    assert_eq!(star.nodes.len(), 7);
+    assert_eq!(degree(&star.edges, "n0"), 6);
+}
+
+#[test]
+fn grid_uses_row_and_column_ids() {
+    let graph = Generator::new().with_prefix("cell").grid(2, 3);
+
+    assert_eq!(graph.nodes.len(), 6);
+    assert_eq!(graph.edges.len(), 7);
+    assert!(graph.node("cell1_2").is_some());
+    assert_eq!(degree(&graph.edges, "cell1_1"), 3);
+}
+
+#[test]
+fn binary_tree_has_heap_layout() {
+    let graph = Generator::new().binary_tree(7);
+
+    assert_eq!(graph.edges.len(), 6);
+    assert_eq!(graph.edges[0], Edge::new("n0", "n1"));
+    assert_eq!(graph.edges[5], Edge::new("n2", "n6"));
+}
+
+#[test]
+fn random_graphs_depend_only_on_the_seed() {
+    let first = Generator::new().with_seed(7).erdos_renyi(30, 0.2);
+    let second = Generator::new().with_seed(7).erdos_renyi(30, 0.2);
+    let other = Generator::new().with_seed(8).erdos_renyi(30, 0.2);
+
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+#[test]
+fn erdos_renyi_extremes() {
+    let mut generator = Generator::new();
+
+    assert!(generator.erdos_renyi(10, 0.0).edges.is_empty());
+    assert_eq!(generator.erdos_renyi(10, 1.0).edges.len(), 45);
+}
+
+#[test]
+fn barabasi_albert_adds_m_edges_per_node() {
+    let graph = Generator::new().with_seed(3).barabasi_albert(50, 2);
+
+    assert_eq!(graph.nodes.len(), 50);
+    assert_eq!(graph.edges.len(), 2 + 2 * (50 - 3));
+    assert!(graph.edges.iter().all(|edge| edge.from != edge.to));
+}
+
+#[test]
+fn random_attributes_come_from_the_given_values() {
+    let graph = Generator::new()
+        .with_seed(1)
+        .with_random_attr("color", &["red", "blue"])
+        .path(20);
+
+    assert!(graph
+        .nodes
+        .iter()
+        .all(|node| matches!(node.attr("color"), Some("red") | Some("blue"))));
+}
+
+#[test]
+fn rng_floats_stay_in_unit_interval() {
+    let mut rng = Rng::seeded(42);
+
+    assert!((0..1000)
+        .map(|_| rng.next_f64())
+        .all(|x| (0.0..1.0).contains(&x)));
+}
+
+#[test]
+fn rng_indices_cover_the_whole_range() {
+    let mut rng = Rng::seeded(7);
+    let mut counts = [0; 3];
+    for _ in 0..3000 {
+        counts[rng.below(3)] += 1;
+    }
+
+    assert!(counts.iter().all(|&count| (900..1100).contains(&count)));
+    assert_eq!(rng.below(1), 0);
+}
+
+#[test]
+#[should_panic(expected = "barabasi_albert needs 0 < m < n")]
+fn barabasi_albert_rejects_too_few_nodes() {
+    Generator::new().barabasi_albert(2, 2);
+}
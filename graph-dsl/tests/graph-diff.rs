@@ -0,0 +1,239 @@
+use graph_dsl::graph::diff::{AttrChange, EdgeKey, PatchError, PatchOp};
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::Graph;
+
+fn source() -> Graph {
+    Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("color", "green")]),
+            Node::new("b"),
+            Node::new("c"),
+        ])
+        .with_edges(&vec![
+            Edge::new("a", "b"),
+            Edge::new("b", "c").with_attrs(&[("style", "dashed")]),
+        ])
+        .with_attrs(&[("title", "Before")])
+}
+
+fn target() -> Graph {
+    Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("color", "blue")]),
+            Node::new("c"),
+            Node::new("d").with_attrs(&[("shape", "box")]),
+        ])
+        .with_edges(&vec![
+            Edge::new("a", "b").with_attrs(&[("weight", "2")]),
+            Edge::new("c", "d"),
+        ])
+        .with_attrs(&[("title", "After"), ("rankdir", "LR")])
+}
+
+#[test]
+fn identical_graphs_have_an_empty_diff() {
+    let diff = source().diff(&source());
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "no changes\n");
+}
+
+#[test]
+fn diff_reports_added_and_removed_nodes() {
+    let diff = source().diff(&target());
+
+    assert_eq!(diff.removed_nodes, vec![Node::new("b")]);
+    assert_eq!(
+        diff.added_nodes,
+        vec![Node::new("d").with_attrs(&[("shape", "box")])]
+    );
+}
+
+#[test]
+fn diff_reports_changed_node_attributes() {
+    let diff = source().diff(&target());
+
+    assert_eq!(
+        diff.changed_nodes,
+        vec![(
+            "a".to_string(),
+            vec![AttrChange::Changed {
+                key: "color".to_string(),
+                old: "green".to_string(),
+                new: "blue".to_string(),
+            }]
+        )]
+    );
+}
+
+#[test]
+fn diff_reports_edge_changes() {
+    let diff = source().diff(&target());
+
+    assert_eq!(diff.added_edges, vec![Edge::new("c", "d")]);
+    assert_eq!(
+        diff.removed_edges,
+        vec![(
+            EdgeKey {
+                from: "b".to_string(),
+                to: "c".to_string(),
+                index: 0,
+                directed: false,
+                key: None,
+            },
+            Edge::new("b", "c").with_attrs(&[("style", "dashed")]),
+        )]
+    );
+    assert_eq!(
+        diff.changed_edges[0].1,
+        vec![AttrChange::Added {
+            key: "weight".to_string(),
+            value: "2".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn diff_reports_graph_attributes_sorted_by_key() {
+    let diff = source().diff(&target());
+
+    assert_eq!(
+        diff.graph_attrs,
+        vec![
+            AttrChange::Added {
+                key: "rankdir".to_string(),
+                value: "LR".to_string(),
+            },
+            AttrChange::Changed {
+                key: "title".to_string(),
+                old: "Before".to_string(),
+                new: "After".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn parallel_edges_are_matched_in_order() {
+    let one = Graph::new().with_edges(&vec![Edge::new("a", "b")]);
+    let two = Graph::new().with_edges(&vec![
+        Edge::new("a", "b"),
+        Edge::new("a", "b").with_attrs(&[("color", "red")]),
+    ]);
+
+    let diff = one.diff(&two);
+
+    assert!(diff.changed_edges.is_empty());
+    assert_eq!(
+        diff.added_edges,
+        vec![Edge::new("a", "b").with_attrs(&[("color", "red")])]
+    );
+}
+
+#[test]
+fn undirected_edges_match_in_either_direction() {
+    let forward = Graph::new().with_edges(&vec![Edge::new("a", "b")]);
+    let backward = Graph::new().with_edges(&vec![Edge::new("b", "a")]);
+
+    assert!(forward.diff(&backward).is_empty());
+    assert!(!forward
+        .clone()
+        .with_directed(true)
+        .diff(&backward.with_directed(true))
+        .is_empty());
+}
+
+#[test]
+fn report_uses_the_edge_operator_of_the_graph() {
+    let before = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("b", "a")]);
+    let after = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("a", "b")]);
+
+    assert_eq!(
+        before.diff(&after).to_string(),
+        "+ edge a -> b\n- edge b -> a\n"
+    );
+}
+
+#[test]
+fn applying_the_patch_reproduces_the_target() {
+    let mut graph = source();
+
+    graph.diff(&target()).to_patch().apply(&mut graph).unwrap();
+
+    assert!(graph.diff(&target()).is_empty());
+}
+
+#[test]
+fn failed_patch_leaves_graph_untouched() {
+    let mut graph = source();
+    let patch = graph.diff(&target()).to_patch();
+    let mut broken = patch.clone();
+    broken.ops.push(PatchOp::RemoveNode("missing".to_string()));
+
+    let result = broken.apply(&mut graph);
+
+    assert_eq!(result, Err(PatchError::MissingNode("missing".to_string())));
+    assert_eq!(graph, source());
+}
+
+#[test]
+fn report_lists_every_change() {
+    let report = source().diff(&target()).to_string();
+
+    assert_eq!(
+        report,
+        [
+            "~ graph +rankdir=\"LR\"",
+            "~ graph title: \"Before\" -> \"After\"",
+            "+ node d [shape=\"box\"]",
+            "- node b",
+            "~ node a: color: \"green\" -> \"blue\"",
+            "+ edge c -- d",
+            "- edge b -- c",
+            "~ edge a -- b: +weight=\"2\"",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn diff_reports_directedness() {
+    let mut graph = source();
+    let directed = source().with_directed(true);
+
+    let diff = graph.diff(&directed);
+    assert_eq!(diff.directed, Some(true));
+    assert_eq!(diff.to_string(), "~ graph directed: false -> true\n");
+
+    diff.to_patch().apply(&mut graph).unwrap();
+    assert!(graph.directed);
+}
+
+#[test]
+fn keyed_edges_are_matched_by_key() {
+    let source = Graph::new().with_edges(&vec![
+        Edge::new("a", "b").with_key("x"),
+        Edge::new("a", "b").with_key("y"),
+    ]);
+    let target = Graph::new().with_edges(&vec![Edge::new("a", "b")
+        .with_key("y")
+        .with_attrs(&[("color", "red")])]);
+
+    let diff = source.diff(&target);
+
+    assert_eq!(diff.removed_edges.len(), 1);
+    assert_eq!(diff.removed_edges[0].0.key.as_deref(), Some("x"));
+    assert_eq!(diff.removed_edges[0].0.to_string(), "a -- b [key x]");
+    assert_eq!(diff.changed_edges.len(), 1);
+    assert_eq!(diff.changed_edges[0].0.key.as_deref(), Some("y"));
+
+    let mut patched = source.clone();
+    diff.to_patch().apply(&mut patched).unwrap();
+    assert_eq!(patched, target);
+}
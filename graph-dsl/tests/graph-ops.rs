@@ -0,0 +1,135 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::ops::{AttrConflict, ConflictPolicy, ConflictSite, KindMismatch, UnionError};
+use graph_dsl::graph::Graph;
+
+fn left() -> Graph {
+    Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("color", "green")]),
+            Node::new("b"),
+        ])
+        .with_edges(&vec![Edge::new("a", "b")])
+        .with_attrs(&[("title", "Left")])
+}
+
+fn right() -> Graph {
+    Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("color", "red"), ("shape", "box")]),
+            Node::new("c"),
+        ])
+        .with_edges(&vec![Edge::new("a", "b"), Edge::new("a", "c")])
+        .with_attrs(&[("title", "Left")])
+}
+
+#[test]
+fn union_keeping_left_values() {
+    let graph = left().union(&right(), ConflictPolicy::KeepLeft).unwrap();
+
+    assert_eq!(
+        graph.nodes,
+        vec![
+            Node::new("a").with_attrs(&[("color", "green"), ("shape", "box")]),
+            Node::new("b"),
+            Node::new("c"),
+        ]
+    );
+    assert_eq!(graph.edges, vec![Edge::new("a", "b"), Edge::new("a", "c")]);
+    assert_eq!(graph.attrs, left().attrs);
+}
+
+#[test]
+fn union_keeping_right_values() {
+    let graph = left().union(&right(), ConflictPolicy::KeepRight).unwrap();
+
+    assert_eq!(graph.node("a").unwrap().attr("color"), Some("red"));
+}
+
+#[test]
+fn union_can_fail_on_conflicts() {
+    let result = left().union(&right(), ConflictPolicy::Fail);
+
+    assert_eq!(
+        result,
+        Err(UnionError::Conflict(AttrConflict {
+            site: ConflictSite::Node("a".to_string()),
+            key: "color".to_string(),
+            left: "green".to_string(),
+            right: "red".to_string(),
+        }))
+    );
+}
+
+#[test]
+fn operations_reject_graphs_of_different_kinds() {
+    let directed = right().with_directed(true);
+
+    assert_eq!(
+        left().union(&directed, ConflictPolicy::KeepLeft),
+        Err(UnionError::KindMismatch(KindMismatch("directed")))
+    );
+    assert_eq!(
+        left().intersection(&directed),
+        Err(KindMismatch("directed"))
+    );
+    assert_eq!(
+        left().difference(&right().with_strict(true)),
+        Err(KindMismatch("strict"))
+    );
+}
+
+#[test]
+fn intersection_keeps_shared_items_and_agreeing_attributes() {
+    let graph = left().intersection(&right()).unwrap();
+
+    assert_eq!(graph.nodes, vec![Node::new("a")]);
+    assert_eq!(graph.edges, vec![Edge::new("a", "b")]);
+    assert_eq!(graph.attrs, left().attrs);
+}
+
+#[test]
+fn difference_removes_items_found_in_other() {
+    let graph = right().difference(&left()).unwrap();
+
+    assert_eq!(graph.nodes, vec![Node::new("c")]);
+    // `a -- c` goes with `a`.
+    assert!(graph.edges.is_empty());
+
+    let graph = right()
+        .difference(&Graph::new().with_edges(&vec![Edge::new("a", "b")]))
+        .unwrap();
+    assert_eq!(graph.nodes, right().nodes);
+    assert_eq!(graph.edges, vec![Edge::new("a", "c")]);
+}
+
+#[test]
+fn induced_subgraph_keeps_edges_between_selected_nodes() {
+    let graph = Graph::new()
+        .with_nodes(&vec![
+            Node::new("api").with_attrs(&[("team", "payments")]),
+            Node::new("db").with_attrs(&[("team", "payments")]),
+            Node::new("web").with_attrs(&[("team", "frontend")]),
+        ])
+        .with_edges(&vec![
+            Edge::new("web", "api"),
+            Edge::new("api", "db").with_attrs(&[("color", "red")]),
+        ]);
+
+    let payments = graph.subgraph_with_attrs(&[("team", "payments")]);
+
+    assert_eq!(
+        payments.nodes,
+        vec![
+            Node::new("api").with_attrs(&[("team", "payments")]),
+            Node::new("db").with_attrs(&[("team", "payments")]),
+        ]
+    );
+    assert_eq!(
+        payments.edges,
+        vec![Edge::new("api", "db").with_attrs(&[("color", "red")])]
+    );
+
+    let not_db = graph.induced_subgraph(|node| node.id != "db");
+    assert_eq!(not_db.edges, vec![Edge::new("web", "api")]);
+}
@@ -0,0 +1,186 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::graph_items::port::{CompassPoint, Port};
+use graph_dsl::graph::history::{Edit, History, HistoryError};
+use graph_dsl::graph::Graph;
+
+fn base() -> Graph {
+    Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("color", "red")]),
+            Node::new("b"),
+        ])
+        .with_edges(&vec![Edge::new("a", "b")])
+}
+
+#[test]
+fn undoes_and_redoes_single_edits() {
+    let mut history = History::new(base());
+
+    history.set_node_attr("a", "color", Some("blue")).unwrap();
+    history.add_node(Node::new("c")).unwrap();
+    assert_eq!(
+        history.graph().node("a").unwrap().attr("color"),
+        Some("blue")
+    );
+
+    assert_eq!(history.undo(), Ok(true));
+    assert!(history.graph().node("c").is_none());
+    assert_eq!(history.undo(), Ok(true));
+    assert_eq!(history.graph(), &base());
+    assert_eq!(history.undo(), Ok(false));
+
+    assert_eq!(history.redo(), Ok(true));
+    assert_eq!(
+        history.graph().node("a").unwrap().attr("color"),
+        Some("blue")
+    );
+    assert!(history.can_redo());
+}
+
+#[test]
+fn new_edits_clear_the_redo_stack() {
+    let mut history = History::new(base());
+
+    history.set_graph_attr("rankdir", Some("LR")).unwrap();
+    history.undo().unwrap();
+    history.set_directed(true).unwrap();
+
+    assert!(!history.can_redo());
+    assert_eq!(history.redo(), Ok(false));
+}
+
+#[test]
+fn removing_a_node_takes_its_edges_in_one_step() {
+    let mut history = History::new(base());
+
+    history.remove_node("a").unwrap();
+    assert!(history.graph().edges.is_empty());
+    assert_eq!(history.undo_stack().len(), 1);
+    assert_eq!(
+        history.undo_stack()[0].label.as_deref(),
+        Some("remove node a")
+    );
+
+    history.undo().unwrap();
+    assert_eq!(history.graph(), &base());
+    assert_eq!(history.graph().nodes[0].id, "a");
+}
+
+#[test]
+fn transactions_group_and_roll_back() {
+    let mut history = History::new(base());
+
+    history.begin("connect c");
+    history.add_node(Node::new("c")).unwrap();
+    history.begin("nested");
+    history.add_edge(Edge::new("b", "c")).unwrap();
+    history.commit().unwrap();
+    assert_eq!(history.undo(), Err(HistoryError::TransactionOpen));
+    history.commit().unwrap();
+
+    assert_eq!(history.undo_stack().len(), 1);
+    history.undo().unwrap();
+    assert_eq!(history.graph(), &base());
+
+    history.begin("abandoned");
+    history.set_edge_attr(0, "style", Some("dashed")).unwrap();
+    history.rollback().unwrap();
+    assert_eq!(history.graph(), &base());
+    assert!(!history.can_undo());
+    assert_eq!(history.commit(), Err(HistoryError::NoTransaction));
+}
+
+#[test]
+fn rejects_edits_that_do_not_fit() {
+    let mut history = History::new(base());
+
+    assert_eq!(
+        history.add_node(Node::new("a")),
+        Err(HistoryError::DuplicateNode("a".to_string()))
+    );
+    assert_eq!(history.remove_edge(3), Err(HistoryError::MissingEdge(3)));
+    assert!(!history.can_undo());
+
+    let stale = Edit::RemoveEdge {
+        index: 0,
+        edge: Edge::new("b", "a"),
+    };
+    let mut graph = base();
+    assert_eq!(stale.apply(&mut graph), Err(HistoryError::MissingEdge(0)));
+    assert_eq!(graph, base());
+}
+
+#[test]
+fn rolled_back_transactions_keep_the_redo_stack() {
+    let mut history = History::new(base());
+    history.set_graph_attr("rankdir", Some("LR")).unwrap();
+    history.undo().unwrap();
+
+    history.begin("abandoned");
+    history.add_node(Node::new("c")).unwrap();
+    assert!(history.can_redo());
+    history.rollback().unwrap();
+
+    assert_eq!(history.redo(), Ok(true));
+    assert_eq!(history.graph().attr("rankdir"), Some("LR"));
+
+    history.undo().unwrap();
+    history.begin("kept");
+    history.add_node(Node::new("c")).unwrap();
+    history.commit().unwrap();
+    assert!(!history.can_redo());
+}
+
+#[test]
+fn edits_invert() {
+    let edit = Edit::SetNodeAttr {
+        node: "a".to_string(),
+        key: "color".to_string(),
+        old: Some("red".to_string()),
+        new: None,
+    };
+    let mut graph = base();
+
+    edit.apply(&mut graph).unwrap();
+    assert_eq!(graph.node("a").unwrap().attr("color"), None);
+    edit.inverse().apply(&mut graph).unwrap();
+    assert_eq!(graph, base());
+}
+
+#[test]
+fn history_round_trips_through_text() {
+    let mut history = History::new(base());
+    history.begin("wire \"c\"");
+    history
+        .add_node(Node::new("c").with_attrs(&[("label", "two\nlines")]))
+        .unwrap();
+    history
+        .add_edge(
+            Edge::new("b", "c")
+                .with_key("1")
+                .with_tail_port(Port::new("out").with_compass(CompassPoint::E)),
+        )
+        .unwrap();
+    history.commit().unwrap();
+    history.set_strict(true).unwrap();
+    history.set_graph_attr("rankdir", Some("LR")).unwrap();
+    history.undo().unwrap();
+
+    let text = history.to_text();
+    assert!(text.starts_with("history 1\nundo \"wire \\\"c\\\"\"\n  insert-node 2 \"c\""));
+
+    let restored = History::restore(base(), &text).unwrap();
+    assert_eq!(restored.graph(), history.graph());
+    assert_eq!(restored.undo_stack(), history.undo_stack());
+    assert_eq!(restored.redo_stack(), history.redo_stack());
+    assert_eq!(restored.to_text(), text);
+
+    assert_eq!(
+        History::restore(base(), "history 1\n  set-strict false true\n").unwrap_err(),
+        HistoryError::Parse {
+            line: 2,
+            message: "edit outside a transaction".to_string()
+        }
+    );
+}
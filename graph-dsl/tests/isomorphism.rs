@@ -0,0 +1,147 @@
+use graph_dsl::graph::generators::Generator;
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::isomorphism::{find_isomorphism, IsoOptions};
+use graph_dsl::graph::Graph;
+
+fn edges(pairs: &[(&str, &str)]) -> Vec<Edge> {
+    pairs.iter().map(|(from, to)| Edge::new(from, to)).collect()
+}
+
+/// Renames every node and shuffles the edge list, which must not affect the result.
+fn relabelled(graph: &Graph) -> Graph {
+    let rename = |id: &str| format!("x_{}", id.chars().rev().collect::<String>());
+    let mut renamed: Vec<Edge> = graph
+        .edges
+        .iter()
+        .map(|edge| Edge {
+            from: rename(&edge.from).into(),
+            to: rename(&edge.to).into(),
+            ..edge.clone()
+        })
+        .collect();
+    renamed.reverse();
+
+    Graph::new()
+        .with_nodes(
+            &graph
+                .nodes
+                .iter()
+                .rev()
+                .map(|node| Node {
+                    id: rename(&node.id).into(),
+                    ..node.clone()
+                })
+                .collect(),
+        )
+        .with_edges(&renamed)
+        .with_directed(graph.directed)
+}
+
+#[test]
+fn relabelled_graphs_are_isomorphic() {
+    let graph = Generator::new().with_seed(5).erdos_renyi(12, 0.3);
+
+    assert!(graph.is_isomorphic(&relabelled(&graph)));
+}
+
+#[test]
+fn mapping_preserves_edges() {
+    let left = Graph::new().with_edges(&edges(&[("a", "b"), ("b", "c")]));
+    let right = Graph::new().with_edges(&edges(&[("z", "y"), ("x", "y")]));
+
+    let mapping = find_isomorphism(&left, &right, IsoOptions::structural()).unwrap();
+
+    assert_eq!(mapping["b"], "y");
+}
+
+#[test]
+fn different_structures_are_not_isomorphic() {
+    let mut generator = Generator::new();
+    let path = generator.path(4);
+    let star = generator.star(3);
+
+    assert_eq!(path.edges.len(), star.edges.len());
+    assert!(!path.is_isomorphic(&star));
+}
+
+#[test]
+fn direction_matters_for_directed_graphs() {
+    let chain = Graph::new()
+        .with_edges(&edges(&[("a", "b"), ("b", "c")]))
+        .with_directed(true);
+    let fork = Graph::new()
+        .with_edges(&edges(&[("b", "a"), ("b", "c")]))
+        .with_directed(true);
+
+    assert!(!chain.is_isomorphic(&fork));
+    assert!(chain
+        .clone()
+        .with_directed(false)
+        .is_isomorphic(&fork.with_directed(false)));
+}
+
+#[test]
+fn attribute_aware_matching() {
+    let left = Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("color", "red")]),
+            Node::new("b"),
+        ])
+        .with_edges(&edges(&[("a", "b")]));
+    let right = Graph::new()
+        .with_nodes(&vec![
+            Node::new("a"),
+            Node::new("b").with_attrs(&[("color", "blue")]),
+        ])
+        .with_edges(&edges(&[("a", "b")]));
+
+    assert!(left.is_isomorphic(&right));
+    assert!(!left.is_isomorphic_with(&right, IsoOptions::with_attrs()));
+    assert!(left.is_isomorphic_with(&relabelled(&left), IsoOptions::with_attrs()));
+}
+
+#[test]
+fn canonical_form_ignores_insertion_order() {
+    let graph = Generator::new().with_seed(11).barabasi_albert(15, 2);
+
+    assert_eq!(
+        graph.canonical_hash(IsoOptions::structural()),
+        relabelled(&graph).canonical_hash(IsoOptions::structural())
+    );
+}
+
+#[test]
+fn canonical_form_separates_non_isomorphic_graphs() {
+    let mut generator = Generator::new();
+    let cycle = generator.cycle(6);
+    let two_triangles = Graph::new().with_edges(&edges(&[
+        ("a", "b"),
+        ("b", "c"),
+        ("c", "a"),
+        ("d", "e"),
+        ("e", "f"),
+        ("f", "d"),
+    ]));
+
+    assert_ne!(
+        cycle.canonical_form(IsoOptions::structural()),
+        two_triangles.canonical_form(IsoOptions::structural())
+    );
+}
+
+#[test]
+fn canonical_form_handles_symmetric_graphs() {
+    let mut generator = Generator::new();
+    let complete = generator.complete(12);
+    let grid = generator.grid(4, 4);
+
+    assert_eq!(
+        complete.canonical_form(IsoOptions::structural()),
+        relabelled(&complete).canonical_form(IsoOptions::structural())
+    );
+    assert_eq!(
+        grid.canonical_hash(IsoOptions::structural()),
+        relabelled(&grid).canonical_hash(IsoOptions::structural())
+    );
+}
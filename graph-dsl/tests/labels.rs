@@ -0,0 +1,137 @@
+use graph_dsl::graph::graph_items::label::{
+    HtmlElement, HtmlLabel, HtmlNode, Label, LabelError, RecordField, RecordLabel,
+};
+use graph_dsl::graph::graph_items::node::Node;
+
+#[test]
+fn parses_record_labels() {
+    let label = RecordLabel::parse("{a|<p1> b|c}").unwrap();
+
+    assert_eq!(
+        label,
+        RecordLabel::new().with_field(RecordField::group(vec![
+            RecordField::text("a"),
+            RecordField::port("p1", "b"),
+            RecordField::text("c"),
+        ]))
+    );
+    assert_eq!(label.ports(), ["p1"]);
+    assert_eq!(label.to_string(), "{a|<p1> b|c}");
+}
+
+#[test]
+fn record_labels_escape_special_characters() {
+    let label = RecordLabel::new()
+        .with_field(RecordField::text("a|b"))
+        .with_field(RecordField::port("out", "{x}"));
+
+    assert_eq!(label.to_string(), "a\\|b|<out> \\{x\\}");
+    assert_eq!(RecordLabel::parse(&label.to_string()), Ok(label));
+}
+
+#[test]
+fn reports_unbalanced_record_labels() {
+    assert_eq!(
+        RecordLabel::parse("a|{b|c"),
+        Err(LabelError {
+            message: "unterminated '{'".to_string(),
+            position: 2,
+        })
+    );
+    assert_eq!(RecordLabel::parse("a}").unwrap_err().position, 1);
+    assert_eq!(
+        RecordLabel::parse("<p1 a").unwrap_err().message,
+        "unterminated port name"
+    );
+}
+
+#[test]
+fn parses_html_labels() {
+    let label = HtmlLabel::parse(
+        "<<TABLE BORDER=\"0\"><TR><TD PORT=\"x\"><B>bold</B><BR/>text</TD></TR></TABLE>>",
+    )
+    .unwrap();
+
+    let HtmlNode::Element(table) = &label.content[0] else {
+        panic!("expected a table");
+    };
+    assert_eq!(table.name, "TABLE");
+    assert_eq!(table.attr("border"), Some("0"));
+    assert_eq!(label.ports(), ["x"]);
+    assert!(label.validate().is_empty());
+    assert_eq!(
+        label.to_string(),
+        "<<TABLE BORDER=\"0\"><TR><TD PORT=\"x\"><B>bold</B><BR/>text</TD></TR></TABLE>>"
+    );
+}
+
+#[test]
+fn reports_malformed_html_labels() {
+    assert_eq!(
+        HtmlLabel::parse("<<B>bold</I>>").unwrap_err().message,
+        "expected </B>, found </I>"
+    );
+    assert_eq!(
+        HtmlLabel::parse("<<TABLE>>").unwrap_err().message,
+        "unclosed <TABLE>"
+    );
+}
+
+#[test]
+fn validates_html_structure() {
+    let label = HtmlLabel::parse("<<TABLE><TD>x</TD></TABLE><BLINK>hi</BLINK>>").unwrap();
+
+    assert_eq!(
+        label.validate(),
+        [
+            "<TD> is not allowed inside <TABLE>",
+            "unknown element <BLINK>",
+        ]
+    );
+}
+
+#[test]
+fn builds_tables_programmatically() {
+    let label = HtmlLabel::table(&[&["name", "a < b"], &["x", "y"]]);
+
+    assert_eq!(
+        label.to_string(),
+        "<<TABLE><TR><TD>name</TD><TD>a &lt; b</TD></TR><TR><TD>x</TD><TD>y</TD></TR></TABLE>>"
+    );
+
+    let custom = HtmlLabel::new().with_child(
+        HtmlElement::new("FONT")
+            .with_attr("COLOR", "red")
+            .with_child("warning"),
+    );
+    assert_eq!(custom.to_string(), "<<FONT COLOR=\"red\">warning</FONT>>");
+}
+
+#[test]
+fn nodes_parse_labels_by_shape() {
+    let record = Node::new("a").with_label(&Label::Record(
+        RecordLabel::new()
+            .with_field(RecordField::port("in", "a"))
+            .with_field(RecordField::text("b")),
+    ));
+    assert_eq!(record.attr("shape"), Some("record"));
+    assert_eq!(record.attr("label"), Some("<in> a|b"));
+    assert_eq!(record.label().unwrap().unwrap().ports(), ["in"]);
+
+    let plain = Node::new("b").with_attrs(&[("label", "a|b")]);
+    assert_eq!(plain.label(), Some(Ok(Label::Text("a|b".to_string()))));
+
+    assert_eq!(Node::new("c").label(), None);
+}
+
+#[test]
+fn record_ports_are_not_mistaken_for_html() {
+    let node = Node::new("a").with_attrs(&[("shape", "record"), ("label", "<p1>")]);
+
+    assert_eq!(
+        node.label(),
+        Some(Ok(Label::Record(
+            RecordLabel::new().with_field(RecordField::port("p1", ""))
+        )))
+    );
+}
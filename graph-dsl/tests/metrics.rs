@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use graph_dsl::graph::generators::Generator;
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::metrics::{DegreeKind, PageRankOptions};
+use graph_dsl::graph::Graph;
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn degrees_and_distributions() {
+    let star = Generator::new().star(3);
+
+    let degrees = star.degrees(DegreeKind::Total);
+    assert_eq!(degrees["n0"], 3);
+    assert_eq!(degrees["n1"], 1);
+    assert_eq!(
+        star.degree_distribution(DegreeKind::Total),
+        BTreeMap::from([(1, 3), (3, 1)])
+    );
+
+    let directed = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("a", "b"), Edge::new("a", "c")]);
+    assert_eq!(directed.degrees(DegreeKind::Out)["a"], 2);
+    assert_eq!(directed.degrees(DegreeKind::In)["a"], 0);
+    assert_eq!(directed.degrees(DegreeKind::Total)["b"], 1);
+}
+
+#[test]
+fn betweenness_peaks_at_the_hub() {
+    let star = Generator::new().star(4);
+    let betweenness = star.betweenness_centrality();
+
+    assert!(close(betweenness["n0"], 1.0));
+    assert!(close(betweenness["n1"], 0.0));
+
+    let path = Generator::new().path(3);
+    assert!(close(path.betweenness_centrality()["n1"], 1.0));
+}
+
+#[test]
+fn closeness_on_a_path() {
+    let path = Generator::new().path(3);
+    let closeness = path.closeness_centrality();
+
+    assert!(close(closeness["n1"], 1.0));
+    assert!(close(closeness["n0"], 2.0 / 3.0));
+}
+
+#[test]
+fn pagerank_sums_to_one_and_follows_weights() {
+    let graph = Graph::new().with_directed(true).with_edges(&vec![
+        Edge::new("a", "b").with_attrs(&[("weight", "9")]),
+        Edge::new("a", "c").with_attrs(&[("weight", "1")]),
+        Edge::new("b", "a"),
+        Edge::new("c", "a"),
+    ]);
+
+    let unweighted = graph.pagerank(&PageRankOptions::default());
+    assert!(close(unweighted.values().sum(), 1.0));
+    assert!(close(unweighted["b"], unweighted["c"]));
+
+    let weighted = graph.pagerank(&PageRankOptions::default().with_weight("weight"));
+    assert!(close(weighted.values().sum(), 1.0));
+    assert!(weighted["b"] > weighted["c"]);
+}
+
+#[test]
+fn clustering_coefficients() {
+    let complete = Generator::new().complete(4);
+    assert!(close(complete.average_clustering(), 1.0));
+
+    let star = Generator::new().star(3);
+    assert!(close(star.clustering_coefficients()["n0"], 0.0));
+
+    let triangle_with_tail = Graph::new().with_edges(&vec![
+        Edge::new("a", "b"),
+        Edge::new("b", "c"),
+        Edge::new("c", "a"),
+        Edge::new("c", "d"),
+    ]);
+    assert!(close(
+        triangle_with_tail.clustering_coefficients()["c"],
+        1.0 / 3.0
+    ));
+}
+
+#[test]
+fn diameter_and_density() {
+    assert_eq!(Generator::new().path(5).diameter(), Some(4));
+    assert_eq!(Generator::new().grid(3, 3).diameter(), Some(4));
+    assert_eq!(Graph::new().diameter(), None);
+
+    assert!(close(Generator::new().complete(5).density(), 1.0));
+    assert!(close(Generator::new().path(3).density(), 2.0 / 3.0));
+    let directed = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("a", "b")]);
+    assert!(close(directed.density(), 0.5));
+}
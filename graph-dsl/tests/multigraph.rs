@@ -0,0 +1,120 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::Graph;
+
+#[test]
+fn parallel_edges_get_distinct_keys() {
+    let mut graph = Graph::new();
+
+    let first = graph.add_edge(Edge::new("web", "db").with_attrs(&[("link", "eth0")]));
+    let second = graph.add_edge(Edge::new("web", "db").with_attrs(&[("link", "eth1")]));
+
+    assert_eq!(first, "0");
+    assert_eq!(second, "1");
+    assert_eq!(graph.edges_between("web", "db").len(), 2);
+    assert_eq!(
+        graph.edge("web", "db", "1").unwrap().attr("link"),
+        Some("eth1")
+    );
+}
+
+#[test]
+fn undirected_lookups_ignore_direction() {
+    let mut graph = Graph::new();
+    graph.add_edge(Edge::new("a", "b"));
+    graph.add_edge(Edge::new("b", "a"));
+
+    assert_eq!(graph.edges_between("a", "b").len(), 2);
+    assert!(graph.edge("a", "b", "1").is_some());
+
+    let mut directed = Graph::new().with_directed(true);
+    directed.add_edge(Edge::new("a", "b"));
+    directed.add_edge(Edge::new("b", "a"));
+
+    assert_eq!(directed.edges_between("a", "b").len(), 1);
+}
+
+#[test]
+fn removing_an_edge_keeps_the_other_keys() {
+    let mut graph = Graph::new();
+    for _ in 0..3 {
+        graph.add_edge(Edge::new("a", "b"));
+    }
+
+    let removed = graph.remove_edge("a", "b", "1").unwrap();
+    assert_eq!(removed.key.as_deref(), Some("1"));
+
+    let keys: Vec<_> = graph
+        .edges_between("a", "b")
+        .iter()
+        .map(|edge| edge.key.clone().unwrap())
+        .collect();
+    assert_eq!(keys, ["0", "2"]);
+
+    assert_eq!(graph.add_edge(Edge::new("a", "b")), "1");
+    assert!(graph.remove_edge("a", "b", "7").is_none());
+}
+
+#[test]
+fn explicit_keys_update_existing_edges() {
+    let mut graph = Graph::new();
+    graph.add_edge(Edge::new("a", "b").with_key("uplink"));
+    graph.add_edge(
+        Edge::new("a", "b")
+            .with_key("uplink")
+            .with_attrs(&[("color", "red")]),
+    );
+
+    assert_eq!(graph.edges.len(), 1);
+    assert_eq!(
+        graph.edge("a", "b", "uplink").unwrap().attr("color"),
+        Some("red")
+    );
+}
+
+#[test]
+fn strict_graphs_collapse_parallel_edges() {
+    let mut graph = Graph::new()
+        .with_edges(&vec![
+            Edge::new("a", "b").with_attrs(&[("color", "red")]),
+            Edge::new("b", "a").with_attrs(&[("style", "bold")]),
+        ])
+        .with_strict(true);
+
+    assert_eq!(graph.edges.len(), 1);
+    assert_eq!(graph.edges[0].attr("color"), Some("red"));
+    assert_eq!(graph.edges[0].attr("style"), Some("bold"));
+
+    assert_eq!(graph.add_edge(Edge::new("a", "b")), "0");
+    assert_eq!(graph.edges.len(), 1);
+}
+
+#[test]
+fn unkeyed_edges_can_be_keyed_afterwards() {
+    let mut graph = Graph::new().with_edges(&vec![
+        Edge::new("a", "b").with_key("1"),
+        Edge::new("a", "b"),
+        Edge::new("a", "b"),
+    ]);
+
+    graph.assign_edge_keys();
+
+    let keys: Vec<_> = graph
+        .edges
+        .iter()
+        .map(|edge| edge.key.clone().unwrap())
+        .collect();
+    assert_eq!(keys, ["1", "0", "2"]);
+}
+
+#[test]
+fn with_edges_respects_strict() {
+    let graph = Graph::new().with_strict(true).with_edges(&vec![
+        Edge::new("a", "b"),
+        Edge::new("b", "a").with_attrs(&[("color", "red")]),
+    ]);
+
+    assert_eq!(
+        graph.edges,
+        vec![Edge::new("a", "b").with_attrs(&[("color", "red")])]
+    );
+}
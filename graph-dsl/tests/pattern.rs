@@ -0,0 +1,217 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::pattern::{
+    Pattern, RewriteError, Rule, Template, TemplateNode, UnknownPatternNode,
+};
+use graph_dsl::graph::query::Predicate;
+use graph_dsl::graph::Graph;
+
+fn pipeline() -> Graph {
+    Graph::new()
+        .with_directed(true)
+        .with_nodes(&vec![
+            Node::new("read").with_attrs(&[("kind", "source")]),
+            Node::new("parse").with_attrs(&[("kind", "map")]),
+            Node::new("clean").with_attrs(&[("kind", "map")]),
+            Node::new("store").with_attrs(&[("kind", "sink")]),
+        ])
+        .with_edges(&vec![
+            Edge::new("read", "parse"),
+            Edge::new("parse", "clean").with_attrs(&[("buffer", "64")]),
+            Edge::new("clean", "store"),
+        ])
+}
+
+fn map_chain() -> Pattern {
+    Pattern::new()
+        .with_node("a", Predicate::eq("kind", "map"))
+        .with_node("b", Predicate::eq("kind", "map"))
+        .with_edge("a", "b", Predicate::Any)
+}
+
+#[test]
+fn finds_matches_with_constraints() {
+    let matches = pipeline().find_matches(&map_chain()).unwrap();
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].nodes["a"], "parse");
+    assert_eq!(matches[0].nodes["b"], "clean");
+    assert_eq!(matches[0].edges, [1]);
+
+    let buffered = Pattern::new()
+        .with_node("a", Predicate::Any)
+        .with_node("b", Predicate::Any)
+        .with_edge("a", "b", Predicate::has("buffer"));
+    assert_eq!(pipeline().find_matches(&buffered).unwrap().len(), 1);
+}
+
+#[test]
+fn respects_direction_and_distinct_nodes() {
+    let backwards = Pattern::new()
+        .with_node("a", Predicate::eq("kind", "sink"))
+        .with_node("b", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any);
+    assert!(pipeline().find_matches(&backwards).unwrap().is_empty());
+
+    let loop_pattern = Pattern::new()
+        .with_node("a", Predicate::Any)
+        .with_node("b", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any)
+        .with_edge("b", "a", Predicate::Any);
+    assert!(pipeline().find_matches(&loop_pattern).unwrap().is_empty());
+}
+
+#[test]
+fn triangles_match_once_per_automorphism() {
+    let triangle = Graph::new().with_edges(&vec![
+        Edge::new("x", "y"),
+        Edge::new("y", "z"),
+        Edge::new("z", "x"),
+    ]);
+    let pattern = Pattern::new()
+        .with_node("a", Predicate::Any)
+        .with_node("b", Predicate::Any)
+        .with_node("c", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any)
+        .with_edge("b", "c", Predicate::Any)
+        .with_edge("c", "a", Predicate::Any);
+
+    assert_eq!(triangle.find_matches(&pattern).unwrap().len(), 6);
+}
+
+#[test]
+fn parallel_pattern_edges_need_parallel_graph_edges() {
+    let pattern = Pattern::new()
+        .with_node("a", Predicate::Any)
+        .with_node("b", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any);
+
+    let single = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("x", "y")]);
+    assert!(single.find_matches(&pattern).unwrap().is_empty());
+
+    let double = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("x", "y"), Edge::new("x", "y")]);
+    assert_eq!(double.find_matches(&pattern).unwrap()[0].edges, [0, 1]);
+}
+
+#[test]
+fn rewrites_by_merging_nodes() {
+    let rule = Rule::new(
+        map_chain(),
+        Template::new().with_node(
+            TemplateNode::new("fused")
+                .with_id("{a}_{b}")
+                .with_attrs(&[("kind", "map"), ("label", "{a.kind}: {a} + {b}")])
+                .with_inherits(&["a", "b"]),
+        ),
+    );
+    let mut graph = pipeline();
+
+    assert_eq!(graph.rewrite(&rule), Ok(1));
+
+    let ids: Vec<&str> = graph.nodes.iter().map(|node| node.id.as_str()).collect();
+    assert_eq!(ids, ["read", "store", "parse_clean"]);
+    assert_eq!(
+        graph.node("parse_clean").unwrap().attr("label"),
+        Some("map: parse + clean")
+    );
+    assert_eq!(
+        graph.edges,
+        [
+            Edge::new("read", "parse_clean"),
+            Edge::new("parse_clean", "store")
+        ]
+    );
+}
+
+#[test]
+fn rewrites_edges_between_kept_nodes() {
+    let rule = Rule::new(
+        Pattern::new()
+            .with_node("a", Predicate::Any)
+            .with_node("b", Predicate::Any)
+            .with_edge("a", "b", Predicate::has("buffer")),
+        Template::new()
+            .with_node(TemplateNode::new("a"))
+            .with_node(TemplateNode::new("b").with_attrs(&[("buffered", "true")]))
+            .with_node(TemplateNode::new("queue").with_id("{a}_queue"))
+            .with_edge("a", "queue", &[])
+            .with_edge("queue", "b", &[("size", "{a.kind}")]),
+    );
+    let mut graph = pipeline();
+
+    assert_eq!(graph.rewrite(&rule), Ok(1));
+    assert_eq!(graph.node("clean").unwrap().attr("buffered"), Some("true"));
+    assert_eq!(
+        graph.edges[2..],
+        [
+            Edge::new("parse", "parse_queue"),
+            Edge::new("parse_queue", "clean").with_attrs(&[("size", "map")]),
+        ]
+    );
+}
+
+#[test]
+fn failed_rewrites_leave_the_graph_alone() {
+    let rule = Rule::new(
+        map_chain(),
+        Template::new().with_node(TemplateNode::new("x").with_id("store")),
+    );
+    let mut graph = pipeline();
+
+    assert_eq!(
+        graph.rewrite(&rule),
+        Err(RewriteError::DuplicateNode("store".to_string()))
+    );
+    assert_eq!(graph, pipeline());
+
+    let unknown = Rule::new(map_chain(), Template::new().with_edge("a", "nope", &[]));
+    assert_eq!(
+        graph.rewrite(&unknown),
+        Err(RewriteError::UnknownName("a".to_string()))
+    );
+}
+
+#[test]
+fn created_nodes_without_an_id_get_a_fresh_one_per_match() {
+    let pattern = Pattern::new()
+        .with_node("a", Predicate::Any)
+        .with_node("b", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any);
+    let rule = Rule::new(
+        pattern,
+        Template::new()
+            .with_node(TemplateNode::new("a"))
+            .with_node(TemplateNode::new("b"))
+            .with_node(TemplateNode::new("hop"))
+            .with_edge("a", "hop", &[])
+            .with_edge("hop", "b", &[]),
+    );
+    let mut graph = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("w", "x"), Edge::new("y", "z")]);
+
+    assert_eq!(graph.rewrite(&rule), Ok(2));
+    assert!(graph.node("hop").is_some());
+    assert!(graph.node("hop_1").is_some());
+}
+
+#[test]
+fn pattern_edges_must_name_pattern_nodes() {
+    let pattern = Pattern::new()
+        .with_node("a", Predicate::Any)
+        .with_edge("a", "b", Predicate::Any);
+
+    assert_eq!(
+        pipeline().find_matches(&pattern),
+        Err(UnknownPatternNode("b".to_string()))
+    );
+    assert_eq!(
+        pipeline().rewrite(&Rule::new(pattern, Template::new())),
+        Err(RewriteError::Pattern(UnknownPatternNode("b".to_string())))
+    );
+}
@@ -0,0 +1,108 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::label::Label;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::graph_items::port::{CompassPoint, Port, PortParseError};
+use graph_dsl::graph::Graph;
+
+#[test]
+fn parses_port_names_and_compass_points() {
+    assert_eq!("out".parse(), Ok(Port::new("out")));
+    assert_eq!(
+        "out:ne".parse(),
+        Ok(Port::new("out").with_compass(CompassPoint::NE))
+    );
+    assert_eq!("sw".parse(), Ok(Port::compass(CompassPoint::SW)));
+    assert_eq!(
+        "out:up".parse::<Port>(),
+        Err(PortParseError::UnknownCompassPoint("up".to_string()))
+    );
+    assert_eq!("".parse::<Port>(), Err(PortParseError::Empty));
+}
+
+#[test]
+fn ports_display_as_written() {
+    let port = Port::new("out").with_compass(CompassPoint::Any);
+
+    assert_eq!(port.to_string(), "out:_");
+    assert_eq!(port.to_string().parse(), Ok(port));
+}
+
+#[test]
+fn dot_output_includes_ports() {
+    let graph = Graph::new()
+        .with_directed(true)
+        .with_nodes(&vec![Node::new("a").with_attrs(&[("shape", "record")])])
+        .with_edges(&vec![Edge::new("a", "b")
+            .with_tail_port("out:ne".parse().unwrap())
+            .with_head_port(Port::new("in"))
+            .with_attrs(&[("color", "green")])]);
+
+    assert_eq!(
+        graph.to_dot(),
+        "digraph {\n    a [shape=\"record\"]\n    a:out:ne -> b:in [color=\"green\"]\n}\n"
+    );
+}
+
+#[test]
+fn dot_output_quotes_when_needed() {
+    let graph = Graph::new()
+        .with_strict(true)
+        .with_attrs(&[("label", "Say \"hi\"")])
+        .with_nodes(&vec![
+            Node::new("my node").with_label(&Label::parse("<<b>bold</b>>", None).unwrap()),
+            Node::new("42").with_attrs(&[
+                ("label", "<b>"),
+                ("tooltip", "C:\\"),
+                ("xlabel", "a\\\\b\\l \\\\\"c\\\\"),
+            ]),
+        ]);
+
+    assert_eq!(
+        graph.to_dot(),
+        [
+            "strict graph {",
+            "    graph [label=\"Say \\\"hi\\\"\"]",
+            "    \"my node\" [label=<<b>bold</b>>]",
+            "    42 [label=\"<b>\", tooltip=\"C:\\\\\", xlabel=\"a\\\\b\\l \\\\\\\"c\\\\\"]",
+            "}",
+            "",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn validates_ports_against_record_labels() {
+    let graph = Graph::new()
+        .with_nodes(&vec![
+            Node::new("a").with_attrs(&[("shape", "record"), ("label", "{<in> a|<out> b}")]),
+            Node::new("b"),
+        ])
+        .with_edges(&vec![
+            Edge::new("a", "b").with_tail_port(Port::new("out")),
+            Edge::new("a", "b").with_tail_port(Port::new("middle")),
+            Edge::new("b", "a").with_tail_port(Port::new("anything")),
+        ]);
+
+    let errors = graph.validate_ports();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].port, "middle");
+    assert_eq!(errors[0].known, ["in", "out"]);
+}
+
+#[test]
+fn validates_ports_against_html_labels() {
+    let graph = Graph::new()
+        .with_nodes(&vec![Node::new("t").with_attrs(&[(
+            "label",
+            "<<TABLE><TR><TD PORT=\"left\">L</TD><TD port='right'>R</TD></TR></TABLE>>",
+        )])])
+        .with_edges(&vec![
+            Edge::new("t", "u").with_tail_port(Port::new("left").with_compass(CompassPoint::S)),
+            Edge::new("t", "u").with_tail_port(Port::new("right")),
+            Edge::new("t", "u").with_tail_port(Port::compass(CompassPoint::N)),
+        ]);
+
+    assert!(graph.validate_ports().is_empty());
+}
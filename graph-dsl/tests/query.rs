@@ -0,0 +1,171 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::query::{
+    CompareOp, Direction, PathPattern, Predicate, Query, QueryError, Selection,
+};
+use graph_dsl::graph::Graph;
+
+fn services() -> Graph {
+    Graph::new()
+        .with_directed(true)
+        .with_attrs(&[("rankdir", "LR")])
+        .with_nodes(&vec![
+            Node::new("gateway").with_attrs(&[("shape", "box")]),
+            Node::new("auth").with_attrs(&[("shape", "box"), ("replicas", "3")]),
+            Node::new("orders").with_attrs(&[("shape", "ellipse"), ("replicas", "12")]),
+            Node::new("db").with_attrs(&[("shape", "box"), ("role", "storage")]),
+            Node::new("audit").with_attrs(&[("shape", "box")]),
+        ])
+        .with_edges(&vec![
+            Edge::new("gateway", "auth"),
+            Edge::new("gateway", "orders").with_attrs(&[("proto", "http")]),
+            Edge::new("orders", "db").with_attrs(&[("proto", "sql")]),
+            Edge::new("auth", "db").with_attrs(&[("proto", "sql")]),
+            Edge::new("audit", "db"),
+        ])
+}
+
+fn ids(selection: &Selection) -> Vec<&str> {
+    selection.nodes.iter().map(String::as_str).collect()
+}
+
+#[test]
+fn filters_by_attribute_and_reachability() {
+    let query =
+        Query::parse("nodes where shape = box reachable from gateway within 3 hops").unwrap();
+
+    assert_eq!(
+        query,
+        Query::nodes()
+            .filter(Predicate::eq("shape", "box"))
+            .reachable_from("gateway", Some(3))
+    );
+    assert_eq!(ids(&query.run(&services()).unwrap()), ["auth", "db"]);
+}
+
+#[test]
+fn hop_limits_and_reverse_reachability() {
+    let graph = services();
+
+    let near = Query::parse("nodes reachable from gateway within 1 hop").unwrap();
+    assert_eq!(ids(&near.run(&graph).unwrap()), ["auth", "orders"]);
+
+    let upstream = Query::nodes().reaching("db", None);
+    assert_eq!(
+        ids(&upstream.run(&graph).unwrap()),
+        ["gateway", "auth", "orders", "audit"]
+    );
+}
+
+#[test]
+fn predicates_combine_and_compare_numbers() {
+    let query =
+        Query::parse("nodes where replicas > 5 or (has role and not @id = \"orders\")").unwrap();
+    assert_eq!(
+        query,
+        Query::nodes().filter(
+            Predicate::compare("replicas", CompareOp::Gt, "5")
+                .or(Predicate::has("role").and(Predicate::id("orders").negate()))
+        )
+    );
+    assert_eq!(ids(&query.run(&services()).unwrap()), ["orders", "db"]);
+
+    assert_eq!(
+        Query::parse("nodes where weight > -1 and x<-2.5").unwrap(),
+        Query::nodes().filter(
+            Predicate::compare("weight", CompareOp::Gt, "-1").and(Predicate::compare(
+                "x",
+                CompareOp::Lt,
+                "-2.5"
+            ))
+        )
+    );
+
+    let missing = Query::parse("nodes where shape != box").unwrap();
+    assert_eq!(ids(&missing.run(&services()).unwrap()), ["orders"]);
+}
+
+#[test]
+fn expands_neighbourhoods() {
+    let query = Query::parse("nodes where @id = audit expand 1").unwrap();
+    assert_eq!(
+        query,
+        Query::nodes().filter(Predicate::id("audit")).expand(1)
+    );
+    assert_eq!(ids(&query.run(&services()).unwrap()), ["db", "audit"]);
+
+    let wider = Query::nodes().filter(Predicate::id("audit")).expand(2);
+    assert_eq!(
+        ids(&wider.run(&services()).unwrap()),
+        ["auth", "orders", "db", "audit"]
+    );
+}
+
+#[test]
+fn matches_path_patterns() {
+    let query = Query::parse("nodes path (@id = gateway) -> (shape = ellipse) -[proto = sql]-> ()")
+        .unwrap();
+    assert_eq!(
+        query,
+        Query::nodes().path(
+            PathPattern::new(Predicate::id("gateway"))
+                .then(
+                    Direction::Out,
+                    Predicate::Any,
+                    Predicate::eq("shape", "ellipse")
+                )
+                .then(
+                    Direction::Out,
+                    Predicate::eq("proto", "sql"),
+                    Predicate::Any
+                )
+        )
+    );
+
+    let selection = query.run(&services()).unwrap();
+    assert_eq!(ids(&selection), ["gateway", "orders", "db"]);
+    assert_eq!(selection.edges, [1, 2]);
+
+    let backwards = Query::parse("nodes path (role = storage) <-[proto = sql]- ()").unwrap();
+    assert_eq!(
+        ids(&backwards.run(&services()).unwrap()),
+        ["auth", "orders", "db"]
+    );
+}
+
+#[test]
+fn projects_into_a_new_graph() {
+    let projected = services()
+        .query("nodes where shape = box reachable from gateway")
+        .unwrap();
+
+    assert!(projected.directed);
+    assert_eq!(projected.attr("rankdir"), Some("LR"));
+    assert_eq!(projected.nodes.len(), 2);
+    assert_eq!(projected.node("db").unwrap().attr("role"), Some("storage"));
+    assert_eq!(
+        projected.edges,
+        [Edge::new("auth", "db").with_attrs(&[("proto", "sql")])]
+    );
+}
+
+#[test]
+fn reports_errors() {
+    assert_eq!(
+        Query::parse("nodes where shape box"),
+        Err(QueryError::Syntax {
+            message: "expected a comparison, found 'box'".to_string(),
+            position: 18,
+        })
+    );
+    assert!(matches!(
+        Query::parse("nodes where label = \"open"),
+        Err(QueryError::Syntax { position: 20, .. })
+    ));
+    assert_eq!(
+        Query::nodes()
+            .reachable_from("nowhere", None)
+            .run(&services()),
+        Err(QueryError::UnknownNode("nowhere".to_string()))
+    );
+}
@@ -0,0 +1,102 @@
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::transitive::{TransitiveError, TransitiveOptions};
+use graph_dsl::graph::Graph;
+
+fn dependencies() -> Graph {
+    Graph::new().with_directed(true).with_edges(&vec![
+        Edge::new("app", "http").with_attrs(&[("label", "uses")]),
+        Edge::new("http", "core"),
+        Edge::new("app", "core").with_attrs(&[("color", "red")]),
+        Edge::new("core", "alloc"),
+    ])
+}
+
+fn pairs(graph: &Graph) -> Vec<(&str, &str)> {
+    graph
+        .edges
+        .iter()
+        .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+        .collect()
+}
+
+#[test]
+fn reduction_drops_implied_edges() {
+    let reduced = dependencies()
+        .transitive_reduction(&TransitiveOptions::new())
+        .unwrap();
+
+    assert_eq!(
+        pairs(&reduced),
+        [("app", "http"), ("http", "core"), ("core", "alloc")]
+    );
+    assert_eq!(reduced.edges[0].attr("label"), Some("uses"));
+}
+
+#[test]
+fn reduction_collapses_parallel_edges() {
+    let graph = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("a", "b"), Edge::new("a", "b")]);
+
+    let reduced = graph
+        .transitive_reduction(&TransitiveOptions::new())
+        .unwrap();
+    assert_eq!(pairs(&reduced), [("a", "b")]);
+}
+
+#[test]
+fn reduction_can_mark_removed_edges() {
+    let reduced = dependencies()
+        .transitive_reduction(&TransitiveOptions::new().with_mark("transitive"))
+        .unwrap();
+
+    assert_eq!(reduced.edges.len(), 4);
+    assert_eq!(reduced.edges[2].attr("transitive"), Some("removed"));
+    assert_eq!(reduced.edges[2].attr("color"), Some("red"));
+    assert_eq!(reduced.edges[0].attr("transitive"), None);
+}
+
+#[test]
+fn closure_adds_reachable_pairs() {
+    let closed = dependencies()
+        .transitive_closure(&TransitiveOptions::new().with_mark("transitive"))
+        .unwrap();
+
+    assert_eq!(pairs(&closed)[4..], [("app", "alloc"), ("http", "alloc")]);
+    assert_eq!(closed.edges[4].attr("transitive"), Some("added"));
+    assert_eq!(closed.edges[2].attr("color"), Some("red"));
+}
+
+#[test]
+fn closure_of_a_cycle_includes_self_loops() {
+    let graph = Graph::new()
+        .with_directed(true)
+        .with_edges(&vec![Edge::new("a", "b"), Edge::new("b", "a")]);
+
+    let closed = graph.transitive_closure(&TransitiveOptions::new()).unwrap();
+    assert_eq!(
+        pairs(&closed),
+        [("a", "b"), ("b", "a"), ("a", "a"), ("b", "b")]
+    );
+}
+
+#[test]
+fn rejects_undirected_and_cyclic_graphs() {
+    let undirected = Graph::new().with_edges(&vec![Edge::new("a", "b")]);
+    assert_eq!(
+        undirected.transitive_closure(&TransitiveOptions::new()),
+        Err(TransitiveError::Undirected)
+    );
+
+    let cyclic = Graph::new().with_directed(true).with_edges(&vec![
+        Edge::new("a", "b"),
+        Edge::new("b", "c"),
+        Edge::new("c", "b"),
+    ]);
+    assert_eq!(
+        cyclic.transitive_reduction(&TransitiveOptions::new()),
+        Err(TransitiveError::Cyclic {
+            node: "b".to_string()
+        })
+    );
+}
@@ -0,0 +1,107 @@
+//! Compares lexing and parsing with tokens that borrow from the source
+//! against tokens that own their text.
+//!
+//! Run with `cargo bench -p graph-parser`. Prints throughput and heap
+//! allocations for each way of getting through the same generated file.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use graph_parser::lexer::Lexer;
+use graph_parser::parser::{parse, parse_chars};
+use graph_parser::streams::statement_stream::StatementStream;
+
+/// Counts allocations, so the benchmark shows what borrowing saves.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+const ROUNDS: usize = 5;
+
+/// A graph of `nodes` nodes with labels and a few edges each.
+fn source(nodes: usize) -> String {
+    let mut text = String::from("digraph bench {\n    node [shape=box, color=\"gray\"]\n");
+    for i in 0..nodes {
+        text.push_str(&format!(
+            "    n{i} [label=\"node number {i}\", weight={}.5]\n",
+            i % 10
+        ));
+        for step in [1, 7, 31] {
+            text.push_str(&format!(
+                "    n{i} -> n{} [label=\"edge {i}\"] // to the next\n",
+                (i + step) % nodes
+            ));
+        }
+    }
+    text.push_str("}\n");
+    text
+}
+
+/// Runs `work` a few times and reports the fastest round.
+fn bench(name: &str, bytes: usize, mut work: impl FnMut() -> usize) {
+    let mut best = Duration::MAX;
+    let mut allocations = 0;
+    for _ in 0..ROUNDS {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        black_box(work());
+        best = best.min(start.elapsed());
+        allocations = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    }
+
+    let megabytes = bytes as f64 / 1_000_000.0;
+    println!(
+        "{name:<28} {:>8.1} MB/s {:>12} allocations ({:.1} per KB)",
+        megabytes / best.as_secs_f64(),
+        allocations,
+        allocations as f64 / (bytes as f64 / 1000.0),
+    );
+}
+
+fn main() {
+    let text = source(50_000);
+    println!("input: {:.1} MB", text.len() as f64 / 1_000_000.0);
+
+    bench("lex, owned tokens", text.len(), || {
+        Lexer::new(text.chars()).map(Result::unwrap).count()
+    });
+    bench("lex, borrowed tokens", text.len(), || {
+        Lexer::borrowed(&text).map(Result::unwrap).count()
+    });
+    bench("parse, owned tokens", text.len(), || {
+        parse_chars(text.chars()).unwrap().statements.len()
+    });
+    bench("parse, borrowed tokens", text.len(), || {
+        parse(&text).unwrap().statements.len()
+    });
+    // Turns each top-level statement into a `Statement` as soon as it is
+    // parsed, which costs a little more per statement than converting the
+    // whole tree at once but keeps memory flat however long the input is.
+    bench("stream from a reader", text.len(), || {
+        StatementStream::new(text.as_bytes())
+            .unwrap()
+            .map(Result::unwrap)
+            .count()
+    });
+}
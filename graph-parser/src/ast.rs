@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+
+/// A parsed file. Ids and values borrow from the source text wherever no
+/// escape had to be resolved; `into_owned` detaches the tree from it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphAST<'a> {
+    /// Comments and blank lines before the header.
+    pub leading: Vec<Statement<'a>>,
+    pub strict: bool,
+    pub directed: bool,
+    pub id: Option<Cow<'a, str>>,
+    pub statements: Vec<Statement<'a>>,
+    /// Comments and blank lines after the closing brace.
+    pub trailing: Vec<Statement<'a>>,
+}
+
+impl<'a> GraphAST<'a> {
+    /// The comments documenting each node that has any: those on the lines
+    /// right above a statement declaring it, then those following it on its
+    /// line. A node declared more than once collects the comments of every
+    /// declaration, in order, under its first one. `#` lines are
+    /// preprocessor output, not documentation.
+    pub fn node_comments(&self) -> Vec<(&NodeId<'a>, Vec<&Comment<'a>>)> {
+        let mut found = Vec::new();
+        attach_comments(&self.statements, &mut found);
+
+        let mut merged: Vec<(&NodeId<'a>, Vec<&Comment<'a>>)> = Vec::new();
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for (node, comments) in found {
+            if comments.is_empty() {
+                continue;
+            }
+            match index.entry(node.id.as_ref()) {
+                Entry::Occupied(entry) => merged[*entry.get()].1.extend(comments),
+                Entry::Vacant(entry) => {
+                    entry.insert(merged.len());
+                    merged.push((node, comments));
+                }
+            }
+        }
+        merged
+    }
+
+    pub fn into_owned(self) -> GraphAST<'static> {
+        GraphAST {
+            leading: owned_statements(self.leading),
+            strict: self.strict,
+            directed: self.directed,
+            id: self.id.map(owned),
+            statements: owned_statements(self.statements),
+            trailing: owned_statements(self.trailing),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Statement<'a> {
+    Node(NodeStatement<'a>),
+    Edge(EdgeStatement<'a>),
+    /// `graph [...]`, `node [...]` or `edge [...]`.
+    Attr(AttrStatement<'a>),
+    /// A bare `key = value`, setting a graph attribute.
+    Assignment(Attribute<'a>),
+    Subgraph(Subgraph<'a>),
+    Comment(Comment<'a>),
+    /// Separates groups of statements.
+    BlankLine,
+}
+
+impl Statement<'_> {
+    pub fn into_owned(self) -> Statement<'static> {
+        match self {
+            Statement::Node(statement) => Statement::Node(NodeStatement {
+                node: statement.node.into_owned(),
+                attrs: owned_attrs(statement.attrs),
+            }),
+            Statement::Edge(statement) => Statement::Edge(EdgeStatement {
+                operands: statement
+                    .operands
+                    .into_iter()
+                    .map(|operand| match operand {
+                        EdgeOperand::Node(node) => EdgeOperand::Node(node.into_owned()),
+                        EdgeOperand::Subgraph(subgraph) => {
+                            EdgeOperand::Subgraph(subgraph.into_owned())
+                        }
+                    })
+                    .collect(),
+                ops: statement.ops,
+                attrs: owned_attrs(statement.attrs),
+            }),
+            Statement::Attr(statement) => Statement::Attr(AttrStatement {
+                target: statement.target,
+                attrs: owned_attrs(statement.attrs),
+            }),
+            Statement::Assignment(attr) => Statement::Assignment(attr.into_owned()),
+            Statement::Subgraph(subgraph) => Statement::Subgraph(subgraph.into_owned()),
+            Statement::Comment(comment) => Statement::Comment(Comment {
+                text: owned(comment.text),
+                trailing: comment.trailing,
+            }),
+            Statement::BlankLine => Statement::BlankLine,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comment<'a> {
+    /// The comment including its delimiters.
+    pub text: Cow<'a, str>,
+    /// Whether it follows code on the same line, rather than standing alone.
+    pub trailing: bool,
+}
+
+impl Comment<'_> {
+    /// The text without its delimiters, with each line trimmed and a block
+    /// comment's leading `*`s removed.
+    pub fn body(&self) -> String {
+        let text = &self.text;
+        let inner = if let Some(block) = text.strip_prefix("/*") {
+            block.strip_suffix("*/").unwrap_or(block)
+        } else {
+            text.strip_prefix("//")
+                .or_else(|| text.strip_prefix('#'))
+                .unwrap_or(text)
+        };
+        let lines: Vec<&str> = inner
+            .lines()
+            .map(|line| {
+                let line = line.trim();
+                line.strip_prefix('*').map_or(line, str::trim_start)
+            })
+            .collect();
+        lines.join("\n").trim().to_string()
+    }
+
+    /// A `#` line, which Graphviz takes for C preprocessor output.
+    pub fn is_preprocessor(&self) -> bool {
+        self.text.starts_with('#')
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Attribute<'a> {
+    pub key: Cow<'a, str>,
+    pub value: Cow<'a, str>,
+    /// Whether `value` is an HTML string like `<<b>x</b>>`, as opposed to
+    /// text that merely looks like one, such as `"<b>"`.
+    pub html: bool,
+}
+
+impl<'a> Attribute<'a> {
+    pub fn new(key: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            html: false,
+        }
+    }
+
+    pub fn html(key: impl Into<Cow<'a, str>>, value: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            html: true,
+            ..Self::new(key, value)
+        }
+    }
+
+    pub fn into_owned(self) -> Attribute<'static> {
+        Attribute {
+            key: owned(self.key),
+            value: owned(self.value),
+            html: self.html,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeId<'a> {
+    pub id: Cow<'a, str>,
+    /// Everything after the first `:`, e.g. `p1:n`.
+    pub port: Option<Cow<'a, str>>,
+}
+
+impl<'a> NodeId<'a> {
+    pub fn new(id: impl Into<Cow<'a, str>>) -> Self {
+        Self {
+            id: id.into(),
+            port: None,
+        }
+    }
+
+    pub fn into_owned(self) -> NodeId<'static> {
+        NodeId {
+            id: owned(self.id),
+            port: self.port.map(owned),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeStatement<'a> {
+    pub node: NodeId<'a>,
+    /// All attribute lists of the statement, in order.
+    pub attrs: Vec<Attribute<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeOp {
+    /// `->`
+    Directed,
+    /// `--`
+    Undirected,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EdgeOperand<'a> {
+    Node(NodeId<'a>),
+    Subgraph(Subgraph<'a>),
+}
+
+/// A chain such as `a -> b -> {c d}`. `ops` sits between `operands`, so it
+/// has one entry fewer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EdgeStatement<'a> {
+    pub operands: Vec<EdgeOperand<'a>>,
+    pub ops: Vec<EdgeOp>,
+    pub attrs: Vec<Attribute<'a>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttrTarget {
+    Graph,
+    Node,
+    Edge,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttrStatement<'a> {
+    pub target: AttrTarget,
+    pub attrs: Vec<Attribute<'a>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Subgraph<'a> {
+    pub id: Option<Cow<'a, str>>,
+    pub statements: Vec<Statement<'a>>,
+}
+
+impl Subgraph<'_> {
+    pub fn into_owned(self) -> Subgraph<'static> {
+        Subgraph {
+            id: self.id.map(owned),
+            statements: owned_statements(self.statements),
+        }
+    }
+}
+
+fn attach_comments<'s, 'a>(
+    statements: &'s [Statement<'a>],
+    found: &mut Vec<(&'s NodeId<'a>, Vec<&'s Comment<'a>>)>,
+) {
+    let mut above = Vec::new();
+    // Where the previous statement went in `found`, if it was a node.
+    let mut previous: Option<usize> = None;
+    for statement in statements {
+        match statement {
+            Statement::Comment(comment) if comment.trailing => {
+                if let Some(index) = previous {
+                    found[index].1.push(comment);
+                }
+                continue;
+            }
+            Statement::Comment(comment) if !comment.is_preprocessor() => above.push(comment),
+            Statement::Node(statement) => {
+                found.push((&statement.node, std::mem::take(&mut above)));
+                previous = Some(found.len() - 1);
+                continue;
+            }
+            Statement::Subgraph(subgraph) => {
+                attach_comments(&subgraph.statements, found);
+                above.clear();
+            }
+            _ => above.clear(),
+        }
+        previous = None;
+    }
+}
+
+fn owned(text: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+fn owned_attrs(attrs: Vec<Attribute>) -> Vec<Attribute<'static>> {
+    attrs.into_iter().map(Attribute::into_owned).collect()
+}
+
+fn owned_statements(statements: Vec<Statement>) -> Vec<Statement<'static>> {
+    statements.into_iter().map(Statement::into_owned).collect()
+}
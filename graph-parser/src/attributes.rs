@@ -0,0 +1,1169 @@
+use crate::ast::AttrTarget;
+
+/// A Graphviz attribute the tooling knows about.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AttributeInfo {
+    pub name: &'static str,
+    /// Where the attribute may be set. Subgraphs and clusters count as `Graph`.
+    pub targets: &'static [AttrTarget],
+    /// The allowed values, or empty if the value is free-form.
+    pub values: &'static [&'static str],
+    pub description: &'static str,
+}
+
+const G: AttrTarget = AttrTarget::Graph;
+const N: AttrTarget = AttrTarget::Node;
+const E: AttrTarget = AttrTarget::Edge;
+
+const SHAPES: &[&str] = &[
+    "box",
+    "circle",
+    "component",
+    "cylinder",
+    "diamond",
+    "doublecircle",
+    "ellipse",
+    "folder",
+    "hexagon",
+    "house",
+    "invtriangle",
+    "none",
+    "note",
+    "octagon",
+    "oval",
+    "parallelogram",
+    "plain",
+    "plaintext",
+    "point",
+    "rect",
+    "rectangle",
+    "record",
+    "square",
+    "star",
+    "tab",
+    "trapezium",
+    "triangle",
+];
+const NODE_STYLES: &[&str] = &[
+    "bold",
+    "dashed",
+    "diagonals",
+    "dotted",
+    "filled",
+    "invis",
+    "rounded",
+    "solid",
+    "striped",
+    "wedged",
+];
+const EDGE_STYLES: &[&str] = &["bold", "dashed", "dotted", "invis", "solid", "tapered"];
+const ARROWS: &[&str] = &[
+    "box", "crow", "curve", "diamond", "dot", "empty", "icurve", "inv", "none", "normal", "odot",
+    "open", "tee", "vee",
+];
+const COLORS: &[&str] = &[
+    "black",
+    "blue",
+    "brown",
+    "cyan",
+    "darkgreen",
+    "gold",
+    "gray",
+    "green",
+    "lightblue",
+    "lightgray",
+    "magenta",
+    "navy",
+    "orange",
+    "pink",
+    "purple",
+    "red",
+    "transparent",
+    "white",
+    "yellow",
+];
+const BOOLEANS: &[&str] = &["false", "true"];
+
+pub const ATTRIBUTES: &[AttributeInfo] = &[
+    AttributeInfo {
+        name: "_background",
+        targets: &[G],
+        values: &[],
+        description: "Xdot drawing to render under the graph.",
+    },
+    AttributeInfo {
+        name: "area",
+        targets: &[N, G],
+        values: &[],
+        description: "Preferred area of a node or cluster, for patchwork.",
+    },
+    AttributeInfo {
+        name: "arrowhead",
+        targets: &[E],
+        values: ARROWS,
+        description: "Style of the arrowhead at the head end.",
+    },
+    AttributeInfo {
+        name: "arrowsize",
+        targets: &[E],
+        values: &[],
+        description: "Scale of the arrowheads.",
+    },
+    AttributeInfo {
+        name: "arrowtail",
+        targets: &[E],
+        values: ARROWS,
+        description: "Style of the arrowhead at the tail end.",
+    },
+    AttributeInfo {
+        name: "bb",
+        targets: &[G],
+        values: &[],
+        description: "Bounding box of the drawing, set by the layout.",
+    },
+    AttributeInfo {
+        name: "beautify",
+        targets: &[G],
+        values: &[],
+        description: "Whether to spread leaves of a node evenly around it, for sfdp.",
+    },
+    AttributeInfo {
+        name: "bgcolor",
+        targets: &[G],
+        values: COLORS,
+        description: "Background colour of the canvas or cluster.",
+    },
+    AttributeInfo {
+        name: "center",
+        targets: &[G],
+        values: &[],
+        description: "Whether to center the drawing in the output canvas.",
+    },
+    AttributeInfo {
+        name: "charset",
+        targets: &[G],
+        values: &[],
+        description: "Character encoding of labels.",
+    },
+    AttributeInfo {
+        name: "class",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Classes to put on the element in SVG output.",
+    },
+    AttributeInfo {
+        name: "cluster",
+        targets: &[G],
+        values: &[],
+        description: "Whether the subgraph is a cluster.",
+    },
+    AttributeInfo {
+        name: "clusterrank",
+        targets: &[G],
+        values: &[],
+        description: "How clusters are handled by dot.",
+    },
+    AttributeInfo {
+        name: "color",
+        targets: &[G, N, E],
+        values: COLORS,
+        description: "Basic drawing colour.",
+    },
+    AttributeInfo {
+        name: "colorscheme",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Colour scheme that colour names are looked up in.",
+    },
+    AttributeInfo {
+        name: "comment",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Comment inserted into the output.",
+    },
+    AttributeInfo {
+        name: "compound",
+        targets: &[G],
+        values: BOOLEANS,
+        description: "Allows edges between clusters.",
+    },
+    AttributeInfo {
+        name: "concentrate",
+        targets: &[G],
+        values: &[],
+        description: "Whether to merge parallel edges.",
+    },
+    AttributeInfo {
+        name: "constraint",
+        targets: &[E],
+        values: BOOLEANS,
+        description: "Whether the edge is used in ranking the nodes.",
+    },
+    AttributeInfo {
+        name: "Damping",
+        targets: &[G],
+        values: &[],
+        description: "Factor damping node motion between iterations, for neato.",
+    },
+    AttributeInfo {
+        name: "decorate",
+        targets: &[E],
+        values: &[],
+        description: "Whether to connect the label to the edge with a line.",
+    },
+    AttributeInfo {
+        name: "defaultdist",
+        targets: &[G],
+        values: &[],
+        description: "Distance between nodes in separate components, for neato.",
+    },
+    AttributeInfo {
+        name: "dim",
+        targets: &[G],
+        values: &[],
+        description: "Number of dimensions used for the layout.",
+    },
+    AttributeInfo {
+        name: "dimen",
+        targets: &[G],
+        values: &[],
+        description: "Number of dimensions used for rendering.",
+    },
+    AttributeInfo {
+        name: "dir",
+        targets: &[E],
+        values: &["back", "both", "forward", "none"],
+        description: "Which ends of the edge get arrowheads.",
+    },
+    AttributeInfo {
+        name: "diredgeconstraints",
+        targets: &[G],
+        values: &[],
+        description: "Whether to constrain most edges to point downwards, for neato.",
+    },
+    AttributeInfo {
+        name: "distortion",
+        targets: &[N],
+        values: &[],
+        description: "Distortion factor of polygon shapes.",
+    },
+    AttributeInfo {
+        name: "dpi",
+        targets: &[G],
+        values: &[],
+        description: "Resolution of bitmap output and of SVG units.",
+    },
+    AttributeInfo {
+        name: "edgehref",
+        targets: &[E],
+        values: &[],
+        description: "Link for the non-label parts of the edge.",
+    },
+    AttributeInfo {
+        name: "edgetarget",
+        targets: &[E],
+        values: &[],
+        description: "Browser window for edgeURL.",
+    },
+    AttributeInfo {
+        name: "edgetooltip",
+        targets: &[E],
+        values: &[],
+        description: "Tooltip for the non-label parts of the edge.",
+    },
+    AttributeInfo {
+        name: "edgeURL",
+        targets: &[E],
+        values: &[],
+        description: "Link for the non-label parts of the edge.",
+    },
+    AttributeInfo {
+        name: "epsilon",
+        targets: &[G],
+        values: &[],
+        description: "Termination condition of the layout, for neato.",
+    },
+    AttributeInfo {
+        name: "esep",
+        targets: &[G],
+        values: &[],
+        description: "Margin around polygons for spline edge routing.",
+    },
+    AttributeInfo {
+        name: "fillcolor",
+        targets: &[G, N, E],
+        values: COLORS,
+        description: "Colour used to fill the background of a node or cluster.",
+    },
+    AttributeInfo {
+        name: "fixedsize",
+        targets: &[N],
+        values: &["false", "shape", "true"],
+        description: "Whether width and height are fixed sizes.",
+    },
+    AttributeInfo {
+        name: "fontcolor",
+        targets: &[G, N, E],
+        values: COLORS,
+        description: "Colour used for text.",
+    },
+    AttributeInfo {
+        name: "fontname",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Font used for text.",
+    },
+    AttributeInfo {
+        name: "fontnames",
+        targets: &[G],
+        values: &[],
+        description: "How font names are written in SVG output.",
+    },
+    AttributeInfo {
+        name: "fontpath",
+        targets: &[G],
+        values: &[],
+        description: "Directories searched for bitmap fonts.",
+    },
+    AttributeInfo {
+        name: "fontsize",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Font size, in points.",
+    },
+    AttributeInfo {
+        name: "forcelabels",
+        targets: &[G],
+        values: &[],
+        description: "Whether to place all xlabels, even overlapping ones.",
+    },
+    AttributeInfo {
+        name: "gradientangle",
+        targets: &[G, N],
+        values: &[],
+        description: "Angle of gradient fills.",
+    },
+    AttributeInfo {
+        name: "group",
+        targets: &[N],
+        values: &[],
+        description: "Keeps edges between nodes of the same group straight.",
+    },
+    AttributeInfo {
+        name: "head_lp",
+        targets: &[E],
+        values: &[],
+        description: "Position of the head label, set by the layout.",
+    },
+    AttributeInfo {
+        name: "headclip",
+        targets: &[E],
+        values: &[],
+        description: "Whether the head is clipped to the node boundary.",
+    },
+    AttributeInfo {
+        name: "headhref",
+        targets: &[E],
+        values: &[],
+        description: "Link for the head label.",
+    },
+    AttributeInfo {
+        name: "headlabel",
+        targets: &[E],
+        values: &[],
+        description: "Text label near the head of the edge.",
+    },
+    AttributeInfo {
+        name: "headport",
+        targets: &[E],
+        values: &[],
+        description: "Where on the head node the edge attaches.",
+    },
+    AttributeInfo {
+        name: "headtarget",
+        targets: &[E],
+        values: &[],
+        description: "Browser window for headURL.",
+    },
+    AttributeInfo {
+        name: "headtooltip",
+        targets: &[E],
+        values: &[],
+        description: "Tooltip for the head label.",
+    },
+    AttributeInfo {
+        name: "headURL",
+        targets: &[E],
+        values: &[],
+        description: "Link for the head label.",
+    },
+    AttributeInfo {
+        name: "height",
+        targets: &[N],
+        values: &[],
+        description: "Height of the node, in inches.",
+    },
+    AttributeInfo {
+        name: "href",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Synonym for URL.",
+    },
+    AttributeInfo {
+        name: "id",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Identifier of the element in SVG and image map output.",
+    },
+    AttributeInfo {
+        name: "image",
+        targets: &[N],
+        values: &[],
+        description: "File of an image to show inside the node.",
+    },
+    AttributeInfo {
+        name: "imagepath",
+        targets: &[G],
+        values: &[],
+        description: "Directories searched for image files.",
+    },
+    AttributeInfo {
+        name: "imagepos",
+        targets: &[N],
+        values: &[],
+        description: "Where to place the image inside the node.",
+    },
+    AttributeInfo {
+        name: "imagescale",
+        targets: &[N],
+        values: &[],
+        description: "How the image fills the node.",
+    },
+    AttributeInfo {
+        name: "inputscale",
+        targets: &[G],
+        values: &[],
+        description: "Scale applied to input positions, for neato and fdp.",
+    },
+    AttributeInfo {
+        name: "K",
+        targets: &[G],
+        values: &[],
+        description: "Spring constant, for fdp and sfdp.",
+    },
+    AttributeInfo {
+        name: "label",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Text label attached to the object.",
+    },
+    AttributeInfo {
+        name: "label_scheme",
+        targets: &[G],
+        values: &[],
+        description: "How sfdp treats nodes as labels.",
+    },
+    AttributeInfo {
+        name: "labelangle",
+        targets: &[E],
+        values: &[],
+        description: "Angle of head and tail labels from the edge.",
+    },
+    AttributeInfo {
+        name: "labeldistance",
+        targets: &[E],
+        values: &[],
+        description: "Distance of head and tail labels from the node.",
+    },
+    AttributeInfo {
+        name: "labelfloat",
+        targets: &[E],
+        values: &[],
+        description: "Whether edge labels may be placed loosely.",
+    },
+    AttributeInfo {
+        name: "labelfontcolor",
+        targets: &[E],
+        values: &[],
+        description: "Colour of head and tail labels.",
+    },
+    AttributeInfo {
+        name: "labelfontname",
+        targets: &[E],
+        values: &[],
+        description: "Font of head and tail labels.",
+    },
+    AttributeInfo {
+        name: "labelfontsize",
+        targets: &[E],
+        values: &[],
+        description: "Font size of head and tail labels.",
+    },
+    AttributeInfo {
+        name: "labelhref",
+        targets: &[E],
+        values: &[],
+        description: "Synonym for labelURL.",
+    },
+    AttributeInfo {
+        name: "labeljust",
+        targets: &[G],
+        values: &[],
+        description: "Justification of graph and cluster labels.",
+    },
+    AttributeInfo {
+        name: "labelloc",
+        targets: &[G, N],
+        values: &["b", "c", "t"],
+        description: "Vertical placement of labels.",
+    },
+    AttributeInfo {
+        name: "labeltarget",
+        targets: &[E],
+        values: &[],
+        description: "Browser window for labelURL.",
+    },
+    AttributeInfo {
+        name: "labeltooltip",
+        targets: &[E],
+        values: &[],
+        description: "Tooltip for the edge label.",
+    },
+    AttributeInfo {
+        name: "labelURL",
+        targets: &[E],
+        values: &[],
+        description: "Link for head and tail labels.",
+    },
+    AttributeInfo {
+        name: "landscape",
+        targets: &[G],
+        values: &[],
+        description: "Whether to render in landscape orientation.",
+    },
+    AttributeInfo {
+        name: "layer",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Layers the element is drawn in.",
+    },
+    AttributeInfo {
+        name: "layerlistsep",
+        targets: &[G],
+        values: &[],
+        description: "Separator characters of layer lists.",
+    },
+    AttributeInfo {
+        name: "layers",
+        targets: &[G],
+        values: &[],
+        description: "Names of the layers.",
+    },
+    AttributeInfo {
+        name: "layerselect",
+        targets: &[G],
+        values: &[],
+        description: "Layers to emit.",
+    },
+    AttributeInfo {
+        name: "layersep",
+        targets: &[G],
+        values: &[],
+        description: "Separator characters of the layers attribute.",
+    },
+    AttributeInfo {
+        name: "layout",
+        targets: &[G],
+        values: &[
+            "circo",
+            "dot",
+            "fdp",
+            "neato",
+            "osage",
+            "patchwork",
+            "sfdp",
+            "twopi",
+        ],
+        description: "Which layout engine to use.",
+    },
+    AttributeInfo {
+        name: "len",
+        targets: &[E],
+        values: &[],
+        description: "Preferred edge length, for neato and fdp.",
+    },
+    AttributeInfo {
+        name: "levels",
+        targets: &[G],
+        values: &[],
+        description: "Number of levels in the multilevel scheme, for sfdp.",
+    },
+    AttributeInfo {
+        name: "levelsgap",
+        targets: &[G],
+        values: &[],
+        description: "Strictness of level constraints, for neato.",
+    },
+    AttributeInfo {
+        name: "lhead",
+        targets: &[E],
+        values: &[],
+        description: "Cluster the edge is clipped at, at its head.",
+    },
+    AttributeInfo {
+        name: "lheight",
+        targets: &[G],
+        values: &[],
+        description: "Height of the graph or cluster label, set by the layout.",
+    },
+    AttributeInfo {
+        name: "linelength",
+        targets: &[G],
+        values: &[],
+        description: "Length at which long strings are split in output.",
+    },
+    AttributeInfo {
+        name: "lp",
+        targets: &[G, E],
+        values: &[],
+        description: "Label position, set by the layout.",
+    },
+    AttributeInfo {
+        name: "ltail",
+        targets: &[E],
+        values: &[],
+        description: "Cluster the edge is clipped at, at its tail.",
+    },
+    AttributeInfo {
+        name: "lwidth",
+        targets: &[G],
+        values: &[],
+        description: "Width of the graph or cluster label, set by the layout.",
+    },
+    AttributeInfo {
+        name: "margin",
+        targets: &[G, N],
+        values: &[],
+        description: "Margin around the drawing, or around a node's label.",
+    },
+    AttributeInfo {
+        name: "maxiter",
+        targets: &[G],
+        values: &[],
+        description: "Maximum number of layout iterations.",
+    },
+    AttributeInfo {
+        name: "mclimit",
+        targets: &[G],
+        values: &[],
+        description: "Scale of the crossing minimization iterations, for dot.",
+    },
+    AttributeInfo {
+        name: "mindist",
+        targets: &[G],
+        values: &[],
+        description: "Minimum separation between nodes, for circo.",
+    },
+    AttributeInfo {
+        name: "minlen",
+        targets: &[E],
+        values: &[],
+        description: "Minimum rank difference between the ends of the edge.",
+    },
+    AttributeInfo {
+        name: "mode",
+        targets: &[G],
+        values: &[],
+        description: "Technique used to optimize the layout.",
+    },
+    AttributeInfo {
+        name: "model",
+        targets: &[G],
+        values: &[],
+        description: "How the distance matrix is computed, for neato.",
+    },
+    AttributeInfo {
+        name: "newrank",
+        targets: &[G],
+        values: &[],
+        description: "Whether to use the single global ranking of dot.",
+    },
+    AttributeInfo {
+        name: "nodesep",
+        targets: &[G],
+        values: &[],
+        description: "Minimum space between two adjacent nodes in the same rank.",
+    },
+    AttributeInfo {
+        name: "nojustify",
+        targets: &[G, N, E],
+        values: &[],
+        description: "How multi-line labels are justified.",
+    },
+    AttributeInfo {
+        name: "normalize",
+        targets: &[G],
+        values: &[],
+        description: "Whether to rotate the final layout so an edge is horizontal.",
+    },
+    AttributeInfo {
+        name: "notranslate",
+        targets: &[G],
+        values: &[],
+        description: "Whether to keep the layout from being moved to the origin.",
+    },
+    AttributeInfo {
+        name: "nslimit",
+        targets: &[G],
+        values: &[],
+        description: "Limit of network simplex iterations for positioning.",
+    },
+    AttributeInfo {
+        name: "nslimit1",
+        targets: &[G],
+        values: &[],
+        description: "Limit of network simplex iterations for ranking.",
+    },
+    AttributeInfo {
+        name: "oneblock",
+        targets: &[G],
+        values: &[],
+        description: "Whether to draw all components on one circle, for circo.",
+    },
+    AttributeInfo {
+        name: "ordering",
+        targets: &[G, N],
+        values: &[],
+        description: "Keeps the order of out- or in-edges.",
+    },
+    AttributeInfo {
+        name: "orientation",
+        targets: &[G, N],
+        values: &[],
+        description: "Rotation of a node shape, or of the graph.",
+    },
+    AttributeInfo {
+        name: "outputorder",
+        targets: &[G],
+        values: &[],
+        description: "Order in which nodes and edges are drawn.",
+    },
+    AttributeInfo {
+        name: "overlap",
+        targets: &[G],
+        values: &[],
+        description: "How node overlaps are removed.",
+    },
+    AttributeInfo {
+        name: "overlap_scaling",
+        targets: &[G],
+        values: &[],
+        description: "Scale applied when removing overlaps.",
+    },
+    AttributeInfo {
+        name: "overlap_shrink",
+        targets: &[G],
+        values: &[],
+        description: "Whether to compress the layout after removing overlaps.",
+    },
+    AttributeInfo {
+        name: "pack",
+        targets: &[G],
+        values: &[],
+        description: "Whether and how far apart components are packed.",
+    },
+    AttributeInfo {
+        name: "packmode",
+        targets: &[G],
+        values: &[],
+        description: "How components are packed.",
+    },
+    AttributeInfo {
+        name: "pad",
+        targets: &[G],
+        values: &[],
+        description: "Extra space around the drawing.",
+    },
+    AttributeInfo {
+        name: "page",
+        targets: &[G],
+        values: &[],
+        description: "Width and height of output pages.",
+    },
+    AttributeInfo {
+        name: "pagedir",
+        targets: &[G],
+        values: &[],
+        description: "Order in which pages are emitted.",
+    },
+    AttributeInfo {
+        name: "pencolor",
+        targets: &[G],
+        values: &[],
+        description: "Colour of the cluster outline.",
+    },
+    AttributeInfo {
+        name: "penwidth",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Width of the pen used to draw lines.",
+    },
+    AttributeInfo {
+        name: "peripheries",
+        targets: &[G, N],
+        values: &[],
+        description: "Number of outlines around a node or cluster.",
+    },
+    AttributeInfo {
+        name: "pin",
+        targets: &[N],
+        values: &[],
+        description: "Whether the node keeps its input position.",
+    },
+    AttributeInfo {
+        name: "pos",
+        targets: &[N, E],
+        values: &[],
+        description: "Position of a node or spline control points of an edge.",
+    },
+    AttributeInfo {
+        name: "quadtree",
+        targets: &[G],
+        values: &[],
+        description: "Quadtree scheme used, for sfdp.",
+    },
+    AttributeInfo {
+        name: "quantum",
+        targets: &[G],
+        values: &[],
+        description: "Unit node sizes are rounded to.",
+    },
+    AttributeInfo {
+        name: "rank",
+        targets: &[G],
+        values: &["max", "min", "same", "sink", "source"],
+        description: "Rank constraints on the nodes of a subgraph.",
+    },
+    AttributeInfo {
+        name: "rankdir",
+        targets: &[G],
+        values: &["BT", "LR", "RL", "TB"],
+        description: "Direction of the layout.",
+    },
+    AttributeInfo {
+        name: "ranksep",
+        targets: &[G],
+        values: &[],
+        description: "Separation between ranks, in inches.",
+    },
+    AttributeInfo {
+        name: "rects",
+        targets: &[N],
+        values: &[],
+        description: "Rectangles of the record fields, set by the layout.",
+    },
+    AttributeInfo {
+        name: "regular",
+        targets: &[N],
+        values: &[],
+        description: "Whether the node is a regular polygon.",
+    },
+    AttributeInfo {
+        name: "remincross",
+        targets: &[G],
+        values: &[],
+        description: "Whether to run crossing minimization again with clusters.",
+    },
+    AttributeInfo {
+        name: "repulsiveforce",
+        targets: &[G],
+        values: &[],
+        description: "Power of the repulsive force, for sfdp.",
+    },
+    AttributeInfo {
+        name: "resolution",
+        targets: &[G],
+        values: &[],
+        description: "Synonym for dpi.",
+    },
+    AttributeInfo {
+        name: "root",
+        targets: &[G, N],
+        values: &[],
+        description: "Center node of the layout, for twopi and circo.",
+    },
+    AttributeInfo {
+        name: "rotate",
+        targets: &[G],
+        values: &[],
+        description: "Rotation of the drawing; 90 means landscape.",
+    },
+    AttributeInfo {
+        name: "rotation",
+        targets: &[G],
+        values: &[],
+        description: "Counter-clockwise rotation of the layout, for sfdp.",
+    },
+    AttributeInfo {
+        name: "samehead",
+        targets: &[E],
+        values: &[],
+        description: "Group of edges sharing their head end point.",
+    },
+    AttributeInfo {
+        name: "sametail",
+        targets: &[E],
+        values: &[],
+        description: "Group of edges sharing their tail end point.",
+    },
+    AttributeInfo {
+        name: "samplepoints",
+        targets: &[N],
+        values: &[],
+        description: "Number of points used for ellipse and circle shapes.",
+    },
+    AttributeInfo {
+        name: "scale",
+        targets: &[G],
+        values: &[],
+        description: "Scale applied to the layout.",
+    },
+    AttributeInfo {
+        name: "searchsize",
+        targets: &[G],
+        values: &[],
+        description: "Search limit for the lowest cut value, for dot.",
+    },
+    AttributeInfo {
+        name: "sep",
+        targets: &[G],
+        values: &[],
+        description: "Margin around nodes when removing overlaps.",
+    },
+    AttributeInfo {
+        name: "shape",
+        targets: &[N],
+        values: SHAPES,
+        description: "Shape of the node.",
+    },
+    AttributeInfo {
+        name: "shapefile",
+        targets: &[N],
+        values: &[],
+        description: "File with user-defined node shape.",
+    },
+    AttributeInfo {
+        name: "showboxes",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Debugging output of the spline routing boxes.",
+    },
+    AttributeInfo {
+        name: "sides",
+        targets: &[N],
+        values: &[],
+        description: "Number of sides of a polygon shape.",
+    },
+    AttributeInfo {
+        name: "size",
+        targets: &[G],
+        values: &[],
+        description: "Maximum width and height of the drawing, in inches.",
+    },
+    AttributeInfo {
+        name: "skew",
+        targets: &[N],
+        values: &[],
+        description: "Skew factor of polygon shapes.",
+    },
+    AttributeInfo {
+        name: "smoothing",
+        targets: &[G],
+        values: &[],
+        description: "Post-processing step that smooths node density.",
+    },
+    AttributeInfo {
+        name: "sortv",
+        targets: &[G, N],
+        values: &[],
+        description: "Sort order of components when packing.",
+    },
+    AttributeInfo {
+        name: "splines",
+        targets: &[G],
+        values: &[
+            "curved", "false", "line", "none", "ortho", "polyline", "spline", "true",
+        ],
+        description: "How edges are drawn.",
+    },
+    AttributeInfo {
+        name: "start",
+        targets: &[G],
+        values: &[],
+        description: "Initial node placement.",
+    },
+    AttributeInfo {
+        name: "style",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Style information for components of the graph.",
+    },
+    AttributeInfo {
+        name: "stylesheet",
+        targets: &[G],
+        values: &[],
+        description: "Stylesheet referenced from SVG output.",
+    },
+    AttributeInfo {
+        name: "tail_lp",
+        targets: &[E],
+        values: &[],
+        description: "Position of the tail label, set by the layout.",
+    },
+    AttributeInfo {
+        name: "tailclip",
+        targets: &[E],
+        values: &[],
+        description: "Whether the tail is clipped to the node boundary.",
+    },
+    AttributeInfo {
+        name: "tailhref",
+        targets: &[E],
+        values: &[],
+        description: "Link for the tail label.",
+    },
+    AttributeInfo {
+        name: "taillabel",
+        targets: &[E],
+        values: &[],
+        description: "Text label near the tail of the edge.",
+    },
+    AttributeInfo {
+        name: "tailport",
+        targets: &[E],
+        values: &[],
+        description: "Where on the tail node the edge attaches.",
+    },
+    AttributeInfo {
+        name: "tailtarget",
+        targets: &[E],
+        values: &[],
+        description: "Browser window for tailURL.",
+    },
+    AttributeInfo {
+        name: "tailtooltip",
+        targets: &[E],
+        values: &[],
+        description: "Tooltip for the tail label.",
+    },
+    AttributeInfo {
+        name: "tailURL",
+        targets: &[E],
+        values: &[],
+        description: "Link for the tail label.",
+    },
+    AttributeInfo {
+        name: "target",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Browser window for URL.",
+    },
+    AttributeInfo {
+        name: "TBbalance",
+        targets: &[G],
+        values: &[],
+        description: "Where nodes without edges on one side are ranked.",
+    },
+    AttributeInfo {
+        name: "tooltip",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Tooltip text in SVG and image map output.",
+    },
+    AttributeInfo {
+        name: "truecolor",
+        targets: &[G],
+        values: &[],
+        description: "Whether bitmap output uses a truecolor model.",
+    },
+    AttributeInfo {
+        name: "URL",
+        targets: &[G, N, E],
+        values: &[],
+        description: "Link for the element in SVG, PostScript and image map output.",
+    },
+    AttributeInfo {
+        name: "vertices",
+        targets: &[N],
+        values: &[],
+        description: "Vertices of the node polygon, set by the layout.",
+    },
+    AttributeInfo {
+        name: "viewport",
+        targets: &[G],
+        values: &[],
+        description: "Part of the drawing that is rendered.",
+    },
+    AttributeInfo {
+        name: "voro_margin",
+        targets: &[G],
+        values: &[],
+        description: "Margin tuning when removing overlaps with Voronoi diagrams.",
+    },
+    AttributeInfo {
+        name: "weight",
+        targets: &[E],
+        values: &[],
+        description: "Weight of the edge; heavier edges are kept shorter and straighter.",
+    },
+    AttributeInfo {
+        name: "width",
+        targets: &[N],
+        values: &[],
+        description: "Width of the node, in inches.",
+    },
+    AttributeInfo {
+        name: "xdotversion",
+        targets: &[G],
+        values: &[],
+        description: "Version of xdot output.",
+    },
+    AttributeInfo {
+        name: "xlabel",
+        targets: &[N, E],
+        values: &[],
+        description: "External label placed outside the node or beside the edge.",
+    },
+    AttributeInfo {
+        name: "xlp",
+        targets: &[N, E],
+        values: &[],
+        description: "Position of the xlabel, set by the layout.",
+    },
+    AttributeInfo {
+        name: "z",
+        targets: &[N],
+        values: &[],
+        description: "Z coordinate of the node, for 3D output.",
+    },
+];
+
+pub fn attribute(name: &str) -> Option<&'static AttributeInfo> {
+    ATTRIBUTES.iter().find(|info| info.name == name)
+}
+
+/// The attributes that apply to `target`, by name.
+pub fn attributes_for(target: AttrTarget) -> impl Iterator<Item = &'static AttributeInfo> {
+    ATTRIBUTES
+        .iter()
+        .filter(move |info| info.targets.contains(&target))
+}
+
+/// Allowed values of `name` on `target`. `style` differs between nodes and edges.
+pub fn values_for(name: &str, target: AttrTarget) -> &'static [&'static str] {
+    match (name, target) {
+        ("style", AttrTarget::Node) => NODE_STYLES,
+        ("style", AttrTarget::Edge) => EDGE_STYLES,
+        _ => attribute(name).map_or(&[], |info| info.values),
+    }
+}
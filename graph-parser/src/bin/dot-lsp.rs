@@ -0,0 +1,6 @@
+use std::io;
+
+fn main() -> io::Result<()> {
+    let code = graph_parser::lsp::serve(io::stdin().lock(), io::stdout().lock())?;
+    std::process::exit(code);
+}
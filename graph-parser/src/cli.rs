@@ -0,0 +1,438 @@
+use std::fs;
+use std::io::{Read, Write};
+
+use graph_dsl::graph::metrics::DegreeKind;
+use graph_dsl::graph::Graph;
+
+use crate::ast::GraphAST;
+use crate::cst;
+use crate::diagnostic::Diagnostic;
+use crate::export::{to_graphml, to_json, to_mermaid};
+use crate::format::format;
+use crate::lint::{lint, mismatched_edge_ops, LintConfig, Severity};
+
+const USAGE: &str = "usage: graph-parser <command> [options] [FILE...]
+
+commands:
+    check [FILE...]                        report syntax errors
+    lint [FILE...]                         report likely mistakes
+    fmt [--check | --write] [FILE...]      reformat files
+    convert --to json|graphml|mermaid [FILE]
+    stats [FILE]                           print node, edge and degree counts
+
+Without FILE, or with `-`, input is read from stdin.";
+
+/// Exit codes: everything was fine.
+pub const SUCCESS: i32 = 0;
+/// The input has errors, lints at error level, or `fmt --check` found files
+/// to reformat.
+pub const FAILURE: i32 = 1;
+/// Bad arguments or unreadable files.
+pub const USAGE_ERROR: i32 = 2;
+
+struct Input {
+    /// The path as given, or `<stdin>`.
+    name: String,
+    path: Option<String>,
+    text: String,
+}
+
+/// Runs the command line `args` (without the program name) and returns the
+/// exit code. Errors go to `stderr`, results to `stdout`.
+pub fn run(
+    args: &[String],
+    stdin: impl Read,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> i32 {
+    let result = match args.first().map(String::as_str) {
+        Some("check") => check(&args[1..], stdin, stderr),
+        Some("lint") => lint_files(&args[1..], stdin, stdout, stderr),
+        Some("fmt") => fmt(&args[1..], stdin, stdout, stderr),
+        Some("convert") => convert(&args[1..], stdin, stdout, stderr),
+        Some("stats") => stats(&args[1..], stdin, stdout, stderr),
+        Some("help" | "-h" | "--help") => writeln!(stdout, "{USAGE}")
+            .map(|_| SUCCESS)
+            .map_err(|error| error.to_string()),
+        Some(other) => Err(format!("unknown command {other:?}\n\n{USAGE}")),
+        None => Err(USAGE.to_string()),
+    };
+
+    result.unwrap_or_else(|message| {
+        let _ = writeln!(stderr, "error: {message}");
+        USAGE_ERROR
+    })
+}
+
+fn check(args: &[String], stdin: impl Read, stderr: &mut impl Write) -> Result<i32, String> {
+    let files = positional(args, &[])?;
+    let mut code = SUCCESS;
+    for input in read_inputs(&files, stdin)? {
+        if parse_input(&input, stderr)?.is_none() {
+            code = FAILURE;
+        }
+    }
+    Ok(code)
+}
+
+/// Prints findings as `name:line:column: severity[lint-id]: message`.
+fn lint_files(
+    args: &[String],
+    stdin: impl Read,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> Result<i32, String> {
+    let files = positional(args, &[])?;
+    let mut code = SUCCESS;
+    for input in read_inputs(&files, stdin)? {
+        let tree = match cst::parse(&input.text) {
+            Ok(tree) => tree,
+            Err(diagnostic) => {
+                report_syntax_error(&input, &diagnostic, stderr)?;
+                code = FAILURE;
+                continue;
+            }
+        };
+        for finding in lint(&tree, &LintConfig::new()) {
+            writeln!(
+                stdout,
+                "{}:{}:{}: {}[{}]: {}",
+                input.name,
+                finding.diagnostic.line,
+                finding.diagnostic.column,
+                finding.severity.name(),
+                finding.lint.id(),
+                finding.diagnostic.message
+            )
+            .map_err(|error| error.to_string())?;
+            if finding.severity == Severity::Error {
+                code = FAILURE;
+            }
+        }
+    }
+    Ok(code)
+}
+
+fn fmt(
+    args: &[String],
+    stdin: impl Read,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> Result<i32, String> {
+    let check_only = args.iter().any(|arg| arg == "--check");
+    let write = args.iter().any(|arg| arg == "--write" || arg == "-w");
+    if check_only && write {
+        return Err("--check and --write cannot be combined".to_string());
+    }
+    let files = positional(args, &["--check", "--write", "-w"])?;
+
+    let mut code = SUCCESS;
+    for input in read_inputs(&files, stdin)? {
+        let Some(ast) = parse_input(&input, stderr)? else {
+            code = FAILURE;
+            continue;
+        };
+        let formatted = format(&ast);
+
+        match (&input.path, check_only, write) {
+            (_, true, _) => {
+                if formatted != input.text {
+                    writeln!(stdout, "{}", input.name).map_err(|error| error.to_string())?;
+                    code = FAILURE;
+                }
+            }
+            (Some(path), _, true) => {
+                if formatted != input.text {
+                    fs::write(path, &formatted).map_err(|error| format!("{path}: {error}"))?;
+                }
+            }
+            _ => write!(stdout, "{formatted}").map_err(|error| error.to_string())?,
+        }
+    }
+    Ok(code)
+}
+
+fn convert(
+    args: &[String],
+    stdin: impl Read,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> Result<i32, String> {
+    let mut rest = args.to_vec();
+    let index = rest
+        .iter()
+        .position(|arg| arg == "--to")
+        .ok_or("convert needs --to json, graphml or mermaid")?;
+    if index + 1 >= rest.len() {
+        return Err("--to needs a format".to_string());
+    }
+    let to: Vec<String> = rest.drain(index..index + 2).collect();
+    let export: fn(&Graph) -> String = match to[1].as_str() {
+        "json" => to_json,
+        "graphml" => to_graphml,
+        "mermaid" => to_mermaid,
+        other => return Err(format!("unknown format {other:?}")),
+    };
+
+    let Some(graph) = single_graph(&rest, stdin, stderr)? else {
+        return Ok(FAILURE);
+    };
+    write!(stdout, "{}", export(&graph)).map_err(|error| error.to_string())?;
+    Ok(SUCCESS)
+}
+
+fn stats(
+    args: &[String],
+    stdin: impl Read,
+    stdout: &mut impl Write,
+    stderr: &mut impl Write,
+) -> Result<i32, String> {
+    let Some(graph) = single_graph(args, stdin, stderr)? else {
+        return Ok(FAILURE);
+    };
+
+    let mut lines = vec![
+        format!("nodes: {}", graph.node_ids().len()),
+        format!("edges: {}", graph.edges.len()),
+        format!("directed: {}", graph.directed),
+        format!("density: {:.3}", graph.density()),
+        degree_summary("degree", &graph, DegreeKind::Total),
+    ];
+    if graph.directed {
+        lines.push(degree_summary("in-degree", &graph, DegreeKind::In));
+        lines.push(degree_summary("out-degree", &graph, DegreeKind::Out));
+    }
+    writeln!(stdout, "{}", lines.join("\n")).map_err(|error| error.to_string())?;
+    Ok(SUCCESS)
+}
+
+fn degree_summary(name: &str, graph: &Graph, kind: DegreeKind) -> String {
+    let degrees: Vec<usize> = graph.degrees(kind).into_values().collect();
+    let min = degrees.iter().min().copied().unwrap_or(0);
+    let max = degrees.iter().max().copied().unwrap_or(0);
+    let mean = match degrees.len() {
+        0 => 0.0,
+        count => degrees.iter().sum::<usize>() as f64 / count as f64,
+    };
+    format!("{name}: min {min}, max {max}, mean {mean:.2}")
+}
+
+/// The graph of the one input `convert` and `stats` accept, or `None` after
+/// reporting why it does not parse.
+fn single_graph(
+    args: &[String],
+    stdin: impl Read,
+    stderr: &mut impl Write,
+) -> Result<Option<Graph>, String> {
+    let files = positional(args, &[])?;
+    if files.len() > 1 {
+        return Err("expected at most one file".to_string());
+    }
+    let input = read_inputs(&files, stdin)?.remove(0);
+    Ok(parse_input(&input, stderr)?.map(|ast| ast.to_graph()))
+}
+
+/// The non-flag arguments, rejecting flags other than `known`.
+fn positional(args: &[String], known: &[&str]) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    for arg in args {
+        if known.contains(&arg.as_str()) {
+            continue;
+        }
+        if arg.starts_with('-') && arg != "-" {
+            return Err(format!("unknown option {arg:?}"));
+        }
+        files.push(arg.clone());
+    }
+    Ok(files)
+}
+
+fn read_inputs(files: &[String], mut stdin: impl Read) -> Result<Vec<Input>, String> {
+    let mut read_stdin = || {
+        let mut text = String::new();
+        stdin
+            .read_to_string(&mut text)
+            .map_err(|error| format!("<stdin>: {error}"))?;
+        Ok::<_, String>(Input {
+            name: "<stdin>".to_string(),
+            path: None,
+            text,
+        })
+    };
+
+    if files.is_empty() {
+        return Ok(vec![read_stdin()?]);
+    }
+    files
+        .iter()
+        .map(|file| match file.as_str() {
+            "-" => read_stdin(),
+            path => fs::read_to_string(path)
+                .map(|text| Input {
+                    name: path.to_string(),
+                    path: Some(path.to_string()),
+                    text,
+                })
+                .map_err(|error| format!("{path}: {error}")),
+        })
+        .collect()
+}
+
+/// Parses an input, reporting errors to `stderr`. Like Graphviz, this
+/// rejects edge operators of the other kind of graph.
+fn parse_input<'a>(
+    input: &'a Input,
+    stderr: &mut impl Write,
+) -> Result<Option<GraphAST<'a>>, String> {
+    match cst::parse(&input.text) {
+        Ok(tree) => {
+            let mismatched = mismatched_edge_ops(&tree);
+            for (diagnostic, _) in &mismatched {
+                report_syntax_error(input, diagnostic, stderr)?;
+            }
+            Ok(mismatched.is_empty().then(|| tree.to_ast()))
+        }
+        Err(diagnostic) => {
+            report_syntax_error(input, &diagnostic, stderr)?;
+            Ok(None)
+        }
+    }
+}
+
+fn report_syntax_error(
+    input: &Input,
+    diagnostic: &Diagnostic,
+    stderr: &mut impl Write,
+) -> Result<(), String> {
+    writeln!(
+        stderr,
+        "{}:{}:{}: error: {}",
+        input.name, diagnostic.line, diagnostic.column, diagnostic.message
+    )
+    .map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{run, FAILURE, SUCCESS, USAGE_ERROR};
+
+    fn run_with(args: &[&str], stdin: &str) -> (i32, String, String) {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let code = run(&args, stdin.as_bytes(), &mut stdout, &mut stderr);
+        (
+            code,
+            String::from_utf8(stdout).unwrap(),
+            String::from_utf8(stderr).unwrap(),
+        )
+    }
+
+    #[test]
+    fn check_reports_diagnostics() {
+        assert_eq!(
+            run_with(&["check"], "graph { a -- b }"),
+            (SUCCESS, String::new(), String::new())
+        );
+
+        let (code, _, stderr) = run_with(&["check", "-"], "graph {\n  a -- }");
+        assert_eq!(code, FAILURE);
+        assert_eq!(
+            stderr,
+            "<stdin>:2:8: error: expected an identifier, found '}'\n"
+        );
+
+        let (code, _, stderr) = run_with(&["check"], "digraph { a -> b -- c }");
+        assert_eq!(code, FAILURE);
+        assert_eq!(
+            stderr,
+            "<stdin>:1:18: error: edges in a digraph are written with '->'\n"
+        );
+    }
+
+    #[test]
+    fn lint_reports_findings() {
+        let (code, stdout, _) = run_with(&["lint"], "graph {\n  a [colr=red]\n  a -> b\n}");
+        assert_eq!(code, FAILURE);
+        assert_eq!(
+            stdout,
+            "<stdin>:2:6: warning[unknown-attribute]: unknown attribute \"colr\"; did you mean \"color\"?
+<stdin>:3:5: error[edge-op-mismatch]: edges in a graph are written with '--'
+"
+        );
+
+        let (code, stdout, _) = run_with(&["lint"], "graph { a [shape=box] }");
+        assert_eq!((code, stdout.as_str()), (SUCCESS, ""));
+    }
+
+    #[test]
+    fn fmt_prints_and_checks() {
+        let (code, stdout, _) = run_with(&["fmt"], "digraph{a->b}");
+        assert_eq!(code, SUCCESS);
+        assert_eq!(stdout, "digraph {\n    a -> b;\n}\n");
+
+        let (code, stdout, _) = run_with(&["fmt", "--check"], "digraph{a->b}");
+        assert_eq!((code, stdout.as_str()), (FAILURE, "<stdin>\n"));
+
+        let (code, _, _) = run_with(&["fmt", "--check"], "digraph {\n    a -> b;\n}\n");
+        assert_eq!(code, SUCCESS);
+
+        let escaped = "graph {\n    a [label=\"\\\\\", tooltip=\"C:\\\\x\"];\n}\n";
+        let (code, stdout, _) = run_with(&["fmt"], escaped);
+        assert_eq!((code, stdout.as_str()), (SUCCESS, escaped));
+    }
+
+    #[test]
+    fn fmt_writes_files() {
+        let path =
+            std::env::temp_dir().join(format!("graph-parser-fmt-{}.dot", std::process::id()));
+        std::fs::write(&path, "graph{x}").unwrap();
+        let path_arg = path.to_str().unwrap();
+
+        let (code, stdout, _) = run_with(&["fmt", "-w", path_arg], "");
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!((code, stdout.as_str()), (SUCCESS, ""));
+        assert_eq!(written, "graph {\n    x;\n}\n");
+    }
+
+    #[test]
+    fn converts_and_summarises() {
+        let (code, stdout, _) = run_with(&["convert", "--to", "mermaid"], "graph { a -- b }");
+        assert_eq!(code, SUCCESS);
+        assert_eq!(
+            stdout,
+            "flowchart TD\n    n0[\"a\"]\n    n1[\"b\"]\n    n0 --- n1\n"
+        );
+
+        let (code, stdout, _) = run_with(&["stats"], "digraph { a -> b; a -> c; b -> c }");
+        assert_eq!(code, SUCCESS);
+        assert_eq!(
+            stdout,
+            "nodes: 3
+edges: 3
+directed: true
+density: 0.500
+degree: min 2, max 2, mean 2.00
+in-degree: min 0, max 2, mean 1.00
+out-degree: min 0, max 2, mean 1.00
+"
+        );
+    }
+
+    #[test]
+    fn rejects_bad_usage() {
+        assert_eq!(run_with(&[], "").0, USAGE_ERROR);
+        assert_eq!(run_with(&["frobnicate"], "").0, USAGE_ERROR);
+        assert_eq!(
+            run_with(&["convert", "--to", "svg"], "graph {}").0,
+            USAGE_ERROR
+        );
+        assert_eq!(run_with(&["check", "--verbose"], "").0, USAGE_ERROR);
+        assert_eq!(
+            run_with(&["check", "/nonexistent/graph.dot"], "").0,
+            USAGE_ERROR
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{Token, TokenKind, Trivia};
+
+mod view;
+
+pub(crate) use view::{statements, to_statement};
+pub use view::{
+    AssignmentView, AttrStatementView, AttributeView, EdgeOperandView, EdgeStatementView,
+    GraphView, NodeIdView, NodeStatementView, StatementView, SubgraphView,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SyntaxKind {
+    /// The whole file, including trivia around the graph and the `Eof` token.
+    Graph,
+    /// `{ ... }` with the statements and their `;` separators.
+    Block,
+    NodeStatement,
+    EdgeStatement,
+    AttrStatement,
+    Assignment,
+    Subgraph,
+    /// An id with its optional port.
+    NodeId,
+    /// `[ ... ]` with the attributes and their separators.
+    AttrList,
+    /// `key = value`.
+    Attribute,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Element<'a> {
+    Node(SyntaxNode<'a>),
+    /// A token, whose own `trivia` is always empty: trivia are separate elements.
+    Token(Token<'a>),
+    Trivia(Trivia<'a>),
+}
+
+/// A node of the lossless syntax tree. Trivia before a node's first token
+/// sits in its parent, so a statement node starts with its first token.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxNode<'a> {
+    pub kind: SyntaxKind,
+    pub children: Vec<Element<'a>>,
+}
+
+impl<'a> SyntaxNode<'a> {
+    pub fn child_nodes(&self) -> impl Iterator<Item = &SyntaxNode<'a>> {
+        self.children.iter().filter_map(|child| match child {
+            Element::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    /// The node's own tokens, not those of its descendants.
+    pub fn child_tokens(&self) -> impl Iterator<Item = &Token<'a>> {
+        self.children.iter().filter_map(|child| match child {
+            Element::Token(token) => Some(token),
+            _ => None,
+        })
+    }
+
+    /// The values of the node's own id tokens.
+    pub fn child_ids(&self) -> impl Iterator<Item = &str> {
+        self.child_tokens().filter_map(|token| match &token.kind {
+            TokenKind::Id(id) => Some(id.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Every token in the subtree, in source order.
+    pub fn tokens(&self) -> Vec<&Token<'a>> {
+        let mut tokens = Vec::new();
+        for child in &self.children {
+            match child {
+                Element::Node(node) => tokens.extend(node.tokens()),
+                Element::Token(token) => tokens.push(token),
+                Element::Trivia(_) => {}
+            }
+        }
+        tokens
+    }
+
+    /// Calls `edit` on every node of the subtree, parents first.
+    pub fn visit_mut(&mut self, edit: &mut impl FnMut(&mut SyntaxNode<'a>)) {
+        edit(self);
+        for child in &mut self.children {
+            if let Element::Node(node) = child {
+                node.visit_mut(edit);
+            }
+        }
+    }
+}
+
+/// Writes the source text back exactly as it was parsed, plus any edits.
+impl fmt::Display for SyntaxNode<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                Element::Node(node) => write!(f, "{node}")?,
+                Element::Token(token) => f.write_str(&token.text)?,
+                Element::Trivia(trivia) => f.write_str(trivia.text())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A parsed file that keeps every character, so `to_string` gives back the
+/// source. Edit the tokens and print it to change a file surgically.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SyntaxTree<'a> {
+    pub root: SyntaxNode<'a>,
+}
+
+/// Parses `source` into a tree whose tokens borrow from it.
+pub fn parse(source: &str) -> Result<SyntaxTree<'_>, Diagnostic> {
+    crate::parser::parse_tree(source)
+}
+
+impl<'a> SyntaxTree<'a> {
+    pub fn graph(&self) -> GraphView<'_, 'a> {
+        GraphView::new(&self.root)
+    }
+
+    /// Renames a node wherever its id appears in a statement or edge,
+    /// leaving everything else untouched. Returns how many ids changed.
+    pub fn rename_node(&mut self, from: &str, to: &str) -> usize {
+        let mut renamed = 0;
+        self.root.visit_mut(&mut |node| {
+            if node.kind != SyntaxKind::NodeId {
+                return;
+            }
+            let id = node.children.iter_mut().find_map(|child| match child {
+                Element::Token(token) if matches!(token.kind, TokenKind::Id(_)) => Some(token),
+                _ => None,
+            });
+            if let Some(token) = id.filter(|token| token.kind == TokenKind::Id(Cow::Borrowed(from)))
+            {
+                token.set_id(to);
+                renamed += 1;
+            }
+        });
+        renamed
+    }
+}
+
+impl fmt::Display for SyntaxTree<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse, StatementView};
+    use crate::ast::Statement;
+
+    const SOURCE: &str = "/* header */
+strict digraph \"deps\"{
+  // services
+  web ->api:in:n  [ label = \"calls\" ,weight=2 ];
+  api->  { db cache }
+
+
+  subgraph cluster_x { node[shape=box] x }  // after x
+}
+# trailing
+";
+
+    #[test]
+    fn round_trips_exactly() {
+        assert_eq!(parse(SOURCE).unwrap().to_string(), SOURCE);
+        assert_eq!(parse("graph{}").unwrap().to_string(), "graph{}");
+    }
+
+    #[test]
+    fn offers_typed_views() {
+        let tree = parse(SOURCE).unwrap();
+        let graph = tree.graph();
+
+        assert!(graph.strict() && graph.directed());
+        assert_eq!(graph.id(), Some("deps"));
+
+        let statements: Vec<StatementView> = graph.statements().collect();
+        assert_eq!(statements.len(), 3);
+        let StatementView::Edge(edge) = &statements[0] else {
+            panic!("expected an edge statement");
+        };
+        let attrs: Vec<(&str, &str)> = edge
+            .attrs()
+            .map(|attr| (attr.key(), attr.value()))
+            .collect();
+        assert_eq!(attrs, [("label", "calls"), ("weight", "2")]);
+        assert_eq!(
+            edge.syntax().to_string(),
+            "web ->api:in:n  [ label = \"calls\" ,weight=2 ]"
+        );
+
+        let StatementView::Subgraph(subgraph) = &statements[2] else {
+            panic!("expected a subgraph");
+        };
+        assert_eq!(subgraph.id(), Some("cluster_x"));
+        assert_eq!(subgraph.statements().count(), 2);
+    }
+
+    #[test]
+    fn tells_html_values_from_text() {
+        let ast = parse("graph { label=\"<b>\"; a [label=<<b>a</b>>] }")
+            .unwrap()
+            .to_ast();
+
+        let Statement::Assignment(label) = &ast.statements[0] else {
+            panic!("expected an assignment");
+        };
+        assert!(!label.html);
+        let Statement::Node(node) = &ast.statements[1] else {
+            panic!("expected a node statement");
+        };
+        assert!(node.attrs[0].html);
+    }
+
+    #[test]
+    fn renames_surgically() {
+        let mut tree = parse(SOURCE).unwrap();
+
+        assert_eq!(tree.rename_node("api", "backend api"), 2);
+        assert_eq!(
+            tree.to_string(),
+            SOURCE
+                .replace("->api:in", "->\"backend api\":in")
+                .replace("api->", "\"backend api\"->")
+        );
+    }
+}
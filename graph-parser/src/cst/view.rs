@@ -0,0 +1,432 @@
+use std::borrow::Cow;
+
+use crate::ast::{
+    AttrStatement, AttrTarget, Attribute, Comment, EdgeOp, EdgeOperand, EdgeStatement, GraphAST,
+    NodeId, NodeStatement, Statement, Subgraph,
+};
+use crate::lexer::{Token, TokenKind, Trivia};
+
+use super::{Element, SyntaxKind, SyntaxNode, SyntaxTree};
+
+/// The root of the tree, seen as a graph.
+#[derive(Clone, Copy, Debug)]
+pub struct GraphView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> GraphView<'t, 'a> {
+    pub(super) fn new(node: &'t SyntaxNode<'a>) -> Self {
+        Self { node }
+    }
+
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn strict(&self) -> bool {
+        self.node
+            .child_tokens()
+            .any(|token| token.kind == TokenKind::Strict)
+    }
+
+    pub fn directed(&self) -> bool {
+        self.node
+            .child_tokens()
+            .any(|token| token.kind == TokenKind::Digraph)
+    }
+
+    pub fn id(&self) -> Option<&'t str> {
+        self.node.child_ids().next()
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = StatementView<'t, 'a>> {
+        block_statements(self.node)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum StatementView<'t, 'a> {
+    Node(NodeStatementView<'t, 'a>),
+    Edge(EdgeStatementView<'t, 'a>),
+    Attr(AttrStatementView<'t, 'a>),
+    Assignment(AssignmentView<'t, 'a>),
+    Subgraph(SubgraphView<'t, 'a>),
+}
+
+impl<'t, 'a> StatementView<'t, 'a> {
+    fn cast(node: &'t SyntaxNode<'a>) -> Option<Self> {
+        Some(match node.kind {
+            SyntaxKind::NodeStatement => StatementView::Node(NodeStatementView { node }),
+            SyntaxKind::EdgeStatement => StatementView::Edge(EdgeStatementView { node }),
+            SyntaxKind::AttrStatement => StatementView::Attr(AttrStatementView { node }),
+            SyntaxKind::Assignment => StatementView::Assignment(AssignmentView { node }),
+            SyntaxKind::Subgraph => StatementView::Subgraph(SubgraphView { node }),
+            _ => return None,
+        })
+    }
+
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        match self {
+            StatementView::Node(view) => view.node,
+            StatementView::Edge(view) => view.node,
+            StatementView::Attr(view) => view.node,
+            StatementView::Assignment(view) => view.node,
+            StatementView::Subgraph(view) => view.node,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NodeStatementView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> NodeStatementView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn node_id(&self) -> NodeIdView<'t, 'a> {
+        let node = self
+            .node
+            .child_nodes()
+            .next()
+            .expect("node statement without id");
+        NodeIdView { node }
+    }
+
+    pub fn attrs(&self) -> impl Iterator<Item = AttributeView<'t, 'a>> {
+        attrs(self.node)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct EdgeStatementView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> EdgeStatementView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn operands(&self) -> impl Iterator<Item = EdgeOperandView<'t, 'a>> {
+        self.node.child_nodes().filter_map(|node| match node.kind {
+            SyntaxKind::NodeId => Some(EdgeOperandView::Node(NodeIdView { node })),
+            SyntaxKind::Subgraph => Some(EdgeOperandView::Subgraph(SubgraphView { node })),
+            _ => None,
+        })
+    }
+
+    pub fn ops(&self) -> impl Iterator<Item = EdgeOp> + 't {
+        self.node
+            .child_tokens()
+            .filter_map(|token| match token.kind {
+                TokenKind::Arrow => Some(EdgeOp::Directed),
+                TokenKind::Line => Some(EdgeOp::Undirected),
+                _ => None,
+            })
+    }
+
+    pub fn attrs(&self) -> impl Iterator<Item = AttributeView<'t, 'a>> {
+        attrs(self.node)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum EdgeOperandView<'t, 'a> {
+    Node(NodeIdView<'t, 'a>),
+    Subgraph(SubgraphView<'t, 'a>),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AttrStatementView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> AttrStatementView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn target(&self) -> AttrTarget {
+        match self.node.child_tokens().next().map(|token| &token.kind) {
+            Some(TokenKind::Graph) => AttrTarget::Graph,
+            Some(TokenKind::Node) => AttrTarget::Node,
+            _ => AttrTarget::Edge,
+        }
+    }
+
+    pub fn attrs(&self) -> impl Iterator<Item = AttributeView<'t, 'a>> {
+        attrs(self.node)
+    }
+}
+
+/// A bare `key = value` statement.
+#[derive(Clone, Copy, Debug)]
+pub struct AssignmentView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> AssignmentView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn key(&self) -> &'t str {
+        self.node.child_ids().next().unwrap_or_default()
+    }
+
+    pub fn value(&self) -> &'t str {
+        self.node.child_ids().nth(1).unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SubgraphView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> SubgraphView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn id(&self) -> Option<&'t str> {
+        self.node.child_ids().next()
+    }
+
+    pub fn statements(&self) -> impl Iterator<Item = StatementView<'t, 'a>> {
+        block_statements(self.node)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct NodeIdView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> NodeIdView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn id_token(&self) -> &'t Token<'a> {
+        self.node
+            .child_tokens()
+            .next()
+            .expect("node id without token")
+    }
+
+    pub fn id(&self) -> &'t str {
+        self.node.child_ids().next().unwrap_or_default()
+    }
+
+    /// Everything after the first `:`, e.g. `p1:n`.
+    pub fn port(&self) -> Option<String> {
+        let parts: Vec<&str> = self.node.child_ids().skip(1).collect();
+        (!parts.is_empty()).then(|| parts.join(":"))
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AttributeView<'t, 'a> {
+    node: &'t SyntaxNode<'a>,
+}
+
+impl<'t, 'a> AttributeView<'t, 'a> {
+    pub fn syntax(&self) -> &'t SyntaxNode<'a> {
+        self.node
+    }
+
+    pub fn key(&self) -> &'t str {
+        self.node.child_ids().next().unwrap_or_default()
+    }
+
+    pub fn value(&self) -> &'t str {
+        self.node.child_ids().nth(1).unwrap_or_default()
+    }
+}
+
+fn block<'t, 'a>(node: &'t SyntaxNode<'a>) -> Option<&'t SyntaxNode<'a>> {
+    node.child_nodes()
+        .find(|node| node.kind == SyntaxKind::Block)
+}
+
+fn block_statements<'t, 'a>(
+    node: &'t SyntaxNode<'a>,
+) -> impl Iterator<Item = StatementView<'t, 'a>> {
+    block(node)
+        .into_iter()
+        .flat_map(SyntaxNode::child_nodes)
+        .filter_map(StatementView::cast)
+}
+
+fn attrs<'t, 'a>(node: &'t SyntaxNode<'a>) -> impl Iterator<Item = AttributeView<'t, 'a>> {
+    node.child_nodes()
+        .filter(|node| node.kind == SyntaxKind::AttrList)
+        .flat_map(SyntaxNode::child_nodes)
+        .map(|node| AttributeView { node })
+}
+
+/// The values of the node's own id tokens, which the AST shares rather
+/// than copies.
+fn values<'t, 'a>(node: &'t SyntaxNode<'a>) -> impl Iterator<Item = &'t Cow<'a, str>> {
+    node.child_tokens().filter_map(|token| match &token.kind {
+        TokenKind::Id(id) => Some(id),
+        _ => None,
+    })
+}
+
+fn value<'a>(node: &SyntaxNode<'a>, index: usize) -> Cow<'a, str> {
+    values(node).nth(index).cloned().unwrap_or_default()
+}
+
+impl<'a> SyntaxTree<'a> {
+    /// The abstract syntax tree. Comments inside a statement are moved after
+    /// it, and those between the header and `{` to the start of the body.
+    pub fn to_ast(&self) -> GraphAST<'a> {
+        let graph = self.graph();
+        let mut leading = Vec::new();
+        let mut body = Vec::new();
+        let mut trailing = Vec::new();
+        let mut seen_token = false;
+        let mut seen_block = false;
+        for child in &self.root.children {
+            match child {
+                Element::Trivia(trivia) if seen_block => push_trivia(&mut trailing, trivia),
+                Element::Trivia(trivia) if seen_token => push_trivia(&mut body, trivia),
+                Element::Trivia(trivia) => push_trivia(&mut leading, trivia),
+                Element::Token(_) => seen_token = true,
+                Element::Node(node) => {
+                    body.extend(statements(node));
+                    seen_block = true;
+                }
+            }
+        }
+
+        GraphAST {
+            leading,
+            strict: graph.strict(),
+            directed: graph.directed(),
+            id: values(&self.root).next().cloned(),
+            statements: body,
+            trailing,
+        }
+    }
+}
+
+/// The statements of a block node, trivia included.
+pub(crate) fn statements<'a>(block: &SyntaxNode<'a>) -> Vec<Statement<'a>> {
+    let mut statements = Vec::new();
+    for child in &block.children {
+        match child {
+            Element::Trivia(trivia) => push_trivia(&mut statements, trivia),
+            Element::Token(_) => {}
+            Element::Node(node) => {
+                if let Some(view) = StatementView::cast(node) {
+                    statements.push(statement(view));
+                    inner_trivia(node, &mut statements);
+                }
+            }
+        }
+    }
+    statements
+}
+
+/// Trivia inside a statement, but not inside its subgraph bodies.
+fn inner_trivia<'a>(node: &SyntaxNode<'a>, statements: &mut Vec<Statement<'a>>) {
+    for child in &node.children {
+        match child {
+            Element::Trivia(trivia) => push_trivia(statements, trivia),
+            Element::Node(node) if node.kind != SyntaxKind::Block => {
+                inner_trivia(node, statements);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn push_trivia<'a>(statements: &mut Vec<Statement<'a>>, trivia: &Trivia<'a>) {
+    match trivia {
+        Trivia::Comment { text, own_line } => statements.push(Statement::Comment(Comment {
+            text: text.clone(),
+            trailing: !own_line,
+        })),
+        trivia if trivia.is_blank_line() => statements.push(Statement::BlankLine),
+        Trivia::Whitespace(_) => {}
+    }
+}
+
+/// The statement a node stands for, if it is one.
+pub(crate) fn to_statement<'a>(node: &SyntaxNode<'a>) -> Option<Statement<'a>> {
+    StatementView::cast(node).map(statement)
+}
+
+fn statement<'a>(view: StatementView<'_, 'a>) -> Statement<'a> {
+    match view {
+        StatementView::Node(view) => Statement::Node(NodeStatement {
+            node: node_id(view.node_id()),
+            attrs: attributes(view.attrs()),
+        }),
+        StatementView::Edge(view) => Statement::Edge(EdgeStatement {
+            operands: view
+                .operands()
+                .map(|operand| match operand {
+                    EdgeOperandView::Node(view) => EdgeOperand::Node(node_id(view)),
+                    EdgeOperandView::Subgraph(view) => EdgeOperand::Subgraph(subgraph(view)),
+                })
+                .collect(),
+            ops: view.ops().collect(),
+            attrs: attributes(view.attrs()),
+        }),
+        StatementView::Attr(view) => Statement::Attr(AttrStatement {
+            target: view.target(),
+            attrs: attributes(view.attrs()),
+        }),
+        StatementView::Assignment(view) => Statement::Assignment(attribute(view.node)),
+        StatementView::Subgraph(view) => Statement::Subgraph(subgraph(view)),
+    }
+}
+
+/// Borrows a port like `p1` from the source; `p1:n` spans two tokens and
+/// has to be put together.
+fn node_id<'a>(view: NodeIdView<'_, 'a>) -> NodeId<'a> {
+    let mut parts = values(view.node).skip(1);
+    let port = parts.next().map(|port| match parts.next() {
+        Some(compass) => Cow::Owned(format!("{port}:{compass}")),
+        None => port.clone(),
+    });
+    NodeId {
+        id: value(view.node, 0),
+        port,
+    }
+}
+
+fn subgraph<'a>(view: SubgraphView<'_, 'a>) -> Subgraph<'a> {
+    Subgraph {
+        id: values(view.node).next().cloned(),
+        statements: block(view.node).map(statements).unwrap_or_default(),
+    }
+}
+
+fn attributes<'t, 'a: 't>(
+    attrs: impl Iterator<Item = AttributeView<'t, 'a>>,
+) -> Vec<Attribute<'a>> {
+    attrs.map(|attr| attribute(attr.node)).collect()
+}
+
+/// The `key=value` pair in an attribute or assignment node.
+fn attribute<'a>(node: &SyntaxNode<'a>) -> Attribute<'a> {
+    let (key, value) = (value(node, 0), value(node, 1));
+    let html = node
+        .child_tokens()
+        .filter(|token| matches!(token.kind, TokenKind::Id(_)))
+        .nth(1)
+        .is_some_and(|token| token.text.starts_with('<'));
+    if html {
+        Attribute::html(key, value)
+    } else {
+        Attribute::new(key, value)
+    }
+}
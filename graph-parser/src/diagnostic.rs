@@ -0,0 +1,57 @@
+use std::error::Error;
+use std::fmt;
+
+/// A place in the source. Lines and columns start at 1; columns count characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub offset: usize,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl Position {
+    pub fn start() -> Self {
+        Self {
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub line: u32,
+    pub column: u32,
+    /// Where the problem ends, exclusive. One character after the start
+    /// unless set with `with_end`.
+    pub end_line: u32,
+    pub end_column: u32,
+}
+
+impl Diagnostic {
+    pub fn new(message: &str, position: Position) -> Self {
+        Self {
+            message: message.to_string(),
+            line: position.line,
+            column: position.column,
+            end_line: position.line,
+            end_column: position.column + 1,
+        }
+    }
+
+    pub fn with_end(mut self, position: Position) -> Self {
+        self.end_line = position.line;
+        self.end_column = position.column;
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl Error for Diagnostic {}
@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use graph_dsl::graph::Graph;
+
+/// `{"directed", "strict", "attrs", "nodes": [{"id", "attrs"}], "edges":
+/// [{"from", "to", "attrs"}]}`, with ports as `tailport`/`headport` on the
+/// edges that have them. Attribute objects keep their DOT order.
+pub fn to_json(graph: &Graph) -> String {
+    let mut out = String::new();
+    writeln!(out, "{{").unwrap();
+    writeln!(out, "  \"directed\": {},", graph.directed).unwrap();
+    writeln!(out, "  \"strict\": {},", graph.strict).unwrap();
+    writeln!(out, "  \"attrs\": {},", json_object(graph.attrs())).unwrap();
+
+    let nodes: Vec<String> = graph
+        .node_ids()
+        .into_iter()
+        .map(|id| {
+            let attrs = graph.node(id).map(|node| json_object(node.attrs()));
+            format!(
+                "{{\"id\": {}, \"attrs\": {}}}",
+                json_string(id),
+                attrs.unwrap_or_else(|| "{}".to_string())
+            )
+        })
+        .collect();
+    writeln!(out, "  \"nodes\": {},", json_array(&nodes)).unwrap();
+
+    let edges: Vec<String> = graph
+        .edges
+        .iter()
+        .map(|edge| {
+            let mut fields = vec![
+                format!("\"from\": {}", json_string(&edge.from)),
+                format!("\"to\": {}", json_string(&edge.to)),
+            ];
+            if let Some(port) = &edge.tail_port {
+                fields.push(format!("\"tailport\": {}", json_string(&port.to_string())));
+            }
+            if let Some(port) = &edge.head_port {
+                fields.push(format!("\"headport\": {}", json_string(&port.to_string())));
+            }
+            fields.push(format!("\"attrs\": {}", json_object(edge.attrs())));
+            format!("{{{}}}", fields.join(", "))
+        })
+        .collect();
+    writeln!(out, "  \"edges\": {}", json_array(&edges)).unwrap();
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+fn json_array(items: &[String]) -> String {
+    if items.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n    {}\n  ]", items.join(",\n    "))
+}
+
+fn json_object<'a>(attrs: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let pairs: Vec<String> = attrs
+        .map(|(key, value)| format!("{}: {}", json_string(key), json_string(value)))
+        .collect();
+    format!("{{{}}}", pairs.join(", "))
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// GraphML with one string-typed `<key>` per attribute name and domain.
+pub fn to_graphml(graph: &Graph) -> String {
+    let mut keys = Vec::new();
+    declare_keys(&mut keys, "graph", graph.attrs());
+    for node in &graph.nodes {
+        declare_keys(&mut keys, "node", node.attrs());
+    }
+    for edge in &graph.edges {
+        declare_keys(&mut keys, "edge", edge.attrs());
+    }
+    let key_ids: HashMap<(&str, &str), String> = keys
+        .iter()
+        .enumerate()
+        .map(|(index, &key)| (key, format!("d{index}")))
+        .collect();
+
+    let mut out = String::new();
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>").unwrap();
+    writeln!(
+        out,
+        "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">"
+    )
+    .unwrap();
+    for key in &keys {
+        writeln!(
+            out,
+            "  <key id=\"{}\" for=\"{}\" attr.name=\"{}\" attr.type=\"string\"/>",
+            key_ids[key],
+            key.0,
+            xml_escape(key.1)
+        )
+        .unwrap();
+    }
+
+    let edge_default = if graph.directed {
+        "directed"
+    } else {
+        "undirected"
+    };
+    writeln!(out, "  <graph id=\"G\" edgedefault=\"{edge_default}\">").unwrap();
+    write_data(&mut out, &key_ids, "graph", graph.attrs(), "    ");
+
+    for id in graph.node_ids() {
+        match graph.node(id).filter(|node| !node.attrs.is_empty()) {
+            Some(node) => {
+                writeln!(out, "    <node id=\"{}\">", xml_escape(id)).unwrap();
+                write_data(&mut out, &key_ids, "node", node.attrs(), "      ");
+                writeln!(out, "    </node>").unwrap();
+            }
+            None => writeln!(out, "    <node id=\"{}\"/>", xml_escape(id)).unwrap(),
+        }
+    }
+
+    for (index, edge) in graph.edges.iter().enumerate() {
+        let open = format!(
+            "    <edge id=\"e{index}\" source=\"{}\" target=\"{}\"",
+            xml_escape(&edge.from),
+            xml_escape(&edge.to)
+        );
+        if edge.attrs.is_empty() {
+            writeln!(out, "{open}/>").unwrap();
+        } else {
+            writeln!(out, "{open}>").unwrap();
+            write_data(&mut out, &key_ids, "edge", edge.attrs(), "      ");
+            writeln!(out, "    </edge>").unwrap();
+        }
+    }
+
+    writeln!(out, "  </graph>").unwrap();
+    writeln!(out, "</graphml>").unwrap();
+    out
+}
+
+fn declare_keys<'a>(
+    keys: &mut Vec<(&'static str, &'a str)>,
+    domain: &'static str,
+    attrs: impl Iterator<Item = (&'a str, &'a str)>,
+) {
+    for (name, _) in attrs {
+        if !keys.contains(&(domain, name)) {
+            keys.push((domain, name));
+        }
+    }
+}
+
+fn write_data<'a>(
+    out: &mut String,
+    key_ids: &HashMap<(&str, &str), String>,
+    domain: &str,
+    attrs: impl Iterator<Item = (&'a str, &'a str)>,
+    indent: &str,
+) {
+    for (name, value) in attrs {
+        writeln!(
+            out,
+            "{indent}<data key=\"{}\">{}</data>",
+            key_ids[&(domain, name)],
+            xml_escape(value)
+        )
+        .unwrap();
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A Mermaid flowchart. Nodes get generated ids and show their `label`, or
+/// their DOT id without one; edge labels go on the arrow. The direction
+/// follows `rankdir`.
+pub fn to_mermaid(graph: &Graph) -> String {
+    let direction = match graph.attr("rankdir") {
+        Some("LR") => "LR",
+        Some("RL") => "RL",
+        Some("BT") => "BT",
+        _ => "TD",
+    };
+    let mut out = format!("flowchart {direction}\n");
+
+    let ids = graph.node_ids();
+    let short: HashMap<&str, String> = ids
+        .iter()
+        .enumerate()
+        .map(|(index, &id)| (id, format!("n{index}")))
+        .collect();
+    for &id in &ids {
+        let label = graph
+            .node(id)
+            .and_then(|node| node.attr("label"))
+            .unwrap_or(id);
+        writeln!(out, "    {}[\"{}\"]", short[id], mermaid_escape(label)).unwrap();
+    }
+
+    let arrow = if graph.directed { "-->" } else { "---" };
+    for edge in &graph.edges {
+        let label = match edge.attr("label") {
+            Some(label) => format!("|\"{}\"|", mermaid_escape(label)),
+            None => String::new(),
+        };
+        writeln!(
+            out,
+            "    {} {arrow}{label} {}",
+            short[edge.from.as_str()],
+            short[edge.to.as_str()]
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_graphml, to_json, to_mermaid};
+    use crate::parser::parse;
+
+    const SOURCE: &str = "digraph { rankdir=LR; a [label=\"A \\\"1\\\"\"]; a -> b [label=go] }";
+
+    fn graph() -> graph_dsl::graph::Graph {
+        parse(SOURCE).unwrap().to_graph()
+    }
+
+    #[test]
+    fn exports_json() {
+        assert_eq!(
+            to_json(&graph()),
+            r#"{
+  "directed": true,
+  "strict": false,
+  "attrs": {"rankdir": "LR"},
+  "nodes": [
+    {"id": "a", "attrs": {"label": "A \"1\""}},
+    {"id": "b", "attrs": {}}
+  ],
+  "edges": [
+    {"from": "a", "to": "b", "attrs": {"label": "go"}}
+  ]
+}
+"#
+        );
+    }
+
+    #[test]
+    fn exports_graphml() {
+        let graphml = to_graphml(&graph());
+
+        assert!(graphml
+            .contains("<key id=\"d1\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>"));
+        assert!(graphml.contains("<graph id=\"G\" edgedefault=\"directed\">"));
+        assert!(graphml.contains("<data key=\"d1\">A &quot;1&quot;</data>"));
+        assert!(graphml.contains("<node id=\"b\"/>"));
+        assert!(graphml.contains("<edge id=\"e0\" source=\"a\" target=\"b\">"));
+        assert!(graphml.contains("<data key=\"d2\">go</data>"));
+    }
+
+    #[test]
+    fn exports_mermaid() {
+        assert_eq!(
+            to_mermaid(&graph()),
+            "flowchart LR
+    n0[\"A #quot;1#quot;\"]
+    n1[\"b\"]
+    n0 -->|\"go\"| n1
+"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use graph_dsl::graph::dot::{quote_id, quote_text};
+
+use crate::ast::{
+    AttrTarget, Attribute, EdgeOp, EdgeOperand, GraphAST, NodeId, Statement, Subgraph,
+};
+
+const INDENT: &str = "    ";
+
+/// Renders the file in canonical style: one statement per line, four-space
+/// indentation, `;` after every statement but subgraph blocks, and a single
+/// ` [a=b, c=d]` list per statement. Ids are quoted only where needed.
+/// Comments stay where they were, and runs of blank lines become one.
+pub fn format(ast: &GraphAST) -> String {
+    let mut out = String::new();
+    lines(&mut out, trim_blank_lines(&ast.leading, true, false), 0);
+    if ast.strict {
+        out.push_str("strict ");
+    }
+    out.push_str(if ast.directed { "digraph" } else { "graph" });
+    if let Some(id) = &ast.id {
+        out.push(' ');
+        out.push_str(&quote_id(id));
+    }
+    out.push_str(" {\n");
+    lines(&mut out, trim_blank_lines(&ast.statements, true, true), 1);
+    out.push_str("}\n");
+    lines(&mut out, trim_blank_lines(&ast.trailing, false, true), 0);
+    out
+}
+
+fn trim_blank_lines<'s, 'a>(
+    statements: &'s [Statement<'a>],
+    start: bool,
+    end: bool,
+) -> &'s [Statement<'a>] {
+    let blank = |statement: &Statement| matches!(statement, Statement::BlankLine);
+    let mut statements = statements;
+    while start && statements.first().is_some_and(blank) {
+        statements = &statements[1..];
+    }
+    while end && statements.last().is_some_and(blank) {
+        statements = &statements[..statements.len() - 1];
+    }
+    statements
+}
+
+fn lines(out: &mut String, statements: &[Statement], depth: usize) {
+    let mut previous_blank = false;
+    for statement in statements {
+        match statement {
+            Statement::BlankLine if previous_blank => continue,
+            Statement::BlankLine => out.push('\n'),
+            // Stays on the line it was written on, which `out` ends with.
+            Statement::Comment(comment) if comment.trailing && !out.is_empty() => {
+                out.pop();
+                out.push(' ');
+                out.push_str(comment.text.trim_end());
+                out.push('\n');
+            }
+            // Graphviz only skips `#` lines that start in the first column.
+            Statement::Comment(comment) if comment.text.starts_with('#') => {
+                out.push_str(comment.text.trim_end());
+                out.push('\n');
+            }
+            Statement::Comment(comment) => {
+                out.push_str(&INDENT.repeat(depth));
+                out.push_str(comment.text.trim_end());
+                out.push('\n');
+            }
+            Statement::Subgraph(subgraph) => {
+                out.push_str(&INDENT.repeat(depth));
+                out.push_str(&block_subgraph(subgraph, depth));
+                out.push('\n');
+            }
+            statement => {
+                out.push_str(&INDENT.repeat(depth));
+                out.push_str(&inline(statement, depth));
+                out.push_str(";\n");
+            }
+        }
+        previous_blank = matches!(statement, Statement::BlankLine);
+    }
+}
+
+/// A statement without its terminator, on a single line unless it contains
+/// a subgraph with comments.
+fn inline(statement: &Statement, depth: usize) -> String {
+    match statement {
+        Statement::Node(statement) => {
+            format!(
+                "{}{}",
+                node_id(&statement.node),
+                attr_list(&statement.attrs)
+            )
+        }
+        Statement::Edge(statement) => {
+            let mut line = operand(&statement.operands[0], depth);
+            for (op, next) in statement.ops.iter().zip(&statement.operands[1..]) {
+                line.push_str(match op {
+                    EdgeOp::Directed => " -> ",
+                    EdgeOp::Undirected => " -- ",
+                });
+                line.push_str(&operand(next, depth));
+            }
+            line + &attr_list(&statement.attrs)
+        }
+        Statement::Attr(statement) => {
+            let target = match statement.target {
+                AttrTarget::Graph => "graph",
+                AttrTarget::Node => "node",
+                AttrTarget::Edge => "edge",
+            };
+            format!("{target}{}", attr_list(&statement.attrs))
+        }
+        Statement::Assignment(attr) => {
+            format!("{}={}", quote_id(&attr.key), quote_attr_value(attr))
+        }
+        Statement::Subgraph(subgraph) => inline_subgraph(subgraph, depth),
+        Statement::Comment(comment) => comment.text.to_string(),
+        Statement::BlankLine => String::new(),
+    }
+}
+
+fn operand(operand: &EdgeOperand, depth: usize) -> String {
+    match operand {
+        EdgeOperand::Node(node) => node_id(node),
+        EdgeOperand::Subgraph(subgraph) => inline_subgraph(subgraph, depth),
+    }
+}
+
+/// `{b; c}`, as subgraphs are usually written inside edge statements.
+/// Comments would swallow the rest of the line, so subgraphs with comments
+/// are written as blocks.
+fn inline_subgraph(subgraph: &Subgraph, depth: usize) -> String {
+    let has_comments = subgraph
+        .statements
+        .iter()
+        .any(|statement| matches!(statement, Statement::Comment(_)));
+    if has_comments {
+        return block_subgraph(subgraph, depth);
+    }
+
+    let statements: Vec<String> = subgraph
+        .statements
+        .iter()
+        .filter(|statement| !matches!(statement, Statement::BlankLine))
+        .map(|statement| inline(statement, depth))
+        .collect();
+    format!("{}{{{}}}", subgraph_header(subgraph), statements.join("; "))
+}
+
+fn block_subgraph(subgraph: &Subgraph, depth: usize) -> String {
+    let mut out = format!("{}{{\n", subgraph_header(subgraph));
+    lines(
+        &mut out,
+        trim_blank_lines(&subgraph.statements, true, true),
+        depth + 1,
+    );
+    out.push_str(&INDENT.repeat(depth));
+    out.push('}');
+    out
+}
+
+fn subgraph_header(subgraph: &Subgraph) -> String {
+    match &subgraph.id {
+        Some(id) => format!("subgraph {} ", quote_id(id)),
+        None => String::new(),
+    }
+}
+
+fn node_id(node: &NodeId) -> String {
+    let mut result = quote_id(&node.id);
+    if let Some(port) = &node.port {
+        for part in port.split(':') {
+            result.push(':');
+            result.push_str(&quote_id(part));
+        }
+    }
+    result
+}
+
+fn attr_list(attrs: &[Attribute]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = attrs
+        .iter()
+        .map(|attr| format!("{}={}", quote_id(&attr.key), quote_attr_value(attr)))
+        .collect();
+    format!(" [{}]", pairs.join(", "))
+}
+
+/// HTML values stay bare; text that only looks like HTML gets quoted.
+fn quote_attr_value(attr: &Attribute) -> String {
+    if attr.html {
+        attr.value.to_string()
+    } else {
+        quote_text(&attr.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::format;
+    use crate::parser::parse;
+
+    fn reformat(source: &str) -> String {
+        format(&parse(source).unwrap())
+    }
+
+    #[test]
+    fn formats_canonically() {
+        let source = "strict  digraph \"my graph\"{rankdir=LR node[shape = box ;color=\"red\"]
+a:p1:n->{b c}->d[label=\"say \\\"hi\\\"\"] [weight=2]
+subgraph cluster_0{label=Inner;e} graph [\"font size\"=12]}";
+
+        assert_eq!(
+            reformat(source),
+            "strict digraph \"my graph\" {
+    rankdir=LR;
+    node [shape=box, color=red];
+    a:p1:n -> {b; c} -> d [label=\"say \\\"hi\\\"\", weight=2];
+    subgraph cluster_0 {
+        label=Inner;
+        e;
+    }
+    graph [\"font size\"=12];
+}
+"
+        );
+    }
+
+    #[test]
+    fn preserves_comments() {
+        let source = "// Services
+#line 1
+
+digraph  { // the main graph
+  node[shape=box]
+
+
+  /* frontends */
+  web->api // calls
+  api->{ // fan out
+     db; cache } ;
+  # preprocessor
+  subgraph s { a } /* after s */
+}  // end
+";
+
+        assert_eq!(
+            reformat(source),
+            "// Services
+#line 1
+
+digraph { // the main graph
+    node [shape=box];
+
+    /* frontends */
+    web -> api; // calls
+    api -> { // fan out
+        db;
+        cache;
+    };
+# preprocessor
+    subgraph s {
+        a;
+    } /* after s */
+} // end
+"
+        );
+        assert_eq!(reformat(&reformat(source)), reformat(source));
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let once = reformat("graph { a -- b -- subgraph s { c } ; {} ; x [label=<<b>x</b>>] }");
+
+        assert_eq!(reformat(&once), once);
+        assert!(once.contains("a -- b -- subgraph s {c};"));
+        assert!(once.contains("x [label=<<b>x</b>>];"));
+    }
+
+    #[test]
+    fn formatting_twice_changes_nothing() {
+        let sources = [
+            r#"graph { a [label="\\", tooltip="C:\\dir\\", xlabel="say \"hi\\\"\l"] }"#,
+            "digraph { a [label=<<b>bold</b> &amp; <i>x</i>>]; b [label=\"<b>\"] }",
+            "digraph { a:out:ne -> b:in; c:s -> { d e:w } }",
+            "/* header */\ngraph { // on the graph\n    # 1 \"x.gv\"\n    a // after a\n\n    /* before b */\n    b\n}\n// trailing\n",
+            "graph { label=\"line one\\\nline two\"; \"a b\" -- \"1x\" }",
+        ];
+        for source in sources {
+            let once = reformat(source);
+            assert_eq!(reformat(&once), once, "{source}");
+            assert_eq!(
+                parse(&once).unwrap().to_graph(),
+                parse(source).unwrap().to_graph()
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_text_that_looks_like_html_quoted() {
+        assert_eq!(
+            reformat("graph { label=\"<b>\"; a [label=<<b>a</b>>] }"),
+            "graph {\n    label=\"<b>\";\n    a [label=<<b>a</b>>];\n}\n"
+        );
+    }
+}
@@ -0,0 +1,810 @@
+use std::borrow::Cow;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use graph_dsl::graph::dot::quote_id;
+
+use crate::diagnostic::{Diagnostic, Position};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TokenKind<'a> {
+    Strict,
+    Graph,
+    Digraph,
+    Subgraph,
+    Node,
+    Edge,
+    /// An identifier, numeral, quoted string (without its quotes, and joined
+    /// with any strings `+` appends to it) or HTML string (with its outer
+    /// angle brackets).
+    Id(Cow<'a, str>),
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    Semicolon,
+    Comma,
+    Equals,
+    Colon,
+    /// `->`
+    Arrow,
+    /// `--`
+    Line,
+    Eof,
+}
+
+impl TokenKind<'_> {
+    pub fn describe(&self) -> String {
+        match self {
+            TokenKind::Strict => "'strict'".to_string(),
+            TokenKind::Graph => "'graph'".to_string(),
+            TokenKind::Digraph => "'digraph'".to_string(),
+            TokenKind::Subgraph => "'subgraph'".to_string(),
+            TokenKind::Node => "'node'".to_string(),
+            TokenKind::Edge => "'edge'".to_string(),
+            TokenKind::Id(id) => format!("{id:?}"),
+            TokenKind::LBrace => "'{'".to_string(),
+            TokenKind::RBrace => "'}'".to_string(),
+            TokenKind::LBracket => "'['".to_string(),
+            TokenKind::RBracket => "']'".to_string(),
+            TokenKind::Semicolon => "';'".to_string(),
+            TokenKind::Comma => "','".to_string(),
+            TokenKind::Equals => "'='".to_string(),
+            TokenKind::Colon => "':'".to_string(),
+            TokenKind::Arrow => "'->'".to_string(),
+            TokenKind::Line => "'--'".to_string(),
+            TokenKind::Eof => "end of input".to_string(),
+        }
+    }
+}
+
+/// What the lexer skips between tokens, kept so the source can be
+/// reproduced exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Trivia<'a> {
+    Whitespace(Cow<'a, str>),
+    /// A `//`, `/* */` or `#` comment, including its delimiters. `own_line`
+    /// is false when code precedes it on the same line.
+    Comment {
+        text: Cow<'a, str>,
+        own_line: bool,
+    },
+}
+
+impl Trivia<'_> {
+    pub fn text(&self) -> &str {
+        match self {
+            Trivia::Whitespace(text) | Trivia::Comment { text, .. } => text,
+        }
+    }
+
+    /// Whitespace spanning at least one empty line.
+    pub fn is_blank_line(&self) -> bool {
+        matches!(self, Trivia::Whitespace(text) if text.matches('\n').count() >= 2)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    pub start: Position,
+    /// The token as written, e.g. with quotes and escapes. Empty for `Eof`.
+    pub text: Cow<'a, str>,
+    /// Whitespace and comments between the previous token and this one.
+    pub trivia: LeadingTrivia<'a>,
+}
+
+/// The trivia before a token, kept as the one piece of text it was read
+/// as, so lexing does not allocate for it. `iter` splits it up.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LeadingTrivia<'a> {
+    pub text: Cow<'a, str>,
+    /// Whether only whitespace precedes `text` on its line.
+    pub own_line: bool,
+}
+
+impl<'a> LeadingTrivia<'a> {
+    pub fn iter(&self) -> TriviaIter<'a> {
+        self.clone().into_iter()
+    }
+}
+
+impl<'a> IntoIterator for LeadingTrivia<'a> {
+    type Item = Trivia<'a>;
+    type IntoIter = TriviaIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        TriviaIter {
+            text: self.text,
+            offset: 0,
+            own_line: self.own_line,
+        }
+    }
+}
+
+/// The whitespace runs and comments of a `LeadingTrivia`, borrowed from the
+/// source when its text is.
+pub struct TriviaIter<'a> {
+    text: Cow<'a, str>,
+    offset: usize,
+    own_line: bool,
+}
+
+impl<'a> TriviaIter<'a> {
+    /// Moves past the next `len` bytes and returns them. Owned text that
+    /// is a single piece is handed over rather than copied.
+    fn advance(&mut self, len: usize) -> Cow<'a, str> {
+        let range = self.offset..self.offset + len;
+        self.offset += len;
+        match &mut self.text {
+            Cow::Borrowed(text) => Cow::Borrowed(&text[range]),
+            Cow::Owned(text) if range.start == 0 && range.end == text.len() => {
+                self.offset = 0;
+                Cow::Owned(std::mem::take(text))
+            }
+            Cow::Owned(text) => Cow::Owned(text[range].to_string()),
+        }
+    }
+}
+
+impl<'a> Iterator for TriviaIter<'a> {
+    type Item = Trivia<'a>;
+
+    /// Relies on the lexer having checked the text: anything that is not
+    /// whitespace starts a comment.
+    fn next(&mut self) -> Option<Trivia<'a>> {
+        let rest = &self.text[self.offset..];
+        if rest.starts_with(char::is_whitespace) {
+            let len = rest
+                .find(|c: char| !c.is_whitespace())
+                .unwrap_or(rest.len());
+            let text = self.advance(len);
+            self.own_line |= text.contains('\n');
+            return Some(Trivia::Whitespace(text));
+        }
+
+        let len = if let Some(body) = rest.strip_prefix("/*") {
+            body.find("*/").map_or(rest.len(), |end| end + 4)
+        } else {
+            // A `\r\n` line break is left whole to the whitespace after.
+            let end = rest.find('\n').unwrap_or(rest.len());
+            rest[..end].strip_suffix('\r').map_or(end, str::len)
+        };
+        if len == 0 {
+            return None;
+        }
+        let own_line = std::mem::replace(&mut self.own_line, false);
+        Some(Trivia::Comment {
+            text: self.advance(len),
+            own_line,
+        })
+    }
+}
+
+impl Token<'_> {
+    /// Turns the token into the id `value`, quoted as needed. `start` is
+    /// left as it was.
+    pub fn set_id(&mut self, value: &str) {
+        self.kind = TokenKind::Id(Cow::Owned(value.to_string()));
+        self.text = Cow::Owned(quote_id(value));
+    }
+}
+
+/// Tracks where in the source the next character sits.
+struct Cursor<'a, I: Iterator<Item = char>> {
+    chars: Peekable<I>,
+    /// The whole source, when it is in memory and tokens can borrow from it.
+    source: Option<&'a str>,
+    position: Position,
+    /// Whether only whitespace precedes the cursor on its line.
+    line_blank: bool,
+    /// Where the text consumed since it was last taken starts.
+    raw_start: usize,
+    /// That text, when there is no `source` to borrow it from.
+    raw: String,
+}
+
+impl<'a, I: Iterator<Item = char>> Cursor<'a, I> {
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if self.source.is_none() {
+            self.raw.push(c);
+        }
+        self.position.offset += c.len_utf8();
+        if c == '\n' {
+            self.position.line += 1;
+            self.position.column = 1;
+            self.line_blank = true;
+        } else {
+            self.position.column += 1;
+            self.line_blank &= c.is_whitespace();
+        }
+        Some(c)
+    }
+
+    fn next_if(&mut self, predicate: impl FnOnce(char) -> bool) -> Option<char> {
+        match self.peek() {
+            Some(c) if predicate(c) => self.next(),
+            _ => None,
+        }
+    }
+
+    /// The text consumed since the last call.
+    fn take_raw(&mut self) -> Cow<'a, str> {
+        let start = std::mem::replace(&mut self.raw_start, self.position.offset);
+        match self.source {
+            Some(source) => Cow::Borrowed(&source[start..self.position.offset]),
+            None => Cow::Owned(std::mem::take(&mut self.raw)),
+        }
+    }
+
+    fn skip_raw(&mut self) {
+        self.raw_start = self.position.offset;
+        self.raw.clear();
+    }
+}
+
+/// Splits DOT source into tokens. Built with `new`, it takes characters from
+/// any iterator and its tokens own their text; built with `borrowed`, they
+/// borrow it from the source instead.
+pub struct Lexer<'a, I: Iterator<Item = char>> {
+    cursor: Cursor<'a, I>,
+    /// Trivia read while looking for a `+` after a quoted string.
+    pending: Option<LeadingTrivia<'a>>,
+    done: bool,
+}
+
+impl<I: Iterator<Item = char>> Lexer<'static, I> {
+    pub fn new(source: I) -> Self {
+        Lexer::with_source(source, None)
+    }
+}
+
+impl<'a> Lexer<'a, Chars<'a>> {
+    pub fn borrowed(source: &'a str) -> Self {
+        Lexer::with_source(source.chars(), Some(source))
+    }
+}
+
+impl<'a, I: Iterator<Item = char>> Lexer<'a, I> {
+    fn with_source(chars: I, source: Option<&'a str>) -> Self {
+        Self {
+            cursor: Cursor {
+                chars: chars.peekable(),
+                source,
+                position: Position::start(),
+                line_blank: true,
+                raw_start: 0,
+                raw: String::new(),
+            },
+            pending: None,
+            done: false,
+        }
+    }
+
+    pub fn next_token(&mut self) -> Result<Token<'a>, Diagnostic> {
+        let trivia = match self.pending.take() {
+            Some(trivia) => trivia,
+            None => self.trivia()?,
+        };
+
+        let start = self.cursor.position;
+        let Some(c) = self.cursor.next() else {
+            return Ok(Token {
+                kind: TokenKind::Eof,
+                start,
+                text: self.cursor.take_raw(),
+                trivia,
+            });
+        };
+
+        let kind = match c {
+            '{' => TokenKind::LBrace,
+            '}' => TokenKind::RBrace,
+            '[' => TokenKind::LBracket,
+            ']' => TokenKind::RBracket,
+            ';' => TokenKind::Semicolon,
+            ',' => TokenKind::Comma,
+            '=' => TokenKind::Equals,
+            ':' => TokenKind::Colon,
+            '-' if self.cursor.next_if(|c| c == '>').is_some() => TokenKind::Arrow,
+            '-' if self.cursor.next_if(|c| c == '-').is_some() => TokenKind::Line,
+            '"' => return self.string(start, trivia),
+            '<' => {
+                self.html(start)?;
+                return Ok(self.id_token(start, trivia));
+            }
+            c if c == '-' || c == '.' || c.is_ascii_digit() => {
+                self.numeral(c, start)?;
+                return Ok(self.id_token(start, trivia));
+            }
+            c if is_id_start(c) => {
+                while self.cursor.next_if(is_id_char).is_some() {}
+                let mut token = self.id_token(start, trivia);
+                if let Some(kind) = keyword(&token.text) {
+                    token.kind = kind;
+                }
+                return Ok(token);
+            }
+            other => {
+                return Err(Diagnostic::new(
+                    &format!("unexpected character {other:?}"),
+                    start,
+                ))
+            }
+        };
+
+        Ok(Token {
+            kind,
+            start,
+            text: self.cursor.take_raw(),
+            trivia,
+        })
+    }
+
+    /// A token whose value is its text, borrowed from the source if possible.
+    fn id_token(&mut self, start: Position, trivia: LeadingTrivia<'a>) -> Token<'a> {
+        let text = self.cursor.take_raw();
+        Token {
+            kind: TokenKind::Id(text.clone()),
+            start,
+            text,
+            trivia,
+        }
+    }
+
+    /// Skips whitespace and comments. `#` only starts a comment at the
+    /// beginning of a line, where Graphviz takes it for preprocessor output.
+    fn trivia(&mut self) -> Result<LeadingTrivia<'a>, Diagnostic> {
+        self.cursor.skip_raw();
+        let own_line = self.cursor.line_blank;
+        let offset = self.cursor.position.offset;
+        loop {
+            let start = self.cursor.position;
+            match self.cursor.peek() {
+                Some(c) if c.is_whitespace() => {
+                    while self.cursor.next_if(char::is_whitespace).is_some() {}
+                }
+                Some('#') if self.cursor.line_blank => self.line_comment(),
+                Some('/') => {
+                    self.cursor.next();
+                    match self.cursor.next() {
+                        Some('/') => self.line_comment(),
+                        Some('*') => self.block_comment(start)?,
+                        _ => return Err(Diagnostic::new("unexpected character '/'", start)),
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let text = if self.cursor.position.offset == offset {
+            Cow::Borrowed("")
+        } else {
+            self.cursor.take_raw()
+        };
+        Ok(LeadingTrivia { text, own_line })
+    }
+
+    /// Consumes the rest of the line, leaving the `\n`. The `\r` of a `\r\n`
+    /// is split off the comment again by `TriviaIter`.
+    fn line_comment(&mut self) {
+        while self.cursor.next_if(|c| c != '\n').is_some() {}
+    }
+
+    fn block_comment(&mut self, start: Position) -> Result<(), Diagnostic> {
+        let mut previous = None;
+        while let Some(c) = self.cursor.next() {
+            if previous == Some('*') && c == '/' {
+                return Ok(());
+            }
+            previous = Some(c);
+        }
+        Err(Diagnostic::new("unterminated comment", start).with_end(self.cursor.position))
+    }
+
+    /// A quoted string and those concatenated to it, as in `"a" + "b"`.
+    fn string(
+        &mut self,
+        start: Position,
+        trivia: LeadingTrivia<'a>,
+    ) -> Result<Token<'a>, Diagnostic> {
+        self.quoted(start)?;
+        let mut text = self.cursor.take_raw();
+        let mut value = unquote(&text);
+        // Only built once a `+` shows up, when there is no source to borrow from.
+        let mut written = String::new();
+
+        loop {
+            let gap = self.trivia()?;
+            if self.cursor.peek() != Some('+') {
+                self.pending = Some(gap);
+                break;
+            }
+            if written.is_empty() {
+                written.push_str(&text);
+            }
+            written.push_str(&gap.text);
+            self.cursor.next();
+            self.cursor.take_raw();
+            written.push('+');
+
+            let gap = self.trivia()?;
+            written.push_str(&gap.text);
+            let quote = self.cursor.position;
+            if self.cursor.next_if(|c| c == '"').is_none() {
+                return Err(Diagnostic::new("expected a quoted string after '+'", quote));
+            }
+            self.quoted(quote)?;
+            let part = self.cursor.take_raw();
+            written.push_str(&part);
+            value.to_mut().push_str(&unquote(&part));
+        }
+
+        if !written.is_empty() {
+            text = match self.cursor.source {
+                Some(source) => Cow::Borrowed(&source[start.offset..start.offset + written.len()]),
+                None => Cow::Owned(written),
+            };
+        }
+        Ok(Token {
+            kind: TokenKind::Id(value),
+            start,
+            text,
+            trivia,
+        })
+    }
+
+    /// Consumes the rest of a quoted string; `unquote` takes its value.
+    fn quoted(&mut self, start: Position) -> Result<(), Diagnostic> {
+        loop {
+            match self.cursor.next() {
+                Some('"') => return Ok(()),
+                Some('\\') => {
+                    if self.cursor.next().is_none() {
+                        break;
+                    }
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        Err(Diagnostic::new("unterminated string", start).with_end(self.cursor.position))
+    }
+
+    /// An HTML string, keeping its outer brackets. Brackets inside must balance.
+    fn html(&mut self, start: Position) -> Result<(), Diagnostic> {
+        let mut depth = 1;
+        while depth > 0 {
+            let Some(c) = self.cursor.next() else {
+                return Err(Diagnostic::new("unterminated HTML string", start)
+                    .with_end(self.cursor.position));
+            };
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn numeral(&mut self, first: char, start: Position) -> Result<(), Diagnostic> {
+        let mut seen_dot = first == '.';
+        let mut seen_digit = first.is_ascii_digit();
+        while let Some(c) = self
+            .cursor
+            .next_if(|c| c.is_ascii_digit() || (c == '.' && !seen_dot))
+        {
+            seen_dot |= c == '.';
+            seen_digit |= c.is_ascii_digit();
+        }
+
+        if !seen_digit {
+            let value = &self.cursor.take_raw();
+            return Err(Diagnostic::new(
+                &format!("expected a number after {value:?}"),
+                start,
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl<'a, I: Iterator<Item = char>> Iterator for Lexer<'a, I> {
+    type Item = Result<Token<'a>, Diagnostic>;
+
+    /// Yields tokens up to and including `Eof`, or the first error.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let token = self.next_token();
+        self.done = !matches!(&token, Ok(token) if token.kind != TokenKind::Eof);
+        Some(token)
+    }
+}
+
+/// The value of a quoted string as written. `\"` stands for a quote and a
+/// backslash before a line break, `\n` or `\r\n`, continues the line;
+/// every other backslash sequence is kept for Graphviz to interpret, so the
+/// value only needs its own copy when the string contains one of those.
+fn unquote<'a>(text: &Cow<'a, str>) -> Cow<'a, str> {
+    let inner = &text[1..text.len() - 1];
+    match text {
+        Cow::Borrowed(text)
+            if !inner.contains("\\\"") && !inner.contains("\\\n") && !inner.contains("\\\r\n") =>
+        {
+            Cow::Borrowed(&text[1..text.len() - 1])
+        }
+        _ => {
+            let mut value = String::with_capacity(inner.len());
+            let mut chars = inner.chars().peekable();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => match chars.next() {
+                        Some('"') => value.push('"'),
+                        Some('\n') => {}
+                        Some('\r') if chars.next_if_eq(&'\n').is_some() => {}
+                        next => {
+                            value.push('\\');
+                            value.extend(next);
+                        }
+                    },
+                    c => value.push(c),
+                }
+            }
+            Cow::Owned(value)
+        }
+    }
+}
+
+fn is_id_start(c: char) -> bool {
+    c.is_alphabetic() || c == '_' || !c.is_ascii()
+}
+
+fn is_id_char(c: char) -> bool {
+    is_id_start(c) || c.is_ascii_digit()
+}
+
+fn keyword(word: &str) -> Option<TokenKind<'static>> {
+    let keywords = [
+        ("strict", TokenKind::Strict),
+        ("graph", TokenKind::Graph),
+        ("digraph", TokenKind::Digraph),
+        ("subgraph", TokenKind::Subgraph),
+        ("node", TokenKind::Node),
+        ("edge", TokenKind::Edge),
+    ];
+    keywords
+        .into_iter()
+        .find(|(name, _)| word.eq_ignore_ascii_case(name))
+        .map(|(_, kind)| kind)
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use super::{Lexer, Token, TokenKind, Trivia};
+
+    fn kinds(source: &str) -> Vec<TokenKind<'_>> {
+        Lexer::borrowed(source)
+            .map(|token| token.unwrap().kind)
+            .collect()
+    }
+
+    fn id(value: &str) -> TokenKind<'_> {
+        TokenKind::Id(Cow::Borrowed(value))
+    }
+
+    #[test]
+    fn lexes_statements() {
+        assert_eq!(
+            kinds("DiGraph G { a -> b:n [w=-1.5]; }"),
+            [
+                TokenKind::Digraph,
+                id("G"),
+                TokenKind::LBrace,
+                id("a"),
+                TokenKind::Arrow,
+                id("b"),
+                TokenKind::Colon,
+                id("n"),
+                TokenKind::LBracket,
+                id("w"),
+                TokenKind::Equals,
+                id("-1.5"),
+                TokenKind::RBracket,
+                TokenKind::Semicolon,
+                TokenKind::RBrace,
+                TokenKind::Eof,
+            ]
+        );
+    }
+
+    #[test]
+    fn lexes_strings() {
+        assert_eq!(
+            kinds(r#""say \"hi\"\n" <<b>bold</b>>"#),
+            [id("say \"hi\"\\n"), id("<<b>bold</b>>"), TokenKind::Eof]
+        );
+        assert_eq!(
+            kinds("\"long \\\nline\" <<table><tr><td>a</td></tr></table>>"),
+            [
+                id("long line"),
+                id("<<table><tr><td>a</td></tr></table>>"),
+                TokenKind::Eof
+            ]
+        );
+        assert_eq!(
+            kinds("\"long \\\r\nline\" \"cr \\\r only\""),
+            [id("long line"), id("cr \\\r only"), TokenKind::Eof]
+        );
+    }
+
+    #[test]
+    fn concatenates_strings() {
+        let source = "a = \"x\" + \"y\" /* z */\n  + \"\\\"w\"; \"v\" b";
+        let borrowed: Vec<Token> = Lexer::borrowed(source).map(Result::unwrap).collect();
+        let owned: Vec<Token> = Lexer::new(source.chars()).map(Result::unwrap).collect();
+
+        assert_eq!(borrowed, owned);
+        let kinds: Vec<&TokenKind> = borrowed.iter().map(|token| &token.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                &id("a"),
+                &TokenKind::Equals,
+                &id("xy\"w"),
+                &TokenKind::Semicolon,
+                &id("v"),
+                &id("b"),
+                &TokenKind::Eof
+            ]
+        );
+        assert_eq!(borrowed[2].text, "\"x\" + \"y\" /* z */\n  + \"\\\"w\"");
+        assert_eq!(borrowed[5].trivia.text, " ");
+
+        let error = Lexer::borrowed("\"a\" + b").find_map(Result::err).unwrap();
+        assert_eq!(error.message, "expected a quoted string after '+'");
+        assert_eq!(error.column, 7);
+    }
+
+    #[test]
+    fn borrows_from_the_source() {
+        let source = r#"a "b c" "d \"e\"" /* f */"#;
+        let borrowed: Vec<Token> = Lexer::borrowed(source).map(Result::unwrap).collect();
+        let owned: Vec<Token> = Lexer::new(source.chars()).map(Result::unwrap).collect();
+
+        assert_eq!(borrowed, owned);
+        let values: Vec<&Cow<str>> = borrowed
+            .iter()
+            .filter_map(|token| match &token.kind {
+                TokenKind::Id(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(
+            values[..],
+            [Cow::Borrowed("a"), Cow::Borrowed("b c"), Cow::Owned(_)]
+        ));
+        assert_eq!(values[2], "d \"e\"");
+        assert!(matches!(
+            borrowed[3].trivia.iter().collect::<Vec<_>>()[..],
+            [
+                _,
+                Trivia::Comment {
+                    text: Cow::Borrowed(_),
+                    ..
+                }
+            ]
+        ));
+        assert!(owned
+            .iter()
+            .all(|token| matches!(token.text, Cow::Owned(_))));
+    }
+
+    #[test]
+    fn keeps_trivia() {
+        let source = "# 1 \"x.gv\"\ngraph { // open\n\n\n  /**/ a /* b */\n  #no\n}\n";
+        let tokens: Vec<Token> = Lexer::new(source.chars()).map(Result::unwrap).collect();
+        let comments = |token: &Token| -> Vec<(String, bool)> {
+            token
+                .trivia
+                .iter()
+                .filter_map(|trivia| match trivia {
+                    Trivia::Comment { text, own_line } => Some((text.to_string(), own_line)),
+                    Trivia::Whitespace(_) => None,
+                })
+                .collect()
+        };
+
+        assert_eq!(comments(&tokens[0]), [("# 1 \"x.gv\"".to_string(), true)]);
+        assert_eq!(
+            comments(&tokens[2]),
+            [("// open".to_string(), false), ("/**/".to_string(), true)]
+        );
+        assert!(tokens[2].trivia.iter().nth(2).unwrap().is_blank_line());
+        assert_eq!(
+            comments(&tokens[3]),
+            [("/* b */".to_string(), false), ("#no".to_string(), true)]
+        );
+
+        let rebuilt: String = tokens
+            .iter()
+            .flat_map(|token| [token.trivia.text.as_ref(), token.text.as_ref()])
+            .collect();
+        assert_eq!(rebuilt, source);
+        let pieces: String = tokens
+            .iter()
+            .flat_map(|token| token.trivia.iter())
+            .map(|trivia| trivia.text().to_string())
+            .collect();
+        let joined: String = tokens
+            .iter()
+            .map(|token| token.trivia.text.as_ref())
+            .collect();
+        assert_eq!(pieces, joined);
+    }
+
+    #[test]
+    fn ends_comments_before_crlf() {
+        let source = "graph { // c\r\n# x\r\n  a /* \r\n */\r\n}";
+        let tokens: Vec<Token> = Lexer::borrowed(source).map(Result::unwrap).collect();
+        let trivia: Vec<Trivia> = tokens
+            .iter()
+            .flat_map(|token| token.trivia.iter())
+            .collect();
+
+        assert_eq!(
+            trivia.iter().map(Trivia::text).collect::<Vec<_>>(),
+            [
+                " ",
+                " ",
+                "// c",
+                "\r\n",
+                "# x",
+                "\r\n  ",
+                " ",
+                "/* \r\n */",
+                "\r\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_positions() {
+        let error = Lexer::new("graph {\n  a -- \"open".chars())
+            .find_map(Result::err)
+            .unwrap();
+
+        assert_eq!(error.message, "unterminated string");
+        assert_eq!((error.line, error.column), (2, 8));
+
+        let error = Lexer::new("a \"b\\\"\nc\\\nd".chars())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(error.message, "unterminated string");
+        assert_eq!((error.line, error.column), (1, 3));
+
+        let error = Lexer::new("a /* b\n c *".chars())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(error.message, "unterminated comment");
+        assert_eq!(
+            (error.line, error.column, error.end_line, error.end_column),
+            (1, 3, 2, 5)
+        );
+
+        let error = Lexer::new("\n <a<b>".chars())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(error.message, "unterminated HTML string");
+        assert_eq!((error.line, error.column), (2, 2));
+    }
+}
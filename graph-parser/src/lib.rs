@@ -1,2 +1,13 @@
-mod parser;
-mod streams;
+pub mod ast;
+pub mod attributes;
+pub mod cli;
+pub mod cst;
+pub mod diagnostic;
+pub mod export;
+pub mod format;
+pub mod lexer;
+pub mod lint;
+pub mod lower;
+pub mod lsp;
+pub mod parser;
+pub mod streams;
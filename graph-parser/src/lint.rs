@@ -0,0 +1,637 @@
+//! Checks that go beyond syntax. Each lint has an id and a default severity,
+//! which `LintConfig` or a comment directive can change:
+//!
+//! ```text
+//! // lint: allow(unreachable-node)
+//! a -> b [colour=red] // lint: allow(unknown-attribute)
+//! ```
+//!
+//! A directive on a line of its own applies to the findings below it, so one
+//! at the top covers the whole file; one that follows code only to findings
+//! on its line. The levels are `allow`, `info`, `warn` and `error`, and `all`
+//! stands for every lint.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{AttrTarget, Comment};
+use crate::attributes::{attribute, ATTRIBUTES};
+use crate::cst::{
+    AttributeView, EdgeOperandView, Element, NodeIdView, StatementView, SyntaxNode, SyntaxTree,
+};
+use crate::diagnostic::{Diagnostic, Position};
+use crate::lexer::{Token, TokenKind, Trivia};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The lint is off.
+    Allow,
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Allow => "allow",
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+
+    /// The level as written in a directive.
+    fn from_directive(level: &str) -> Option<Self> {
+        Some(match level {
+            "allow" => Severity::Allow,
+            "info" => Severity::Info,
+            "warn" => Severity::Warning,
+            "error" => Severity::Error,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lint {
+    /// An attribute Graphviz does not know.
+    UnknownAttribute,
+    /// A known attribute set on an element it does not apply to.
+    MisplacedAttribute,
+    /// `->` in an undirected `graph`, or `--` in a `digraph`.
+    EdgeOpMismatch,
+    /// A node declared again with a different value for an attribute.
+    ConflictingNode,
+    /// `node [...]` defaults with no node created after them.
+    UnusedDefault,
+    /// A node no path leads to from a node without incoming edges.
+    UnreachableNode,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 6] = [
+        Lint::UnknownAttribute,
+        Lint::MisplacedAttribute,
+        Lint::EdgeOpMismatch,
+        Lint::ConflictingNode,
+        Lint::UnusedDefault,
+        Lint::UnreachableNode,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Lint::UnknownAttribute => "unknown-attribute",
+            Lint::MisplacedAttribute => "misplaced-attribute",
+            Lint::EdgeOpMismatch => "edge-op-mismatch",
+            Lint::ConflictingNode => "conflicting-node",
+            Lint::UnusedDefault => "unused-default",
+            Lint::UnreachableNode => "unreachable-node",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|lint| lint.id() == id)
+    }
+
+    pub fn default_severity(&self) -> Severity {
+        match self {
+            Lint::EdgeOpMismatch => Severity::Error,
+            Lint::UnreachableNode => Severity::Info,
+            _ => Severity::Warning,
+        }
+    }
+}
+
+/// Severities that differ from the defaults.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LintConfig {
+    severities: HashMap<Lint, Severity>,
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_severity(mut self, lint: Lint, severity: Severity) -> Self {
+        self.severities.insert(lint, severity);
+        self
+    }
+
+    pub fn severity(&self, lint: Lint) -> Severity {
+        self.severities
+            .get(&lint)
+            .copied()
+            .unwrap_or(lint.default_severity())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub lint: Lint,
+    pub severity: Severity,
+    pub diagnostic: Diagnostic,
+    /// A replacement for the flagged text, such as a known attribute name.
+    pub suggestion: Option<&'static str>,
+}
+
+/// Runs every lint that `config` and the file's directives leave on, and
+/// returns the findings in source order.
+pub fn lint(tree: &SyntaxTree, config: &LintConfig) -> Vec<Finding> {
+    let mut own_line_directives = Vec::new();
+    let mut line_directives = Vec::new();
+    directives(
+        &tree.root,
+        &mut 0,
+        &mut own_line_directives,
+        &mut line_directives,
+    );
+
+    let graph = tree.graph();
+    let mut linter = Linter {
+        directed: graph.directed(),
+        findings: Vec::new(),
+        declared: HashMap::new(),
+        created: HashSet::new(),
+        first_mentions: Vec::new(),
+        defaults: Vec::new(),
+    };
+    linter.statements(graph.statements());
+    linter.unreachable(tree);
+    for (diagnostic, op) in mismatched_edge_ops(tree) {
+        linter
+            .findings
+            .push((Lint::EdgeOpMismatch, diagnostic, Some(op)));
+    }
+
+    let mut findings: Vec<Finding> = linter
+        .findings
+        .into_iter()
+        .filter_map(|(lint, diagnostic, suggestion)| {
+            let applies =
+                |directive_lint: &Option<Lint>| directive_lint.is_none_or(|other| other == lint);
+            let directive = line_directives
+                .iter()
+                .rev()
+                .find(|(line, directive_lint, _)| {
+                    *line == diagnostic.line && applies(directive_lint)
+                })
+                .or_else(|| {
+                    own_line_directives
+                        .iter()
+                        .rev()
+                        .find(|(line, directive_lint, _)| {
+                            *line < diagnostic.line && applies(directive_lint)
+                        })
+                })
+                .map(|(_, _, severity)| *severity);
+            let severity = directive.unwrap_or(config.severity(lint));
+            (severity != Severity::Allow).then_some(Finding {
+                lint,
+                severity,
+                diagnostic,
+                suggestion,
+            })
+        })
+        .collect();
+    findings.sort_by_key(|finding| (finding.diagnostic.line, finding.diagnostic.column));
+    findings
+}
+
+/// Edge operators of the other kind of graph, such as `--` in a `digraph`,
+/// each with the operator to use instead. Graphviz rejects them.
+pub fn mismatched_edge_ops(tree: &SyntaxTree) -> Vec<(Diagnostic, &'static str)> {
+    let (wrong, graph, op) = if tree.graph().directed() {
+        (TokenKind::Line, "digraph", "->")
+    } else {
+        (TokenKind::Arrow, "graph", "--")
+    };
+    tree.root
+        .tokens()
+        .into_iter()
+        .filter(|token| token.kind == wrong)
+        .map(|token| {
+            let diagnostic = Diagnostic::new(
+                &format!("edges in a {graph} are written with '{op}'"),
+                token.start,
+            )
+            .with_end(end(token));
+            (diagnostic, op)
+        })
+        .collect()
+}
+
+/// A directive's lints, with `None` for `all`, and its level.
+type Directive = (Vec<Option<Lint>>, Severity);
+
+/// Parses `lint: level(id, ...)`. Unknown levels and lints are ignored.
+fn parse_directive(comment: &Comment) -> Option<Directive> {
+    let body = comment.body();
+    let rest = body.strip_prefix("lint:")?.trim();
+    let (level, ids) = rest.strip_suffix(')')?.split_once('(')?;
+    let severity = Severity::from_directive(level.trim())?;
+    let lints = ids
+        .split(',')
+        .filter_map(|id| match id.trim() {
+            "all" => Some(None),
+            id => Lint::from_id(id).map(Some),
+        })
+        .collect();
+    Some((lints, severity))
+}
+
+/// Collects directives on lines of their own with the line of the code above
+/// them, and the others with their line. `line` is where the last token
+/// ended.
+fn directives(
+    node: &SyntaxNode,
+    line: &mut u32,
+    own_line_directives: &mut Vec<(u32, Option<Lint>, Severity)>,
+    line_directives: &mut Vec<(u32, Option<Lint>, Severity)>,
+) {
+    for child in &node.children {
+        match child {
+            Element::Node(node) => directives(node, line, own_line_directives, line_directives),
+            Element::Token(token) => *line = end(token).line,
+            Element::Trivia(Trivia::Comment { text, own_line }) => {
+                let comment = Comment {
+                    text: text.clone(),
+                    trailing: !own_line,
+                };
+                let Some((lints, severity)) = parse_directive(&comment) else {
+                    continue;
+                };
+                let found = if *own_line {
+                    &mut *own_line_directives
+                } else {
+                    &mut *line_directives
+                };
+                found.extend(lints.into_iter().map(|lint| (*line, lint, severity)));
+            }
+            Element::Trivia(Trivia::Whitespace(_)) => {}
+        }
+    }
+}
+
+struct Linter<'t> {
+    directed: bool,
+    findings: Vec<(Lint, Diagnostic, Option<&'static str>)>,
+    /// The attributes each node statement set so far, with where.
+    declared: HashMap<&'t str, HashMap<&'t str, (&'t str, u32)>>,
+    /// Nodes mentioned so far.
+    created: HashSet<&'t str>,
+    /// The id token of each node's first mention.
+    first_mentions: Vec<&'t Token<'t>>,
+    /// `node [...]` statements of the enclosing scopes, and whether a node
+    /// was created after them.
+    defaults: Vec<Vec<(&'t SyntaxNode<'t>, bool)>>,
+}
+
+impl<'t> Linter<'t> {
+    fn report(&mut self, lint: Lint, message: String, node: &SyntaxNode) {
+        let tokens = node.tokens();
+        let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+            return;
+        };
+        let diagnostic = Diagnostic::new(&message, first.start).with_end(end(last));
+        self.findings.push((lint, diagnostic, None));
+    }
+
+    fn statements(&mut self, statements: impl Iterator<Item = StatementView<'t, 't>>) {
+        self.defaults.push(Vec::new());
+        for statement in statements {
+            match statement {
+                StatementView::Node(view) => {
+                    self.mention(view.node_id());
+                    self.attributes(view.attrs(), AttrTarget::Node);
+                    self.declaration(view.node_id().id(), view.attrs());
+                }
+                StatementView::Edge(view) => {
+                    for operand in view.operands() {
+                        match operand {
+                            EdgeOperandView::Node(node) => self.mention(node),
+                            EdgeOperandView::Subgraph(subgraph) => {
+                                self.statements(subgraph.statements())
+                            }
+                        }
+                    }
+                    self.attributes(view.attrs(), AttrTarget::Edge);
+                }
+                StatementView::Attr(view) => {
+                    self.attributes(view.attrs(), view.target());
+                    if view.target() == AttrTarget::Node {
+                        let scope = self.defaults.last_mut().expect("inside a scope");
+                        scope.push((view.syntax(), false));
+                    }
+                }
+                StatementView::Assignment(view) => {
+                    self.attribute(view.key(), AttrTarget::Graph, view.syntax());
+                }
+                StatementView::Subgraph(view) => self.statements(view.statements()),
+            }
+        }
+
+        for (node, used) in self.defaults.pop().expect("scope was pushed") {
+            if !used {
+                let message = "no node is created after these defaults".to_string();
+                self.report(Lint::UnusedDefault, message, node);
+            }
+        }
+    }
+
+    /// Records a node id, creating the node if it is new.
+    fn mention(&mut self, view: NodeIdView<'t, 't>) {
+        if self.created.insert(view.id()) {
+            self.first_mentions.push(view.id_token());
+            for (_, used) in self.defaults.iter_mut().flatten() {
+                *used = true;
+            }
+        }
+    }
+
+    fn attributes(
+        &mut self,
+        attrs: impl Iterator<Item = AttributeView<'t, 't>>,
+        target: AttrTarget,
+    ) {
+        for attr in attrs {
+            self.attribute(attr.key(), target, attr.syntax());
+        }
+    }
+
+    fn attribute(&mut self, key: &str, target: AttrTarget, node: &'t SyntaxNode<'t>) {
+        let key_token = node.tokens()[0];
+        let Some(info) = attribute(key) else {
+            let suggestion = suggest(key);
+            let mut message = format!("unknown attribute {key:?}");
+            if let Some(name) = suggestion {
+                message.push_str(&format!("; did you mean {name:?}?"));
+            }
+            let diagnostic = Diagnostic::new(&message, key_token.start).with_end(end(key_token));
+            self.findings
+                .push((Lint::UnknownAttribute, diagnostic, suggestion));
+            return;
+        };
+        if !info.targets.contains(&target) {
+            let element = match target {
+                AttrTarget::Graph => "graphs",
+                AttrTarget::Node => "nodes",
+                AttrTarget::Edge => "edges",
+            };
+            let message = format!("{key:?} does not apply to {element}");
+            self.report(Lint::MisplacedAttribute, message, node);
+        }
+    }
+
+    fn declaration(&mut self, id: &'t str, attrs: impl Iterator<Item = AttributeView<'t, 't>>) {
+        for attr in attrs {
+            let line = attr.syntax().tokens()[0].start.line;
+            let earlier = self.declared.entry(id).or_default();
+            match earlier.insert(attr.key(), (attr.value(), line)) {
+                Some((value, line)) if value != attr.value() => {
+                    let message = format!(
+                        "{id:?} sets {} to {:?} here but to {value:?} on line {line}",
+                        attr.key(),
+                        attr.value()
+                    );
+                    self.report(Lint::ConflictingNode, message, attr.syntax());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Reports nodes of a digraph that no path reaches from an entry, a node
+    /// with outgoing but no incoming edges, and nodes without any edges.
+    /// Connected parts with no entry, such as a lone cycle, are left alone
+    /// since any of their nodes could be the start.
+    fn unreachable(&mut self, tree: &SyntaxTree) {
+        if !self.directed {
+            return;
+        }
+        let graph = tree.to_ast().to_graph();
+        if graph.edges.is_empty() {
+            return;
+        }
+        let compact = graph.to_compact();
+        let entries: Vec<_> = compact
+            .nodes()
+            .filter(|&node| compact.out_degree(node) > 0 && compact.in_degree(node) == 0)
+            .collect();
+
+        let mut reached = HashSet::new();
+        let mut pending = entries.clone();
+        while let Some(node) = pending.pop() {
+            if reached.insert(node) {
+                pending.extend(compact.successors(node));
+            }
+        }
+        // Everything connected to an entry, whichever way the edges point.
+        let mut entered = HashSet::new();
+        let mut pending = entries;
+        while let Some(node) = pending.pop() {
+            if entered.insert(node) {
+                pending.extend(compact.successors(node));
+                pending.extend(compact.predecessors(node));
+            }
+        }
+
+        for token in std::mem::take(&mut self.first_mentions) {
+            let TokenKind::Id(id) = &token.kind else {
+                continue;
+            };
+            if compact.symbol(id).is_some_and(|node| {
+                !reached.contains(&node)
+                    && (entered.contains(&node)
+                        || compact.out_degree(node) + compact.in_degree(node) == 0)
+            }) {
+                let diagnostic =
+                    Diagnostic::new(&format!("node {id:?} is unreachable"), token.start)
+                        .with_end(end(token));
+                self.findings
+                    .push((Lint::UnreachableNode, diagnostic, None));
+            }
+        }
+    }
+}
+
+/// Where `token` ends, exclusive.
+fn end(token: &Token) -> Position {
+    let mut position = token.start;
+    for c in token.text.chars() {
+        position.offset += c.len_utf8();
+        if c == '\n' {
+            position.line += 1;
+            position.column = 1;
+        } else {
+            position.column += 1;
+        }
+    }
+    position
+}
+
+/// The known attribute closest to `name`, if close enough to be a typo.
+fn suggest(name: &str) -> Option<&'static str> {
+    let name = name.to_lowercase();
+    ATTRIBUTES
+        .iter()
+        .map(|info| (distance(&name, &info.name.to_lowercase()), info.name))
+        .filter(|&(distance, _)| distance <= (name.chars().count() / 3).max(1))
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, name)| name)
+}
+
+/// The Levenshtein distance between `a` and `b`.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod test {
+    use super::{distance, lint, Lint, LintConfig, Severity};
+    use crate::cst::parse;
+
+    fn findings(source: &str, config: &LintConfig) -> Vec<(Lint, Severity, String, u32)> {
+        lint(&parse(source).unwrap(), config)
+            .into_iter()
+            .map(|finding| {
+                (
+                    finding.lint,
+                    finding.severity,
+                    finding.diagnostic.message,
+                    finding.diagnostic.line,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn measures_edit_distance() {
+        assert_eq!(distance("colr", "color"), 1);
+        assert_eq!(distance("shape", "shape"), 0);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn reports_each_lint() {
+        let source = "graph {
+            a [colr=red, arrowhead=dot]
+            node [shape=box]
+            a -> b
+            b [label=x]
+            b [label=y]
+            edge [shape=box]
+            node [color=red]
+            a -- b
+        }";
+        let found = findings(source, &LintConfig::new());
+
+        let lints: Vec<Lint> = found.iter().map(|(lint, ..)| *lint).collect();
+        assert_eq!(
+            lints,
+            [
+                Lint::UnknownAttribute,
+                Lint::MisplacedAttribute,
+                Lint::EdgeOpMismatch,
+                Lint::ConflictingNode,
+                Lint::MisplacedAttribute,
+                Lint::UnusedDefault,
+            ]
+        );
+        assert_eq!(
+            found[0].2,
+            "unknown attribute \"colr\"; did you mean \"color\"?"
+        );
+        assert_eq!(found[2].1, Severity::Error);
+        assert_eq!(
+            found[3].2,
+            "\"b\" sets label to \"y\" here but to \"x\" on line 5"
+        );
+        assert_eq!(found[5].3, 8);
+    }
+
+    #[test]
+    fn accepts_cluster_attributes() {
+        let source = "graph {
+            subgraph cluster_a {
+                layer=a; pencolor=red; peripheries=2; penwidth=2; margin=8; sortv=1
+                bgcolor=gray; fillcolor=blue; gradientangle=90; area=2; K=1; lp=\"1,2\"
+                x
+            }
+        }";
+        assert!(findings(source, &LintConfig::new()).is_empty());
+    }
+
+    #[test]
+    fn finds_unreachable_nodes() {
+        let source = "digraph { a -> b; c -> d -> c; d -> b; e; f -> a; g -> h -> g }";
+        let found = findings(source, &LintConfig::new());
+
+        let messages: Vec<&str> = found
+            .iter()
+            .map(|(_, _, message, _)| message.as_str())
+            .collect();
+        assert_eq!(
+            messages,
+            [
+                "node \"c\" is unreachable",
+                "node \"d\" is unreachable",
+                "node \"e\" is unreachable"
+            ]
+        );
+        assert!(found
+            .iter()
+            .all(|(_, severity, ..)| *severity == Severity::Info));
+
+        assert!(findings("digraph { a -> b -> c -> a }", &LintConfig::new()).is_empty());
+        assert!(findings("graph { a -- b; b -- c; d }", &LintConfig::new()).is_empty());
+    }
+
+    #[test]
+    fn follows_config_and_directives() {
+        let source = "digraph {
+            // lint: allow(unreachable-node)
+            a [colr=red] // lint: allow(unknown-attribute)
+            b [colr=red]
+            c -- d // lint: warn(all)
+        }";
+        let found = findings(source, &LintConfig::new());
+        assert_eq!(
+            found
+                .iter()
+                .map(|(lint, severity, _, line)| (*lint, *severity, *line))
+                .collect::<Vec<_>>(),
+            [
+                (Lint::UnknownAttribute, Severity::Warning, 4),
+                (Lint::EdgeOpMismatch, Severity::Warning, 5)
+            ]
+        );
+
+        let config = LintConfig::new()
+            .with_severity(Lint::UnknownAttribute, Severity::Error)
+            .with_severity(Lint::EdgeOpMismatch, Severity::Allow);
+        let found = findings("digraph { a [colr=red]; a -- b }", &config);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1, Severity::Error);
+
+        let source = "digraph {
+            a [colr=red]
+            // lint: allow(unknown-attribute)
+            b [colr=red]
+        }";
+        let found = findings(source, &LintConfig::new());
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].3, 2);
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::HashMap;
+
+use graph_dsl::graph::graph_items::attr::AttrMap;
+use graph_dsl::graph::graph_items::edge::Edge;
+use graph_dsl::graph::graph_items::node::Node;
+use graph_dsl::graph::Graph;
+
+use crate::ast::{AttrTarget, Attribute, EdgeOperand, GraphAST, NodeId, Statement};
+
+impl GraphAST<'_> {
+    /// Builds the graph the file describes. Subgraphs are flattened, and
+    /// `node [...]`/`edge [...]` defaults apply to the elements created after
+    /// them within the same subgraph. Graph attributes set inside subgraphs
+    /// are dropped since `Graph` has nowhere to keep them, as are comments;
+    /// see [`GraphAST::node_comments`] for those. A `strict` graph keeps one
+    /// edge per pair of nodes, with the attributes of all its repeats.
+    pub fn to_graph(&self) -> Graph {
+        let mut lowering = Lowering {
+            graph: Graph::new().with_directed(self.directed),
+            index: HashMap::new(),
+        };
+        lowering.graph.strict = self.strict;
+        lowering.statements(&self.statements, &mut Scope::default(), true);
+        lowering.graph
+    }
+}
+
+#[derive(Clone, Default)]
+struct Scope<'a> {
+    node: Vec<Attribute<'a>>,
+    edge: Vec<Attribute<'a>>,
+}
+
+struct Lowering {
+    graph: Graph,
+    /// Position of each node in `graph.nodes`.
+    index: HashMap<String, usize>,
+}
+
+impl Lowering {
+    /// Lowers `statements` and returns the ids of the nodes they mention, in
+    /// order of first mention, for edges that have a subgraph as endpoint.
+    fn statements<'a>(
+        &mut self,
+        statements: &[Statement<'a>],
+        scope: &mut Scope<'a>,
+        root: bool,
+    ) -> Vec<String> {
+        let mut mentioned = Vec::new();
+
+        for statement in statements {
+            match statement {
+                Statement::Node(statement) => {
+                    self.declare(&statement.node.id, scope);
+                    let node = &mut self.graph.nodes[self.index[statement.node.id.as_ref()]];
+                    insert_all(&mut node.attrs, &statement.attrs);
+                    mention(&mut mentioned, &statement.node.id);
+                }
+                Statement::Edge(statement) => {
+                    let ends: Vec<Vec<(String, Option<&str>)>> = statement
+                        .operands
+                        .iter()
+                        .map(|operand| match operand {
+                            EdgeOperand::Node(NodeId { id, port }) => {
+                                self.declare(id, scope);
+                                vec![(id.to_string(), port.as_deref())]
+                            }
+                            EdgeOperand::Subgraph(subgraph) => self
+                                .statements(&subgraph.statements, &mut scope.clone(), false)
+                                .into_iter()
+                                .map(|id| (id, None))
+                                .collect(),
+                        })
+                        .collect();
+
+                    for pair in ends.windows(2) {
+                        for (from, tail_port) in &pair[0] {
+                            for (to, head_port) in &pair[1] {
+                                let mut edge = Edge::new(from, to);
+                                edge.tail_port = tail_port.and_then(|port| port.parse().ok());
+                                edge.head_port = head_port.and_then(|port| port.parse().ok());
+                                insert_all(&mut edge.attrs, &scope.edge);
+                                insert_all(&mut edge.attrs, &statement.attrs);
+                                self.graph.insert_edge(edge);
+                            }
+                        }
+                    }
+                    for (id, _) in ends.iter().flatten() {
+                        mention(&mut mentioned, id);
+                    }
+                }
+                Statement::Attr(statement) => match statement.target {
+                    AttrTarget::Graph if root => {
+                        insert_all(&mut self.graph.attrs, &statement.attrs)
+                    }
+                    AttrTarget::Graph => {}
+                    AttrTarget::Node => scope.node.extend(statement.attrs.iter().cloned()),
+                    AttrTarget::Edge => scope.edge.extend(statement.attrs.iter().cloned()),
+                },
+                Statement::Assignment(attr) => {
+                    if root {
+                        insert_all(&mut self.graph.attrs, std::slice::from_ref(attr));
+                    }
+                }
+                Statement::Subgraph(subgraph) => {
+                    for id in self.statements(&subgraph.statements, &mut scope.clone(), false) {
+                        mention(&mut mentioned, &id);
+                    }
+                }
+                Statement::Comment(_) | Statement::BlankLine => {}
+            }
+        }
+
+        mentioned
+    }
+
+    /// Creates the node with the current defaults unless it already exists.
+    fn declare(&mut self, id: &str, scope: &Scope) {
+        if self.index.contains_key(id) {
+            return;
+        }
+        let mut node = Node::new(id);
+        node.id = self.graph.intern(id);
+        insert_all(&mut node.attrs, &scope.node);
+        self.index.insert(id.to_string(), self.graph.nodes.len());
+        self.graph.nodes.push(node);
+    }
+}
+
+fn mention(mentioned: &mut Vec<String>, id: &str) {
+    if !mentioned.iter().any(|other| other == id) {
+        mentioned.push(id.to_string());
+    }
+}
+
+fn insert_all(map: &mut AttrMap, attrs: &[Attribute]) {
+    for attr in attrs {
+        if attr.html {
+            map.insert_html(attr.key.to_string(), attr.value.to_string());
+        } else {
+            map.insert(attr.key.to_string(), attr.value.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use graph_dsl::graph::graph_items::port::{CompassPoint, Port};
+
+    use crate::ast::{Attribute, GraphAST, NodeId, NodeStatement, Statement};
+    use crate::parser::parse;
+
+    #[test]
+    fn keeps_html_values_apart_from_text() {
+        let node = |id, attr| {
+            Statement::Node(NodeStatement {
+                node: NodeId::new(id),
+                attrs: vec![attr],
+            })
+        };
+        let ast = GraphAST {
+            leading: Vec::new(),
+            strict: false,
+            directed: false,
+            id: None,
+            statements: vec![
+                node("a", Attribute::html("label", "<<b>a</b>>")),
+                node("b", Attribute::new("label", "<b>")),
+            ],
+            trailing: Vec::new(),
+        };
+        let graph = ast.to_graph();
+
+        assert!(graph.nodes[0].attrs.is_html("label"));
+        assert!(!graph.nodes[1].attrs.is_html("label"));
+        assert!(graph.to_dot().contains("b [label=\"<b>\"]"));
+    }
+
+    #[test]
+    fn reads_written_dot_back_the_same() {
+        let source = r#"digraph {
+            a [label="C:\\", xlabel="line\l", tooltip="say \"hi\\\"", comment="\\\\"]
+            a -> b [label=<<i>x</i>>]
+        }"#;
+        let graph = parse(source).unwrap().to_graph();
+        let written = graph.to_dot();
+
+        assert_eq!(graph.node("a").unwrap().attr("label"), Some(r"C:\\"));
+        assert_eq!(parse(&written).unwrap().to_graph(), graph);
+        assert_eq!(parse(&written).unwrap().to_graph().to_dot(), written);
+    }
+
+    #[test]
+    fn lowers_statements() {
+        let source = "strict digraph {
+            rankdir = LR
+            node [shape=box]
+            a [label=A]
+            edge [color=red]
+            a:out:s -> { b c } [weight=2]
+            subgraph s { node [shape=circle]; d }
+            e
+        }";
+        let graph = parse(source).unwrap().to_graph();
+
+        assert!(graph.strict && graph.directed);
+        assert_eq!(graph.attr("rankdir"), Some("LR"));
+        assert_eq!(graph.node_ids(), ["a", "b", "c", "d", "e"]);
+        assert_eq!(graph.node("a").unwrap().attr("label"), Some("A"));
+        assert_eq!(graph.node("b").unwrap().attr("shape"), Some("box"));
+        assert_eq!(graph.node("d").unwrap().attr("shape"), Some("circle"));
+        assert_eq!(graph.node("e").unwrap().attr("shape"), Some("box"));
+
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(
+            (graph.edges[1].from.as_str(), graph.edges[1].to.as_str()),
+            ("a", "c")
+        );
+        assert_eq!(
+            graph.edges[0].tail_port,
+            Some(Port::new("out").with_compass(CompassPoint::S))
+        );
+        assert_eq!(graph.edges[0].attr("color"), Some("red"));
+        assert_eq!(graph.edges[0].attr("weight"), Some("2"));
+    }
+
+    #[test]
+    fn merges_repeated_edges_of_strict_graphs() {
+        let source = "strict digraph { a -> b; b -> a; a -> b [color=red] }";
+        let graph = parse(source).unwrap().to_graph();
+
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].attr("color"), Some("red"));
+        assert_eq!(graph.edges[0].key, None);
+
+        let source = source.replace("strict ", "");
+        assert_eq!(parse(&source).unwrap().to_graph().edges.len(), 3);
+    }
+
+    #[test]
+    fn leaves_comments_out_of_the_graph() {
+        let graph = parse("graph { // The entry point.\n a\n b [comment=kept] }")
+            .unwrap()
+            .to_graph();
+
+        assert_eq!(graph.node("a").unwrap().attr("comment"), None);
+        assert_eq!(graph.node("b").unwrap().attr("comment"), Some("kept"));
+    }
+
+    #[test]
+    fn expands_chains_between_subgraphs() {
+        let graph = parse("graph { {a b} -- {c d} -- e }").unwrap().to_graph();
+        let pairs: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+
+        assert_eq!(
+            pairs,
+            [
+                ("a", "c"),
+                ("a", "d"),
+                ("b", "c"),
+                ("b", "d"),
+                ("c", "e"),
+                ("d", "e")
+            ]
+        );
+    }
+}
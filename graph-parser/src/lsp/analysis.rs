@@ -0,0 +1,394 @@
+use graph_dsl::graph::dot::{quote_id, quote_text};
+use serde_json::{json, Value};
+
+use crate::ast::AttrTarget;
+use crate::attributes::{attributes_for, values_for};
+use crate::cst::{SyntaxKind, SyntaxNode, SyntaxTree};
+use crate::diagnostic::Diagnostic;
+use crate::format::format;
+use crate::lexer::TokenKind;
+use crate::lint::{lint, LintConfig, Severity};
+
+/// Converts between byte offsets and LSP positions, whose `character`
+/// counts UTF-16 code units.
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        Self { text, line_starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Value {
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let start = self.line_starts[line];
+        let character: usize = self.text[start..offset].encode_utf16().count();
+        json!({ "line": line, "character": character })
+    }
+
+    pub fn range(&self, start: usize, end: usize) -> Value {
+        json!({ "start": self.position(start), "end": self.position(end) })
+    }
+
+    /// The byte offset of an LSP position, clamped to the line and text.
+    pub fn offset(&self, position: &Value) -> usize {
+        let line = position["line"].as_u64().unwrap_or(0) as usize;
+        let character = position["character"].as_u64().unwrap_or(0) as usize;
+        let Some(&start) = self.line_starts.get(line) else {
+            return self.text.len();
+        };
+        let mut units = 0;
+        for (index, c) in self.text[start..].char_indices() {
+            if units >= character || c == '\n' {
+                return start + index;
+            }
+            units += c.len_utf16();
+        }
+        self.text.len()
+    }
+
+    /// The offset of a diagnostic's line and (character) column.
+    fn diagnostic_offset(&self, line: u32, column: u32) -> usize {
+        let start = self
+            .line_starts
+            .get(line as usize - 1)
+            .copied()
+            .unwrap_or(self.text.len());
+        self.text[start..]
+            .char_indices()
+            .nth(column as usize - 1)
+            .map_or(self.text.len(), |(index, _)| start + index)
+    }
+}
+
+/// The syntax error, or else the lint findings.
+pub fn diagnostics(text: &str, parsed: Result<&SyntaxTree, &Diagnostic>) -> Value {
+    let index = LineIndex::new(text);
+    let diagnostic = |diagnostic: &Diagnostic, severity: u8| {
+        let start = index.diagnostic_offset(diagnostic.line, diagnostic.column);
+        let end = index.diagnostic_offset(diagnostic.end_line, diagnostic.end_column);
+        json!({
+            "range": index.range(start, end),
+            "severity": severity,
+            "source": "graph-parser",
+            "message": diagnostic.message,
+        })
+    };
+
+    let tree = match parsed {
+        Ok(tree) => tree,
+        Err(error) => return json!([diagnostic(error, 1)]),
+    };
+    let findings = lint(tree, &LintConfig::new()).into_iter().map(|finding| {
+        let severity = match finding.severity {
+            Severity::Error => 1,
+            Severity::Warning => 2,
+            Severity::Info | Severity::Allow => 3,
+        };
+        let mut value = diagnostic(&finding.diagnostic, severity);
+        value["code"] = json!(finding.lint.id());
+        value
+    });
+    Value::Array(findings.collect())
+}
+
+/// A node id written in a statement or edge.
+struct Occurrence<'a> {
+    id: &'a str,
+    start: usize,
+    end: usize,
+    /// Whether it stands in a node statement, rather than an edge.
+    declaration: bool,
+}
+
+fn occurrences<'t>(tree: &'t SyntaxTree) -> Vec<Occurrence<'t>> {
+    fn walk<'t>(node: &'t SyntaxNode, out: &mut Vec<Occurrence<'t>>) {
+        for child in node.child_nodes() {
+            if child.kind == SyntaxKind::NodeId {
+                let token = child.tokens()[0];
+                if let TokenKind::Id(id) = &token.kind {
+                    out.push(Occurrence {
+                        id,
+                        start: token.start.offset,
+                        end: token.start.offset + token.text.len(),
+                        declaration: node.kind == SyntaxKind::NodeStatement,
+                    });
+                }
+            } else {
+                walk(child, out);
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(&tree.root, &mut out);
+    out
+}
+
+fn id_at<'a>(occurrences: &[Occurrence<'a>], offset: usize) -> Option<&'a str> {
+    occurrence_at(occurrences, offset).map(|occurrence| occurrence.id)
+}
+
+fn occurrence_at<'o, 'a>(
+    occurrences: &'o [Occurrence<'a>],
+    offset: usize,
+) -> Option<&'o Occurrence<'a>> {
+    occurrences
+        .iter()
+        .find(|occurrence| occurrence.start <= offset && offset <= occurrence.end)
+}
+
+/// The first node statement for the id under the cursor, or its first
+/// mention if it is only used in edges.
+pub fn definition(text: &str, tree: &SyntaxTree, position: &Value, uri: &str) -> Value {
+    let index = LineIndex::new(text);
+    let all = occurrences(tree);
+    let Some(id) = id_at(&all, index.offset(position)) else {
+        return Value::Null;
+    };
+    let same: Vec<&Occurrence> = all.iter().filter(|other| other.id == id).collect();
+    let target = same
+        .iter()
+        .find(|occurrence| occurrence.declaration)
+        .unwrap_or(&same[0]);
+    json!({ "uri": uri, "range": index.range(target.start, target.end) })
+}
+
+pub fn references(
+    text: &str,
+    tree: &SyntaxTree,
+    position: &Value,
+    uri: &str,
+    include_declaration: bool,
+) -> Value {
+    let index = LineIndex::new(text);
+    let all = occurrences(tree);
+    let Some(id) = id_at(&all, index.offset(position)) else {
+        return Value::Null;
+    };
+    let locations: Vec<Value> = all
+        .iter()
+        .filter(|other| other.id == id && (include_declaration || !other.declaration))
+        .map(|other| json!({ "uri": uri, "range": index.range(other.start, other.end) }))
+        .collect();
+    Value::Array(locations)
+}
+
+/// Renames the id under the cursor with [`SyntaxTree::rename_node`] and
+/// sends an edit for each token it changed.
+pub fn rename(text: &str, tree: &SyntaxTree, position: &Value, uri: &str, new_name: &str) -> Value {
+    let index = LineIndex::new(text);
+    let all = occurrences(tree);
+    let Some(id) = id_at(&all, index.offset(position)) else {
+        return Value::Null;
+    };
+    let mut renamed = tree.clone();
+    renamed.rename_node(id, new_name);
+    let edits: Vec<Value> = tree
+        .root
+        .tokens()
+        .into_iter()
+        .zip(renamed.root.tokens())
+        .filter(|(old, new)| old.text != new.text)
+        .map(|(old, new)| {
+            let start = old.start.offset;
+            json!({ "range": index.range(start, start + old.text.len()), "newText": new.text })
+        })
+        .collect();
+    json!({ "changes": { uri: edits } })
+}
+
+/// The node under the cursor as one statement, with the attributes it ends
+/// up with once defaults and all its statements are applied.
+pub fn hover(text: &str, tree: &SyntaxTree, position: &Value) -> Value {
+    let index = LineIndex::new(text);
+    let all = occurrences(tree);
+    let Some(occurrence) = occurrence_at(&all, index.offset(position)) else {
+        return Value::Null;
+    };
+
+    let graph = tree.to_ast().to_graph();
+    let attrs: Vec<String> = graph
+        .node(occurrence.id)
+        .map(|node| {
+            node.attrs()
+                .map(|(key, value)| {
+                    format!(
+                        "{}={}",
+                        quote_id(key),
+                        if node.attrs.is_html(key) {
+                            value.to_string()
+                        } else {
+                            quote_text(value)
+                        }
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let statement = if attrs.is_empty() {
+        quote_id(occurrence.id)
+    } else {
+        format!("{} [{}]", quote_id(occurrence.id), attrs.join(", "))
+    };
+    json!({
+        "contents": { "kind": "markdown", "value": format!("```dot\n{statement}\n```") },
+        "range": index.range(occurrence.start, occurrence.end),
+    })
+}
+
+/// Attribute names inside `[...]`, or values after `name=`. Works on the
+/// text alone, so it keeps working while the file does not parse.
+pub fn completion(text: &str, position: &Value) -> Value {
+    let index = LineIndex::new(text);
+    let before = &text[..index.offset(position)];
+    let Some(open) = before.rfind('[') else {
+        return json!([]);
+    };
+    if before.rfind(']').is_some_and(|close| close > open) {
+        return json!([]);
+    }
+
+    let statement = before[..open]
+        .rsplit(['\n', ';', '{', '}'])
+        .next()
+        .unwrap_or_default()
+        .trim();
+    let target = if statement.starts_with("graph") {
+        AttrTarget::Graph
+    } else if statement.starts_with("edge") || statement.contains("->") || statement.contains("--")
+    {
+        AttrTarget::Edge
+    } else {
+        AttrTarget::Node
+    };
+
+    let entry = before[open + 1..]
+        .rsplit([',', ';'])
+        .next()
+        .unwrap_or_default();
+    let items: Vec<Value> = match entry.split_once('=') {
+        Some((name, _)) => {
+            let name = name.trim().trim_matches('"');
+            values_for(name, target)
+                .iter()
+                .map(|value| json!({ "label": value, "kind": 12 }))
+                .collect()
+        }
+        None => attributes_for(target)
+            .map(|info| json!({ "label": info.name, "kind": 10, "detail": info.description }))
+            .collect(),
+    };
+    Value::Array(items)
+}
+
+/// Named subgraphs as a tree of `DocumentSymbol`s.
+pub fn document_symbols(text: &str, tree: &SyntaxTree) -> Value {
+    fn walk(index: &LineIndex, node: &SyntaxNode) -> Vec<Value> {
+        let mut symbols = Vec::new();
+        for child in node.child_nodes() {
+            let nested = walk(index, child);
+            let id = child
+                .child_tokens()
+                .find(|token| matches!(token.kind, TokenKind::Id(_)));
+            match (child.kind, id) {
+                (SyntaxKind::Subgraph, Some(id)) => {
+                    let (start, end) = span(child);
+                    let name = match &id.kind {
+                        TokenKind::Id(name) => name.clone(),
+                        _ => unreachable!(),
+                    };
+                    let detail = attribute_of(child, "label").unwrap_or_default();
+                    symbols.push(json!({
+                        "name": name,
+                        "detail": detail,
+                        "kind": 3,
+                        "range": index.range(start, end),
+                        "selectionRange": index.range(id.start.offset, id.start.offset + id.text.len()),
+                        "children": nested,
+                    }));
+                }
+                _ => symbols.extend(nested),
+            }
+        }
+        symbols
+    }
+
+    Value::Array(walk(&LineIndex::new(text), &tree.root))
+}
+
+/// A `key=value` statement directly in the subgraph's body.
+fn attribute_of(subgraph: &SyntaxNode, key: &str) -> Option<String> {
+    let block = subgraph
+        .child_nodes()
+        .find(|node| node.kind == SyntaxKind::Block)?;
+    block
+        .child_nodes()
+        .filter(|node| node.kind == SyntaxKind::Assignment)
+        .find_map(|node| {
+            let mut ids = node.child_ids();
+            (ids.next() == Some(key)).then(|| ids.next().unwrap_or_default().to_string())
+        })
+}
+
+fn span(node: &SyntaxNode) -> (usize, usize) {
+    let tokens = node.tokens();
+    let first = tokens.first().map_or(0, |token| token.start.offset);
+    let last = tokens
+        .last()
+        .map_or(0, |token| token.start.offset + token.text.len());
+    (first, last)
+}
+
+/// Replaces the whole document, unless it is formatted already.
+pub fn formatting(text: &str, tree: &SyntaxTree) -> Value {
+    let formatted = format(&tree.to_ast());
+    if formatted == text {
+        return json!([]);
+    }
+    let index = LineIndex::new(text);
+    json!([{ "range": index.range(0, text.len()), "newText": formatted }])
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::{completion, LineIndex};
+
+    #[test]
+    fn converts_positions() {
+        let text = "graph {\n  \"€😀\" -- b\n}";
+        let index = LineIndex::new(text);
+        let b = text.find('b').unwrap();
+
+        assert_eq!(index.position(b), json!({ "line": 1, "character": 11 }));
+        assert_eq!(index.offset(&json!({ "line": 1, "character": 11 })), b);
+        assert_eq!(index.offset(&json!({ "line": 1, "character": 99 })), b + 1);
+    }
+
+    #[test]
+    fn completes_by_context() {
+        let labels = |text: &str| -> Vec<String> {
+            let line = text.lines().count() - 1;
+            let character = text.lines().last().unwrap().len();
+            completion(text, &json!({ "line": line, "character": character }))
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|item| item["label"].as_str().unwrap().to_string())
+                .collect()
+        };
+
+        assert!(labels("digraph {\n  a [sh").contains(&"shape".to_string()));
+        assert!(!labels("digraph {\n  a -> b [").contains(&"shape".to_string()));
+        assert!(labels("digraph {\n  a -> b [color=red, dir=").contains(&"both".to_string()));
+        assert!(labels("digraph {\n  graph [rankdir=").contains(&"LR".to_string()));
+        assert!(labels("digraph {\n  a [shape=box] ").is_empty());
+    }
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+
+use crate::cst;
+
+pub mod analysis;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// Reads one `Content-Length` framed message body, or `None` at end of input.
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = length.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length header")
+    })?;
+    let mut body = vec![0; length];
+    input.read_exact(&mut body)?;
+    String::from_utf8(body)
+        .map(Some)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush()
+}
+
+/// The language server's state, independent of the transport.
+#[derive(Default)]
+pub struct Server {
+    /// Open documents by URI. They are parsed again for each request, since
+    /// a tree borrows from its text.
+    documents: HashMap<String, String>,
+    shutting_down: bool,
+    exited: bool,
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the client sent `exit`.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// The exit code the process should end with: 0 if `shutdown` came
+    /// before `exit`, 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        if self.shutting_down {
+            0
+        } else {
+            1
+        }
+    }
+
+    /// Handles one message and returns the responses and notifications to send.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        // Responses to requests of ours carry an id but no method.
+        let Some(method) = message["method"].as_str() else {
+            return Vec::new();
+        };
+        let params = &message["params"];
+        let Some(id) = message.get("id") else {
+            return self.notification(method, params);
+        };
+
+        if self.shutting_down {
+            return vec![error(id, INVALID_REQUEST, "server is shutting down")];
+        }
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "renameProvider": true,
+                    "hoverProvider": true,
+                    "completionProvider": { "triggerCharacters": ["[", ",", ";", "="] },
+                    "documentSymbolProvider": true,
+                    "documentFormattingProvider": true,
+                },
+                "serverInfo": { "name": "dot-lsp", "version": env!("CARGO_PKG_VERSION") },
+            }),
+            "shutdown" => {
+                self.shutting_down = true;
+                Value::Null
+            }
+            "textDocument/completion" => match self.document(params) {
+                Some(text) => analysis::completion(text, &params["position"]),
+                None => Value::Null,
+            },
+            "textDocument/definition"
+            | "textDocument/references"
+            | "textDocument/rename"
+            | "textDocument/hover"
+            | "textDocument/documentSymbol"
+            | "textDocument/formatting" => self.query(method, params),
+            _ => {
+                return vec![error(
+                    id,
+                    METHOD_NOT_FOUND,
+                    &format!("unknown method {method}"),
+                )];
+            }
+        };
+        vec![json!({ "jsonrpc": "2.0", "id": id, "result": result })]
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.to_string(), text.to_string());
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+                let Some(text) = changes.and_then(|changes| changes.last()?["text"].as_str())
+                else {
+                    return Vec::new();
+                };
+                self.documents.insert(uri.to_string(), text.to_string());
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                vec![publish(uri, json!([]))]
+            }
+            "exit" => {
+                self.exited = true;
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn document(&self, params: &Value) -> Option<&str> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        self.documents.get(uri).map(String::as_str)
+    }
+
+    /// Requests that need the document to parse; they get `null` otherwise.
+    fn query(&self, method: &str, params: &Value) -> Value {
+        let Some(text) = self.document(params) else {
+            return Value::Null;
+        };
+        let Ok(tree) = &cst::parse(text) else {
+            return Value::Null;
+        };
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let position = &params["position"];
+
+        match method {
+            "textDocument/definition" => analysis::definition(text, tree, position, uri),
+            "textDocument/references" => {
+                let include = params["context"]["includeDeclaration"]
+                    .as_bool()
+                    .unwrap_or(true);
+                analysis::references(text, tree, position, uri, include)
+            }
+            "textDocument/rename" => {
+                let new_name = params["newName"].as_str().unwrap_or_default();
+                analysis::rename(text, tree, position, uri, new_name)
+            }
+            "textDocument/hover" => analysis::hover(text, tree, position),
+            "textDocument/documentSymbol" => analysis::document_symbols(text, tree),
+            "textDocument/formatting" => analysis::formatting(text, tree),
+            _ => Value::Null,
+        }
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let text = &self.documents[uri];
+        let diagnostics = analysis::diagnostics(text, cst::parse(text).as_ref());
+        publish(uri, diagnostics)
+    }
+}
+
+fn publish(uri: &str, diagnostics: Value) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+fn error(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+/// Serves LSP over `input` and `output` until the client sends `exit` or
+/// closes the input. Returns the process exit code.
+pub fn serve(mut input: impl BufRead, mut output: impl Write) -> io::Result<i32> {
+    let mut server = Server::new();
+    while let Some(body) = read_message(&mut input)? {
+        let replies = match serde_json::from_str::<Value>(&body) {
+            Ok(message) => server.handle(&message),
+            Err(parse_error) => vec![error(&Value::Null, PARSE_ERROR, &parse_error.to_string())],
+        };
+        for reply in replies {
+            write_message(&mut output, &reply)?;
+        }
+        if server.exited() {
+            return Ok(server.exit_code());
+        }
+    }
+    Ok(1)
+}
@@ -1,3 +1,12 @@
+use std::io;
+
 fn main() {
-    let a = "somestring".to_string().chars().peekable();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let code = graph_parser::cli::run(
+        &args,
+        io::stdin().lock(),
+        &mut io::stdout().lock(),
+        &mut io::stderr().lock(),
+    );
+    std::process::exit(code);
 }
@@ -1,70 +1,546 @@
-use std::iter::Peekable;
+use std::borrow::Cow;
 
-type Chars<'a> = Peekable<std::str::Chars<'a>>;
+use graph_dsl::graph::graph_items::port::Port;
 
-struct Diagnostic {
-    message: String,
-    line: u32,
-    column: u32,
-}
+use crate::ast::GraphAST;
+use crate::cst::{Element, SyntaxKind, SyntaxNode, SyntaxTree};
+use crate::diagnostic::Diagnostic;
+use crate::lexer::{Lexer, Token, TokenKind};
 
-struct GraphAST {
-    root: Node,
+/// Parses a whole file. The result borrows ids and comments from `source`.
+pub fn parse(source: &str) -> Result<GraphAST<'_>, Diagnostic> {
+    Ok(parse_tree(source)?.to_ast())
 }
 
-struct GraphNode {}
-enum Node {
-    Graph(GraphNode),
+/// Parses characters from any source. Unlike `parse`, the result has to own
+/// every id and comment.
+pub fn parse_chars(source: impl Iterator<Item = char>) -> Result<GraphAST<'static>, Diagnostic> {
+    let mut parser = Parser::new(Lexer::new(source))?;
+    parser.header()?;
+    while parser.root_statement()? {}
+    Ok(parser.into_tree().to_ast())
 }
 
-pub fn parse(source: Chars) -> Result<GraphAST, Diagnostic> {
-    let parser = Parser { source };
+/// Parses into the lossless syntax tree that `parse` builds its result from.
+pub fn parse_tree(source: &str) -> Result<SyntaxTree<'_>, Diagnostic> {
+    let mut parser = Parser::new(Lexer::borrowed(source))?;
+    parser.header()?;
+    while parser.root_statement()? {}
+    Ok(parser.into_tree())
+}
 
-    parser.run()
+/// What `Parser::block_step` parsed.
+pub(crate) enum Step<'a> {
+    /// A statement other than a subgraph, now the last node of its block.
+    Statement,
+    /// `subgraph id? {`, whose block is now the innermost open node.
+    SubgraphStart { id: Option<Cow<'a, str>> },
+    /// The `}` of a subgraph, and the rest of the edge statement if the
+    /// subgraph turned out to be its first operand.
+    SubgraphEnd { edge: bool },
+    /// The `}` of the root block, followed by the end of input.
+    GraphEnd,
 }
 
-struct Parser<'a> {
-    source: Chars<'a>,
+/// A recursive descent parser that builds the syntax tree as it goes. The
+/// root block can also be parsed a statement at a time, see `StatementStream`.
+pub(crate) struct Parser<'a, I: Iterator<Item = char>> {
+    lexer: Lexer<'a, I>,
+    current: Token<'a>,
+    /// Nodes under construction with the children they have so far,
+    /// innermost last.
+    stack: Vec<(SyntaxKind, Vec<Element<'a>>)>,
 }
 
-impl<'a> Parser<'a> {
-    fn run(&self) -> Result<GraphAST, Diagnostic> {
-        todo!()
+impl<'a, I: Iterator<Item = char>> Parser<'a, I> {
+    pub(crate) fn new(mut lexer: Lexer<'a, I>) -> Result<Self, Diagnostic> {
+        let current = lexer.next_token()?;
+        Ok(Self {
+            lexer,
+            current,
+            stack: vec![(SyntaxKind::Graph, Vec::new())],
+        })
     }
 
-    fn read_word(&mut self) -> Result<String, Diagnostic> {
-        let mut result = String::new();
-        while let Some(char) = self.source.next_if(|c| !c.is_whitespace()) {
-            result.push(char);
+    /// `strict? (graph | digraph) id? '{'`, leaving the root block open.
+    pub(crate) fn header(&mut self) -> Result<(), Diagnostic> {
+        self.eat(&TokenKind::Strict)?;
+        if !matches!(self.current.kind, TokenKind::Graph | TokenKind::Digraph) {
+            return Err(self.unexpected("'graph' or 'digraph'"));
         }
+        self.bump()?;
+        self.optional_id()?;
+        if self.current.kind != TokenKind::LBrace {
+            return Err(self.unexpected("'{'"));
+        }
+        self.start(SyntaxKind::Block);
+        self.bump()
+    }
 
-        if result.is_empty() {
-            return Err(self.make_diagnostic("Expected word, received nothing"));
+    /// Parses the next statement of the root block into it, or closes the
+    /// block at `}` and returns false.
+    pub(crate) fn root_statement(&mut self) -> Result<bool, Diagnostic> {
+        if self.eat(&TokenKind::RBrace)? {
+            self.finish();
+            if self.current.kind != TokenKind::Eof {
+                return Err(self.unexpected("end of input"));
+            }
+            self.attach_trivia();
+            let eof = self.current.clone();
+            self.children().push(Element::Token(eof));
+            return Ok(false);
         }
+        self.statement()?;
+        self.eat(&TokenKind::Semicolon)?;
+        Ok(true)
+    }
 
-        Ok(result)
+    /// Parses one step of the innermost open block, keeping subgraph blocks
+    /// open so their statements come one at a time too. See `Step`.
+    pub(crate) fn block_step(&mut self) -> Result<Step<'a>, Diagnostic> {
+        if self.stack.len() == 2 && self.current.kind == TokenKind::RBrace {
+            self.root_statement()?;
+            return Ok(Step::GraphEnd);
+        }
+        if self.eat(&TokenKind::RBrace)? {
+            self.finish();
+            self.finish();
+            let edge = self.at_edge_op();
+            if edge {
+                let checkpoint = self.children().len() - 1;
+                self.edge_rest(checkpoint)?;
+            }
+            self.eat(&TokenKind::Semicolon)?;
+            return Ok(Step::SubgraphEnd { edge });
+        }
+        if matches!(self.current.kind, TokenKind::Subgraph | TokenKind::LBrace) {
+            self.start(SyntaxKind::Subgraph);
+            let id = if self.eat(&TokenKind::Subgraph)? {
+                self.optional_id()?
+            } else {
+                None
+            };
+            self.start(SyntaxKind::Block);
+            self.expect(&TokenKind::LBrace)?;
+            return Ok(Step::SubgraphStart { id });
+        }
+        self.statement()?;
+        self.eat(&TokenKind::Semicolon)?;
+        Ok(Step::Statement)
+    }
+
+    /// The last node the innermost open node holds, e.g. the statement
+    /// `block_step` just parsed.
+    pub(crate) fn last_node(&self) -> Option<&SyntaxNode<'a>> {
+        let children = &self.stack.last()?.1;
+        children.iter().rev().find_map(|child| match child {
+            Element::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    /// Swaps what the innermost open node holds so far, e.g. the statements
+    /// of the root block parsed since the last call, with `buffer`. Passing
+    /// back a cleared buffer each time saves reallocating it.
+    pub(crate) fn swap_children(&mut self, buffer: &mut Vec<Element<'a>>) {
+        std::mem::swap(self.children(), buffer);
+    }
+
+    /// Drops what the innermost open node holds so far, keeping its capacity.
+    pub(crate) fn clear_children(&mut self) {
+        self.children().clear();
+    }
+
+    /// Takes what the root holds so far, e.g. the header once it is parsed.
+    pub(crate) fn take_root(&mut self) -> SyntaxNode<'a> {
+        SyntaxNode {
+            kind: SyntaxKind::Graph,
+            children: std::mem::take(&mut self.stack[0].1),
+        }
+    }
+
+    pub(crate) fn into_tree(mut self) -> SyntaxTree<'a> {
+        let (kind, children) = self.stack.pop().unwrap();
+        SyntaxTree {
+            root: SyntaxNode { kind, children },
+        }
+    }
+
+    fn children(&mut self) -> &mut Vec<Element<'a>> {
+        &mut self.stack.last_mut().unwrap().1
+    }
+
+    /// Moves the trivia before the current token into the innermost node.
+    fn attach_trivia(&mut self) {
+        let trivia = std::mem::take(&mut self.current.trivia);
+        self.children()
+            .extend(trivia.into_iter().map(Element::Trivia));
+    }
+
+    /// Adds the current token to the innermost node and moves on.
+    fn bump(&mut self) -> Result<(), Diagnostic> {
+        self.attach_trivia();
+        let next = self.lexer.next_token()?;
+        let token = std::mem::replace(&mut self.current, next);
+        self.children().push(Element::Token(token));
+        Ok(())
+    }
+
+    fn start(&mut self, kind: SyntaxKind) {
+        let checkpoint = self.checkpoint();
+        self.start_at(checkpoint, kind);
+    }
+
+    /// Where a node that is only recognised later will begin.
+    fn checkpoint(&mut self) -> usize {
+        self.attach_trivia();
+        self.children().len()
+    }
+
+    /// Starts a node holding everything added since `checkpoint`.
+    fn start_at(&mut self, checkpoint: usize, kind: SyntaxKind) {
+        let children = self.children().split_off(checkpoint);
+        self.stack.push((kind, children));
+    }
+
+    fn finish(&mut self) {
+        let (kind, children) = self.stack.pop().unwrap();
+        self.children()
+            .push(Element::Node(SyntaxNode { kind, children }));
+    }
+
+    /// Consumes the current token if it is `kind`.
+    fn eat(&mut self, kind: &TokenKind) -> Result<bool, Diagnostic> {
+        if &self.current.kind == kind {
+            self.bump()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect(&mut self, kind: &TokenKind) -> Result<(), Diagnostic> {
+        if self.eat(kind)? {
+            Ok(())
+        } else {
+            Err(self.unexpected(&kind.describe()))
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> Diagnostic {
+        self.make_diagnostic(&format!(
+            "expected {expected}, found {}",
+            self.current.kind.describe()
+        ))
     }
 
     fn make_diagnostic(&self, message: &str) -> Diagnostic {
-        Diagnostic {
-            message: message.to_string(),
-            line: 0,
-            column: 0,
-            // line: self.source.line,
-            // column: self.source.column,
+        Diagnostic::new(message, self.current.start)
+    }
+
+    fn optional_id(&mut self) -> Result<Option<Cow<'a, str>>, Diagnostic> {
+        match &self.current.kind {
+            TokenKind::Id(id) => {
+                let id = id.clone();
+                self.bump()?;
+                Ok(Some(id))
+            }
+            _ => Ok(None),
         }
     }
+
+    fn id(&mut self) -> Result<Cow<'a, str>, Diagnostic> {
+        self.optional_id()?
+            .ok_or_else(|| self.unexpected("an identifier"))
+    }
+
+    /// `'{' stmt_list '}'`
+    fn block(&mut self) -> Result<(), Diagnostic> {
+        if self.current.kind != TokenKind::LBrace {
+            return Err(self.unexpected("'{'"));
+        }
+        self.start(SyntaxKind::Block);
+        self.bump()?;
+        while !self.eat(&TokenKind::RBrace)? {
+            self.statement()?;
+            self.eat(&TokenKind::Semicolon)?;
+        }
+        self.finish();
+        Ok(())
+    }
+
+    fn statement(&mut self) -> Result<(), Diagnostic> {
+        if matches!(
+            self.current.kind,
+            TokenKind::Graph | TokenKind::Node | TokenKind::Edge
+        ) {
+            self.start(SyntaxKind::AttrStatement);
+            self.bump()?;
+            if self.current.kind != TokenKind::LBracket {
+                return Err(self.unexpected("'['"));
+            }
+            self.attr_lists()?;
+            self.finish();
+            return Ok(());
+        }
+
+        let checkpoint = self.checkpoint();
+        if matches!(self.current.kind, TokenKind::Subgraph | TokenKind::LBrace) {
+            self.subgraph()?;
+            if self.at_edge_op() {
+                self.edge_rest(checkpoint)?;
+            }
+            return Ok(());
+        }
+
+        self.id()?;
+        if self.current.kind == TokenKind::Equals {
+            self.start_at(checkpoint, SyntaxKind::Assignment);
+            self.bump()?;
+            self.id()?;
+            self.finish();
+            return Ok(());
+        }
+
+        self.node_id_rest(checkpoint)?;
+        if self.at_edge_op() {
+            return self.edge_rest(checkpoint);
+        }
+        self.start_at(checkpoint, SyntaxKind::NodeStatement);
+        self.attr_lists()?;
+        self.finish();
+        Ok(())
+    }
+
+    fn at_edge_op(&self) -> bool {
+        matches!(self.current.kind, TokenKind::Arrow | TokenKind::Line)
+    }
+
+    /// The rest of an edge statement whose first operand starts at `checkpoint`.
+    fn edge_rest(&mut self, checkpoint: usize) -> Result<(), Diagnostic> {
+        self.start_at(checkpoint, SyntaxKind::EdgeStatement);
+        while self.at_edge_op() {
+            self.bump()?;
+            self.edge_operand()?;
+        }
+        self.attr_lists()?;
+        self.finish();
+        Ok(())
+    }
+
+    fn edge_operand(&mut self) -> Result<(), Diagnostic> {
+        if matches!(self.current.kind, TokenKind::Subgraph | TokenKind::LBrace) {
+            return self.subgraph();
+        }
+        let checkpoint = self.checkpoint();
+        self.id()?;
+        self.node_id_rest(checkpoint)
+    }
+
+    /// Wraps the id added at `checkpoint` into a node id, with the optional
+    /// `:port[:compass]` that follows.
+    fn node_id_rest(&mut self, checkpoint: usize) -> Result<(), Diagnostic> {
+        self.start_at(checkpoint, SyntaxKind::NodeId);
+        if self.eat(&TokenKind::Colon)? {
+            let start = self.current.start;
+            let mut text = self.id()?;
+            if self.eat(&TokenKind::Colon)? {
+                text = Cow::Owned(format!("{text}:{}", self.id()?));
+            }
+            if let Err(error) = text.parse::<Port>() {
+                return Err(Diagnostic::new(&error.to_string(), start));
+            }
+        }
+        self.finish();
+        Ok(())
+    }
+
+    fn subgraph(&mut self) -> Result<(), Diagnostic> {
+        self.start(SyntaxKind::Subgraph);
+        if self.eat(&TokenKind::Subgraph)? {
+            self.optional_id()?;
+        }
+        self.block()?;
+        self.finish();
+        Ok(())
+    }
+
+    /// Zero or more `[a=b, c=d; e=f]` lists.
+    fn attr_lists(&mut self) -> Result<(), Diagnostic> {
+        while self.current.kind == TokenKind::LBracket {
+            self.start(SyntaxKind::AttrList);
+            self.bump()?;
+            while !self.eat(&TokenKind::RBracket)? {
+                self.start(SyntaxKind::Attribute);
+                self.id()?;
+                self.expect(&TokenKind::Equals)?;
+                self.id()?;
+                self.finish();
+                if !self.eat(&TokenKind::Comma)? {
+                    self.eat(&TokenKind::Semicolon)?;
+                }
+            }
+            self.finish();
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use std::borrow::Cow;
+
     use super::parse;
+    use crate::ast::{
+        AttrStatement, AttrTarget, Attribute, EdgeOp, EdgeOperand, EdgeStatement, NodeId,
+        NodeStatement, Statement, Subgraph,
+    };
 
     #[test]
     fn should_work() {
         let input: String = "graph MyGraph {}".to_string();
-        let result = parse(input.chars().peekable());
+        let result = parse(&input);
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn parses_statements() {
+        let source = "strict digraph {
+            rankdir = LR
+            node [shape=box]
+            a [label=\"A\"; color=red] [style=bold]
+            a:out:e -> b -> {c d} [weight=2];
+            subgraph cluster_x { e }
+        }";
+        let ast = parse(source).unwrap();
+
+        assert!(ast.strict);
+        assert!(ast.directed);
+        assert_eq!(ast.id, None);
+        assert_eq!(
+            ast.statements,
+            [
+                Statement::Assignment(Attribute::new("rankdir", "LR")),
+                Statement::Attr(AttrStatement {
+                    target: AttrTarget::Node,
+                    attrs: vec![Attribute::new("shape", "box")],
+                }),
+                Statement::Node(NodeStatement {
+                    node: NodeId::new("a"),
+                    attrs: vec![
+                        Attribute::new("label", "A"),
+                        Attribute::new("color", "red"),
+                        Attribute::new("style", "bold"),
+                    ],
+                }),
+                Statement::Edge(EdgeStatement {
+                    operands: vec![
+                        EdgeOperand::Node(NodeId {
+                            id: "a".into(),
+                            port: Some("out:e".into()),
+                        }),
+                        EdgeOperand::Node(NodeId::new("b")),
+                        EdgeOperand::Subgraph(Subgraph {
+                            id: None,
+                            statements: vec![
+                                Statement::Node(NodeStatement {
+                                    node: NodeId::new("c"),
+                                    attrs: vec![],
+                                }),
+                                Statement::Node(NodeStatement {
+                                    node: NodeId::new("d"),
+                                    attrs: vec![],
+                                }),
+                            ],
+                        }),
+                    ],
+                    ops: vec![EdgeOp::Directed, EdgeOp::Directed],
+                    attrs: vec![Attribute::new("weight", "2")],
+                }),
+                Statement::Subgraph(Subgraph {
+                    id: Some("cluster_x".into()),
+                    statements: vec![Statement::Node(NodeStatement {
+                        node: NodeId::new("e"),
+                        attrs: vec![],
+                    })],
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn attaches_comments_to_nodes() {
+        let ast = parse(
+            "graph {
+            # 1 \"x.gv\"
+            // The entry point.
+            /* Runs
+             * first. */
+            a // really
+            b
+
+            // Detached.
+
+            c
+            // Declared again.
+            a
+        }",
+        )
+        .unwrap();
+        let comments: Vec<(&str, Vec<String>)> = ast
+            .node_comments()
+            .into_iter()
+            .map(|(node, comments)| {
+                let bodies = comments.iter().map(|comment| comment.body()).collect();
+                (node.id.as_ref(), bodies)
+            })
+            .collect();
+
+        assert_eq!(
+            comments,
+            [(
+                "a",
+                vec![
+                    "The entry point.".to_string(),
+                    "Runs\nfirst.".to_string(),
+                    "really".to_string(),
+                    "Declared again.".to_string(),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn borrows_from_the_source() {
+        let source = String::from("graph { a -- \"b\" [label=\"say \\\"hi\\\"\"] }");
+        let ast = parse(&source).unwrap();
+        let Statement::Edge(edge) = &ast.statements[0] else {
+            panic!("expected an edge statement");
+        };
+
+        assert!(matches!(
+            &edge.operands[1],
+            EdgeOperand::Node(NodeId {
+                id: Cow::Borrowed("b"),
+                ..
+            })
+        ));
+        assert!(matches!(&edge.attrs[0].value, Cow::Owned(value) if value == "say \"hi\""));
+
+        let owned = ast.clone().into_owned();
+        drop(source);
+        assert_eq!(owned.statements.len(), 1);
+    }
+
+    #[test]
+    fn reports_unexpected_tokens() {
+        let error = parse("graph {\n  a -- ;\n}").unwrap_err();
+
+        assert_eq!(error.message, "expected an identifier, found ';'");
+        assert_eq!((error.line, error.column), (2, 8));
 
-        assert(matches!(result, Ok()));
+        let trailing = parse("graph {} x").unwrap_err();
+        assert_eq!(trailing.message, "expected end of input, found \"x\"");
     }
 }
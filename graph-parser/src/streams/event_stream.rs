@@ -0,0 +1,290 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::ast::{AttrStatement, Attribute, EdgeStatement, NodeStatement, Statement};
+use crate::cst;
+use crate::parser::{Parser, Step};
+
+use super::read_stream::{ReadErrors, ReadStream};
+use super::statement_stream::{error, start, StreamError};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    GraphStart {
+        strict: bool,
+        directed: bool,
+        id: Option<String>,
+    },
+    NodeStmt(NodeStatement<'static>),
+    /// An edge statement. Subgraphs among its operands come whole, except a
+    /// statement's first one: its statements already came between
+    /// `SubgraphStart` and `SubgraphEnd` right before, so here it only has
+    /// its id.
+    EdgeStmt(EdgeStatement<'static>),
+    AttrStmt(AttrStatement<'static>),
+    /// A bare `key = value`.
+    Assignment(Attribute<'static>),
+    SubgraphStart {
+        id: Option<String>,
+    },
+    SubgraphEnd,
+    GraphEnd,
+}
+
+/// Parses a graph from a reader as a series of events, without building its
+/// AST. Comments are skipped.
+///
+/// Memory is bounded by the largest statement outside subgraph blocks, plus
+/// how deep they nest: each statement is dropped once its event is out, in
+/// subgraphs too, so a whole file wrapped in one subgraph streams as well.
+pub struct EventStream<R: BufRead> {
+    parser: Parser<'static, ReadStream<R>>,
+    errors: ReadErrors,
+    pending: VecDeque<Event>,
+    done: bool,
+}
+
+impl<R: BufRead> EventStream<R> {
+    /// Reads up to and including the `{` that opens the graph.
+    pub fn new(reader: R) -> Result<Self, StreamError> {
+        let (parser, errors, header) = start(reader)?;
+        Ok(Self {
+            parser,
+            errors,
+            pending: VecDeque::from([Event::GraphStart {
+                strict: header.strict,
+                directed: header.directed,
+                id: header.id.map(Cow::into_owned),
+            }]),
+            done: false,
+        })
+    }
+
+    fn advance(&mut self) -> Result<(), StreamError> {
+        let step = self
+            .parser
+            .block_step()
+            .map_err(|diagnostic| error(&self.errors, diagnostic))?;
+        match step {
+            Step::Statement => {
+                let event = self.last_statement();
+                self.pending.push_back(event);
+            }
+            Step::SubgraphStart { id } => self.pending.push_back(Event::SubgraphStart {
+                id: id.map(Cow::into_owned),
+            }),
+            Step::SubgraphEnd { edge } => {
+                self.pending.push_back(Event::SubgraphEnd);
+                if edge {
+                    let event = self.last_statement();
+                    self.pending.push_back(event);
+                }
+            }
+            Step::GraphEnd => {
+                if let Some(error) = self.errors.take() {
+                    return Err(StreamError::Io(error));
+                }
+                self.pending.push_back(Event::GraphEnd);
+                self.done = true;
+            }
+        }
+
+        // Nothing refers back to a finished statement: the edge operator
+        // that could follow a subgraph was already looked for at its `}`.
+        self.parser.clear_children();
+        Ok(())
+    }
+
+    fn last_statement(&self) -> Event {
+        let statement = self
+            .parser
+            .last_node()
+            .and_then(cst::to_statement)
+            .expect("a statement was just parsed");
+        match statement {
+            Statement::Node(statement) => Event::NodeStmt(statement),
+            Statement::Edge(statement) => Event::EdgeStmt(statement),
+            Statement::Attr(statement) => Event::AttrStmt(statement),
+            Statement::Assignment(attribute) => Event::Assignment(attribute),
+            other => unreachable!("{other:?} is not parsed as a single step"),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for EventStream<R> {
+    type Item = Result<Event, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            if let Err(error) = self.advance() {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// Parses a graph from `reader`, passing each event to `on_event` as soon as
+/// it is parsed.
+pub fn parse_events(
+    reader: impl BufRead,
+    mut on_event: impl FnMut(Event),
+) -> Result<(), StreamError> {
+    for event in EventStream::new(reader)? {
+        on_event(event?);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use super::{parse_events, Event, EventStream, StreamError};
+    use crate::ast::{EdgeOperand, Subgraph};
+
+    fn events(source: &str) -> Vec<Event> {
+        EventStream::new(source.as_bytes())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn names(events: &[Event]) -> Vec<&'static str> {
+        events
+            .iter()
+            .map(|event| match event {
+                Event::GraphStart { .. } => "GraphStart",
+                Event::NodeStmt(_) => "NodeStmt",
+                Event::EdgeStmt(_) => "EdgeStmt",
+                Event::AttrStmt(_) => "AttrStmt",
+                Event::Assignment(_) => "Assignment",
+                Event::SubgraphStart { .. } => "SubgraphStart",
+                Event::SubgraphEnd => "SubgraphEnd",
+                Event::GraphEnd => "GraphEnd",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn emits_nested_events() {
+        let events = events(
+            "digraph G {
+                rankdir=LR
+                node [shape=box]
+                subgraph cluster_a { label=A; x; subgraph { y -> z } }
+                // a comment
+                a -> {b c}
+            }",
+        );
+
+        assert_eq!(
+            names(&events),
+            [
+                "GraphStart",
+                "Assignment",
+                "AttrStmt",
+                "SubgraphStart",
+                "Assignment",
+                "NodeStmt",
+                "SubgraphStart",
+                "EdgeStmt",
+                "SubgraphEnd",
+                "SubgraphEnd",
+                "EdgeStmt",
+                "GraphEnd",
+            ]
+        );
+        assert_eq!(
+            events[0],
+            Event::GraphStart {
+                strict: false,
+                directed: true,
+                id: Some("G".to_string()),
+            }
+        );
+        assert_eq!(
+            events[3],
+            Event::SubgraphStart {
+                id: Some("cluster_a".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn follows_subgraphs_with_their_edges() {
+        let events = events("graph { subgraph s { a } -- b; c }");
+
+        assert_eq!(
+            names(&events),
+            [
+                "GraphStart",
+                "SubgraphStart",
+                "NodeStmt",
+                "SubgraphEnd",
+                "EdgeStmt",
+                "NodeStmt",
+                "GraphEnd",
+            ]
+        );
+        let Event::EdgeStmt(edge) = &events[4] else {
+            panic!("expected an edge statement");
+        };
+        assert!(matches!(
+            &edge.operands[0],
+            EdgeOperand::Subgraph(Subgraph { id: Some(id), .. }) if id == "s"
+        ));
+    }
+
+    #[test]
+    fn drops_statements_inside_subgraphs() {
+        let source = "digraph { subgraph cluster_all { a -> b; c; { d } -> e; f } }";
+        let mut stream = EventStream::new(source.as_bytes()).unwrap();
+        let mut events = Vec::new();
+        while let Some(event) = stream.next() {
+            events.push(event.unwrap());
+            assert!(stream.parser.last_node().is_none());
+        }
+
+        assert_eq!(names(&events).len(), 11);
+        let Event::EdgeStmt(edge) = &events[7] else {
+            panic!("expected an edge statement");
+        };
+        assert!(matches!(
+            &edge.operands[0],
+            EdgeOperand::Subgraph(Subgraph { statements, .. }) if statements.is_empty()
+        ));
+    }
+
+    #[test]
+    fn aggregates_without_an_ast() {
+        let mut degrees = BTreeMap::new();
+        let source = "graph { a -- b -- c; a -- c; subgraph { d -- a } }";
+        parse_events(source.as_bytes(), |event| {
+            if let Event::EdgeStmt(edge) = event {
+                for operand in edge.operands {
+                    if let EdgeOperand::Node(node) = operand {
+                        *degrees.entry(node.id.into_owned()).or_insert(0) += 1;
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let expected = [("a", 3), ("b", 1), ("c", 2), ("d", 1)];
+        let expected = expected.map(|(id, count)| (id.to_string(), count));
+        assert_eq!(degrees, BTreeMap::from(expected));
+    }
+
+    #[test]
+    fn stops_at_errors() {
+        let mut stream = EventStream::new("graph { a; b -> }".as_bytes()).unwrap();
+
+        assert!(matches!(stream.next(), Some(Ok(Event::GraphStart { .. }))));
+        assert!(matches!(stream.next(), Some(Ok(Event::NodeStmt(_)))));
+        assert!(matches!(stream.next(), Some(Err(StreamError::Syntax(_)))));
+        assert!(stream.next().is_none());
+    }
+}
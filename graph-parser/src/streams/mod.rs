@@ -1,2 +1,5 @@
-mod string_stream;
-mod token_stream;
+pub mod event_stream;
+pub mod read_stream;
+pub mod statement_stream;
+pub mod string_stream;
+pub mod token_stream;
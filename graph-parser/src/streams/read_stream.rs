@@ -0,0 +1,141 @@
+use std::cell::RefCell;
+use std::io::{self, BufRead};
+use std::rc::Rc;
+
+use super::token_stream::TokenStream;
+
+/// The characters of a UTF-8 reader, decoded straight from its buffer, so
+/// only the reader's own buffer is held in memory. A character split across
+/// two fills of the buffer is put back together.
+///
+/// An iterator cannot return errors, so the stream ends at the first read
+/// error or invalid byte and keeps the error in its `ReadErrors`.
+pub struct ReadStream<R: BufRead> {
+    reader: R,
+    /// Bytes consumed so far.
+    offset: usize,
+    errors: ReadErrors,
+}
+
+/// A handle on the error that ended a `ReadStream`, still usable once the
+/// stream has been handed to a parser.
+#[derive(Clone, Debug, Default)]
+pub struct ReadErrors(Rc<RefCell<Option<io::Error>>>);
+
+impl ReadErrors {
+    pub fn take(&self) -> Option<io::Error> {
+        self.0.borrow_mut().take()
+    }
+}
+
+impl<R: BufRead> ReadStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            errors: ReadErrors::default(),
+        }
+    }
+
+    pub fn errors(&self) -> ReadErrors {
+        self.errors.clone()
+    }
+
+    fn read_byte(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            match self.reader.fill_buf() {
+                Ok(buffer) => {
+                    let Some(&byte) = buffer.first() else {
+                        return Ok(None);
+                    };
+                    self.reader.consume(1);
+                    self.offset += 1;
+                    return Ok(Some(byte));
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn read_char(&mut self) -> io::Result<Option<char>> {
+        let start = self.offset;
+        let invalid = || {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid UTF-8 at byte {start}"),
+            )
+        };
+
+        let Some(first) = self.read_byte()? else {
+            return Ok(None);
+        };
+        let width = match first {
+            0x00..=0x7f => return Ok(Some(first as char)),
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => return Err(invalid()),
+        };
+        let mut bytes = [first, 0, 0, 0];
+        for byte in &mut bytes[1..width] {
+            *byte = self.read_byte()?.ok_or_else(invalid)?;
+        }
+        let decoded = std::str::from_utf8(&bytes[..width]).map_err(|_| invalid())?;
+        Ok(decoded.chars().next())
+    }
+}
+
+impl<R: BufRead> Iterator for ReadStream<R> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read_char() {
+            Ok(c) => c,
+            Err(error) => {
+                *self.errors.0.borrow_mut() = Some(error);
+                None
+            }
+        }
+    }
+}
+
+impl<R: BufRead> TokenStream for ReadStream<R> {
+    fn take_next_word(&mut self) -> Option<String> {
+        let result: String = self.by_ref().take_while(|c| !c.is_whitespace()).collect();
+
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{BufReader, ErrorKind};
+
+    use super::ReadStream;
+
+    #[test]
+    fn decodes_across_buffer_boundaries() {
+        let text = "a€😀ü b";
+        for capacity in 1..=4 {
+            let stream = ReadStream::new(BufReader::with_capacity(capacity, text.as_bytes()));
+            assert_eq!(stream.collect::<String>(), text);
+        }
+    }
+
+    #[test]
+    fn stops_at_invalid_bytes() {
+        let bytes: &[u8] = b"ab\xe2\x82z";
+        let mut stream = ReadStream::new(BufReader::with_capacity(2, bytes));
+        let errors = stream.errors();
+
+        assert_eq!(stream.by_ref().collect::<String>(), "ab");
+        let error = errors.take().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert_eq!(error.to_string(), "invalid UTF-8 at byte 2");
+    }
+}
@@ -0,0 +1,268 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead};
+
+use crate::ast::{GraphAST, Statement};
+use crate::cst::{self, SyntaxKind, SyntaxNode, SyntaxTree};
+use crate::diagnostic::Diagnostic;
+use crate::lexer::Lexer;
+use crate::parser::Parser;
+
+use super::read_stream::{ReadErrors, ReadStream};
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Syntax(Diagnostic),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "{error}"),
+            StreamError::Syntax(diagnostic) => write!(f, "{diagnostic}"),
+        }
+    }
+}
+
+impl Error for StreamError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StreamError::Io(error) => Some(error),
+            StreamError::Syntax(diagnostic) => Some(diagnostic),
+        }
+    }
+}
+
+/// `strict? (graph | digraph) id?`, with the comments before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GraphHeader {
+    pub leading: Vec<Statement<'static>>,
+    pub strict: bool,
+    pub directed: bool,
+    pub id: Option<String>,
+}
+
+/// Parses a graph from a reader one top-level statement at a time, so
+/// memory stays bounded by the largest statement rather than the file.
+/// Subgraphs are top-level statements too and arrive whole.
+///
+/// Yields the statements `parse` would put in `GraphAST::statements`,
+/// followed by those in `GraphAST::trailing`. Stops after the first error.
+pub struct StatementStream<R: BufRead> {
+    parser: Parser<'static, ReadStream<R>>,
+    /// Holds each statement's syntax while it is turned into a `Statement`,
+    /// then goes back to the parser empty.
+    block: SyntaxNode<'static>,
+    errors: ReadErrors,
+    header: GraphHeader,
+    pending: VecDeque<Statement<'static>>,
+    done: bool,
+}
+
+impl<R: BufRead> StatementStream<R> {
+    /// Reads up to and including the `{` that opens the graph.
+    pub fn new(reader: R) -> Result<Self, StreamError> {
+        let (parser, errors, header) = start(reader)?;
+        Ok(Self {
+            parser,
+            block: SyntaxNode {
+                kind: SyntaxKind::Block,
+                children: Vec::new(),
+            },
+            errors,
+            header: GraphHeader {
+                leading: header.leading,
+                strict: header.strict,
+                directed: header.directed,
+                id: header.id.map(Cow::into_owned),
+            },
+            pending: header.statements.into(),
+            done: false,
+        })
+    }
+
+    pub fn header(&self) -> &GraphHeader {
+        &self.header
+    }
+
+    /// Parses the next statement into `pending`, along with the comments and
+    /// blank lines before it.
+    fn advance(&mut self) -> Result<(), StreamError> {
+        let more = self
+            .parser
+            .root_statement()
+            .map_err(|diagnostic| error(&self.errors, diagnostic))?;
+
+        if more {
+            self.parser.swap_children(&mut self.block.children);
+            self.pending.extend(cst::statements(&self.block));
+            self.block.children.clear();
+            self.parser.swap_children(&mut self.block.children);
+        } else {
+            if let Some(error) = self.errors.take() {
+                return Err(StreamError::Io(error));
+            }
+            // The closed block, the comments after it and `Eof`.
+            let GraphAST {
+                statements,
+                trailing,
+                ..
+            } = SyntaxTree {
+                root: self.parser.take_root(),
+            }
+            .to_ast();
+            self.pending.extend(statements.into_iter().chain(trailing));
+            self.done = true;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for StatementStream<R> {
+    type Item = Result<Statement<'static>, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() && !self.done {
+            if let Err(error) = self.advance() {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+type Started<R> = (
+    Parser<'static, ReadStream<R>>,
+    ReadErrors,
+    GraphAST<'static>,
+);
+
+/// Parses the header of the graph in `reader`. The header's `GraphAST` has
+/// the comments before `{` as its statements.
+pub(super) fn start<R: BufRead>(reader: R) -> Result<Started<R>, StreamError> {
+    let source = ReadStream::new(reader);
+    let errors = source.errors();
+    let started = Parser::new(Lexer::new(source)).and_then(|mut parser| {
+        parser.header()?;
+        Ok(parser)
+    });
+    let mut parser = started.map_err(|diagnostic| error(&errors, diagnostic))?;
+
+    // The root holds the header and the trivia around it, which `to_ast`
+    // splits into the leading comments and those opening the body.
+    let header = SyntaxTree {
+        root: parser.take_root(),
+    }
+    .to_ast();
+    Ok((parser, errors, header))
+}
+
+/// A read error explains a syntax error at the point the input was cut short.
+pub(super) fn error(errors: &ReadErrors, diagnostic: Diagnostic) -> StreamError {
+    match errors.take() {
+        Some(error) => StreamError::Io(error),
+        None => StreamError::Syntax(diagnostic),
+    }
+}
+
+/// Parses a graph from `reader`, passing each statement to `on_statement`
+/// as soon as it is complete.
+pub fn parse_reader(
+    reader: impl BufRead,
+    mut on_statement: impl FnMut(Statement<'static>),
+) -> Result<GraphHeader, StreamError> {
+    let mut stream = StatementStream::new(reader)?;
+    for statement in stream.by_ref() {
+        on_statement(statement?);
+    }
+    Ok(stream.header)
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, BufReader, Read};
+
+    use super::{parse_reader, StatementStream, StreamError};
+    use crate::ast::{Comment, Statement};
+    use crate::parser::parse;
+
+    const SOURCE: &str = "// leading
+strict digraph \"größe\" /* opening */ {
+    a -> b [label=\"☃\"];
+    subgraph cluster_x { c; d }
+
+    // between
+    e
+}
+# trailing
+";
+
+    #[test]
+    fn streams_what_parse_returns() {
+        let ast = parse(SOURCE).unwrap();
+        let reader = BufReader::with_capacity(3, SOURCE.as_bytes());
+        let mut statements = Vec::new();
+        let header = parse_reader(reader, |statement| statements.push(statement)).unwrap();
+
+        assert_eq!(header.leading, ast.leading);
+        assert!(header.strict && header.directed);
+        assert_eq!(header.id.as_deref(), Some("größe"));
+        assert_eq!(
+            statements,
+            [ast.statements, ast.trailing].concat(),
+            "statements differ"
+        );
+        assert_eq!(
+            statements.last(),
+            Some(&Statement::Comment(Comment {
+                text: "# trailing".into(),
+                trailing: false,
+            }))
+        );
+    }
+
+    /// Serves its bytes, then fails, like a connection dropping mid-file.
+    struct Truncated<'a>(&'a [u8]);
+
+    impl Read for Truncated<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+            }
+            self.0.read(buffer)
+        }
+    }
+
+    #[test]
+    fn emits_statements_before_the_input_ends() {
+        let reader = BufReader::new(Truncated(b"graph { a -- b; c; d -- "));
+        let mut stream = StatementStream::new(reader).unwrap();
+
+        assert!(matches!(stream.next(), Some(Ok(Statement::Edge(_)))));
+        assert!(matches!(stream.next(), Some(Ok(Statement::Node(_)))));
+        match stream.next() {
+            Some(Err(StreamError::Io(error))) => assert_eq!(error.to_string(), "reset"),
+            other => panic!("expected a read error, got {other:?}"),
+        }
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn reports_syntax_errors() {
+        let reader = BufReader::new("graph {\n  a -- ;\n}".as_bytes());
+        let error = StatementStream::new(reader)
+            .unwrap()
+            .find_map(Result::err)
+            .unwrap();
+
+        assert_eq!(error.to_string(), "2:8: expected an identifier, found ';'");
+        assert!(matches!(
+            StatementStream::new("x".as_bytes()),
+            Err(StreamError::Syntax(_))
+        ));
+    }
+}
@@ -1,51 +1,38 @@
-use std::{iter::Peekable, str::Chars};
-
 use super::token_stream::TokenStream;
 
-pub struct StringStream<'a> {
+pub struct StringStream {
     data: String,
-    chars: Option<Peekable<Chars<'a>>>,
+    /// Byte offset of the next character in `data`.
+    position: usize,
 }
 
-impl<'a> Iterator for StringStream<'a> {
+impl Iterator for StringStream {
     type Item = char;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.chars_iter().next()
+        let c = self.data[self.position..].chars().next()?;
+        self.position += c.len_utf8();
+        Some(c)
     }
 }
 
-impl<'a> From<String> for StringStream<'a> {
+impl From<String> for StringStream {
     fn from(value: String) -> Self {
         Self {
             data: value,
-            chars: None,
+            position: 0,
         }
     }
 }
 
-impl<'a> TokenStream for StringStream<'a> {
+impl TokenStream for StringStream {
     fn take_next_word(&mut self) -> Option<String> {
-        let result: String = self
-            .chars_iter()
-            .take_while(|c| !c.is_whitespace())
-            .collect();
+        let result: String = self.by_ref().take_while(|c| !c.is_whitespace()).collect();
 
-        if result.len() == 0 {
+        if result.is_empty() {
             None
         } else {
             Some(result)
         }
     }
 }
-
-impl<'a> StringStream<'a> {
-    fn chars_iter(&'a mut self) -> &mut Peekable<Chars<'a>> {
-        if let Some(chars_iter) = self.chars.as_mut() {
-            return chars_iter;
-        } else {
-            self.chars = Some(self.data.chars().peekable());
-            self.chars.as_mut().unwrap()
-        }
-    }
-}
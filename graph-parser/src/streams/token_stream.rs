@@ -1,3 +1,5 @@
 pub trait TokenStream: Iterator<Item = char> {
+    /// The next run of non-whitespace characters. Quotes, escapes and `+`
+    /// mean nothing here; `Lexer` splits DOT source into tokens.
     fn take_next_word(&mut self) -> Option<String>;
 }
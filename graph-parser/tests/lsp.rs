@@ -0,0 +1,256 @@
+use std::io::BufReader;
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+use graph_parser::lsp::{read_message, write_message};
+use serde_json::{json, Value};
+
+const URI: &str = "file:///graph.dot";
+
+/// Drives `dot-lsp` over its stdio, the way an editor would.
+struct Client {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+}
+
+impl Client {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_dot-lsp"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to start dot-lsp");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut client = Self {
+            child,
+            stdin,
+            stdout,
+            next_id: 0,
+        };
+        let result = client.request("initialize", json!({ "capabilities": {} }));
+        assert_eq!(result["serverInfo"]["name"], "dot-lsp");
+        client.notify("initialized", json!({}));
+        client
+    }
+
+    fn receive(&mut self) -> Value {
+        let body = read_message(&mut self.stdout)
+            .unwrap()
+            .expect("server hung up");
+        serde_json::from_str(&body).unwrap()
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        let message = json!({ "jsonrpc": "2.0", "method": method, "params": params });
+        write_message(&mut self.stdin, &message).unwrap();
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": self.next_id,
+            "method": method,
+            "params": params,
+        });
+        write_message(&mut self.stdin, &message).unwrap();
+        let response = self.receive();
+        assert_eq!(response["id"], self.next_id);
+        response["result"].clone()
+    }
+
+    /// Opens the document and returns the diagnostics published for it.
+    fn open(&mut self, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({ "textDocument": { "uri": URI, "languageId": "dot", "version": 1, "text": text } }),
+        );
+        let published = self.receive();
+        assert_eq!(published["method"], "textDocument/publishDiagnostics");
+        published["params"]["diagnostics"].clone()
+    }
+
+    fn at(&mut self, method: &str, line: u32, character: u32, extra: Value) -> Value {
+        let mut params = json!({
+            "textDocument": { "uri": URI },
+            "position": { "line": line, "character": character },
+        });
+        if let (Some(params), Some(extra)) = (params.as_object_mut(), extra.as_object()) {
+            params.extend(extra.clone());
+        }
+        self.request(method, params)
+    }
+
+    fn stop(mut self) -> i32 {
+        assert_eq!(self.request("shutdown", Value::Null), Value::Null);
+        self.notify("exit", Value::Null);
+        self.child.wait().unwrap().code().unwrap()
+    }
+}
+
+fn range(line: u32, start: u32, end: u32) -> Value {
+    json!({
+        "start": { "line": line, "character": start },
+        "end": { "line": line, "character": end },
+    })
+}
+
+const SOURCE: &str = "digraph {
+    a -> b;
+    b [shape=box];
+    b [color=red];
+    subgraph cluster_db { label=\"Storage\"; c }
+    a -> c;
+}
+";
+
+#[test]
+fn publishes_diagnostics() {
+    let mut client = Client::start();
+
+    assert_eq!(client.open(SOURCE), json!([]));
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 2 },
+            "contentChanges": [{ "text": "digraph {\n    a -> ;\n}\n" }],
+        }),
+    );
+    let published = client.receive();
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["range"], range(1, 9, 10));
+    assert_eq!(diagnostics[0]["severity"], 1);
+
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": URI, "version": 3 },
+            "contentChanges": [{ "text": "digraph {\n    a [colr=red];\n}\n" }],
+        }),
+    );
+    let published = client.receive();
+    let diagnostics = published["params"]["diagnostics"].as_array().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["range"], range(1, 7, 11));
+    assert_eq!(diagnostics[0]["severity"], 2);
+    assert_eq!(diagnostics[0]["code"], "unknown-attribute");
+
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn navigates_node_ids() {
+    let mut client = Client::start();
+    client.open(SOURCE);
+
+    let definition = client.at("textDocument/definition", 1, 9, json!({}));
+    assert_eq!(definition, json!({ "uri": URI, "range": range(2, 4, 5) }));
+
+    let references = client.at(
+        "textDocument/references",
+        5,
+        4,
+        json!({ "context": { "includeDeclaration": true } }),
+    );
+    let ranges: Vec<&Value> = references
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|location| &location["range"])
+        .collect();
+    assert_eq!(ranges, [&range(1, 4, 5), &range(5, 4, 5)]);
+
+    assert_eq!(
+        client.at("textDocument/definition", 0, 0, json!({})),
+        Value::Null
+    );
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn renames_across_the_file() {
+    let mut client = Client::start();
+    client.open(SOURCE);
+
+    let edit = client.at("textDocument/rename", 2, 4, json!({ "newName": "big b" }));
+    let edits = edit["changes"][URI].as_array().unwrap();
+    assert_eq!(edits.len(), 3);
+    assert!(edits.iter().all(|edit| edit["newText"] == "\"big b\""));
+    assert_eq!(edits[0]["range"], range(1, 9, 10));
+
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn hovers_and_completes() {
+    let mut client = Client::start();
+    client.open(SOURCE);
+
+    let hover = client.at("textDocument/hover", 1, 9, json!({}));
+    assert_eq!(
+        hover["contents"]["value"],
+        "```dot\nb [shape=box, color=red]\n```"
+    );
+
+    let items = client.at("textDocument/completion", 2, 7, json!({}));
+    let labels: Vec<&str> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].as_str().unwrap())
+        .collect();
+    assert!(labels.contains(&"shape") && labels.contains(&"color"));
+
+    let items = client.at("textDocument/completion", 2, 13, json!({}));
+    assert!(items
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|item| item["label"] == "ellipse"));
+
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn lists_symbols_and_formats() {
+    let mut client = Client::start();
+    client.open("graph {\n  subgraph cluster_a { label=A; subgraph inner { x } }\n}\n");
+
+    let symbols = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": URI } }),
+    );
+    assert_eq!(symbols[0]["name"], "cluster_a");
+    assert_eq!(symbols[0]["detail"], "A");
+    assert_eq!(symbols[0]["children"][0]["name"], "inner");
+
+    let edits = client.request(
+        "textDocument/formatting",
+        json!({ "textDocument": { "uri": URI }, "options": { "tabSize": 4, "insertSpaces": true } }),
+    );
+    let formatted = edits[0]["newText"].as_str().unwrap();
+    assert!(formatted.starts_with("graph {\n    subgraph cluster_a {\n        label=A;\n"));
+
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn ignores_responses_from_the_client() {
+    let mut client = Client::start();
+    let response = json!({ "jsonrpc": "2.0", "id": 99, "result": null });
+    write_message(&mut client.stdin, &response).unwrap();
+
+    // The next message is the answer to this request, not an error for the response.
+    assert_eq!(client.stop(), 0);
+}
+
+#[test]
+fn exits_with_failure_without_shutdown() {
+    let mut client = Client::start();
+    client.notify("exit", Value::Null);
+    assert_eq!(client.child.wait().unwrap().code(), Some(1));
+}